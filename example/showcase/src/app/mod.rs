@@ -8,15 +8,14 @@ use rlink::api::data_stream::{
 };
 use rlink::api::env::{StreamApp, StreamExecutionEnvironment};
 use rlink::api::properties::{Properties, SystemProperties};
+use rlink::api::schema::Schema;
+use rlink::api::state::MapStateDescriptor;
 use rlink::api::watermark::BoundedOutOfOrdernessTimestampExtractor;
 use rlink::api::window::SlidingEventTimeWindows;
-use rlink::functions::broadcast_flat_map::BroadcastFlagMapFunction;
-use rlink::functions::round_robin_flat_map::RoundRobinFlagMapFunction;
 use rlink::functions::schema_base::key_selector::SchemaBaseKeySelector;
 use rlink::functions::schema_base::print_output_format::PrintOutputFormat;
 use rlink::functions::schema_base::reduce::{sum_i64, SchemaBaseReduceFunction};
 use rlink::functions::schema_base::timestamp_assigner::SchemaBaseTimestampAssigner;
-use rlink::functions::schema_base::FunctionSchema;
 
 use crate::app::functions::*;
 use crate::buffer_gen::model;
@@ -35,16 +34,10 @@ impl StreamApp for SimpleStreamApp {
         let reduce_function =
             SchemaBaseReduceFunction::new(vec![sum_i64(model::index::value)], &FIELD_TYPE);
 
-        // the schema after reduce
-        let output_schema_types = {
-            let mut key_types = key_selector.get_schema_types();
-            let reduce_types = reduce_function.get_schema_types();
-            key_types.extend_from_slice(reduce_types.as_slice());
-            key_types
-        };
-
-        env.register_source(TestInputFormat::new(properties.clone()), 3)
+        let data_stream = env
+            .register_source(TestInputFormat::new(properties.clone()), 3)
             .flat_map(MyFlatMapFunction::new())
+            .with_schema(model_schema())
             .filter(MyFilterFunction::new())
             .assign_timestamps_and_watermarks(BoundedOutOfOrdernessTimestampExtractor::new(
                 Duration::from_secs(1),
@@ -56,8 +49,11 @@ impl StreamApp for SimpleStreamApp {
                 Duration::from_secs(20),
                 None,
             ))
-            .reduce(reduce_function, 2)
-            .add_sink(PrintOutputFormat::new(output_schema_types.as_slice()));
+            .aggregate(reduce_function, 2);
+
+        // the key followed by the reduced values
+        let output_schema_types = data_stream.get_schema().get_field_types();
+        data_stream.add_sink(PrintOutputFormat::new(output_schema_types.as_slice()));
     }
 }
 
@@ -75,17 +71,10 @@ impl StreamApp for JoinStreamApp {
         let reduce_function =
             SchemaBaseReduceFunction::new(vec![sum_i64(model::index::value)], &FIELD_TYPE);
 
-        // the schema after reduce
-        let output_schema_types = {
-            let mut key_types = key_selector.get_schema_types();
-            let reduce_types = reduce_function.get_schema_types();
-            key_types.extend_from_slice(reduce_types.as_slice());
-            key_types
-        };
-
         let data_stream_left = env
             .register_source(RandInputFormat::new(), 2)
             .flat_map(MyFlatMapFunction::new())
+            .with_schema(model_schema())
             .filter(MyFilterFunction::new())
             .assign_timestamps_and_watermarks(BoundedOutOfOrdernessTimestampExtractor::new(
                 Duration::from_secs(1),
@@ -93,13 +82,13 @@ impl StreamApp for JoinStreamApp {
             ));
         let data_stream_right = env
             .register_source(ConfigInputFormat::new("Broadcast"), 1)
-            .flat_map(BroadcastFlagMapFunction::new());
+            .broadcast(MapStateDescriptor::new("Broadcast"));
 
         let data_stream_right1 = env
             .register_source(ConfigInputFormat::new("RoundRobin"), 1)
-            .flat_map(RoundRobinFlagMapFunction::new());
+            .rebalance();
 
-        data_stream_left
+        let data_stream = data_stream_left
             .connect(
                 vec![
                     CoStream::from(data_stream_right),
@@ -113,7 +102,15 @@ impl StreamApp for JoinStreamApp {
                 Duration::from_secs(60),
                 None,
             ))
-            .reduce(reduce_function, 2)
-            .add_sink(PrintOutputFormat::new(output_schema_types.as_slice()));
+            .aggregate(reduce_function, 2);
+
+        // the key followed by the reduced values
+        let output_schema_types = data_stream.get_schema().get_field_types();
+        data_stream.add_sink(PrintOutputFormat::new(output_schema_types.as_slice()));
     }
 }
+
+/// the schema of `model`, the fields are declared in `build.rs`
+fn model_schema() -> Schema {
+    Schema::from_names(&["timestamp", "name", "value"], &FIELD_TYPE)
+}
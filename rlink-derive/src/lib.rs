@@ -7,6 +7,8 @@ use syn::DeriveInput;
 
 use proc_macro::TokenStream;
 
+mod record_schema;
+
 #[proc_macro_derive(Function)]
 pub fn derive_function(input: TokenStream) -> TokenStream {
     // Parse the input tokens into a syntax tree
@@ -27,6 +29,17 @@ pub fn derive_function(input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+/// Map the struct with named fields to a `Record`, generate the `FIELD_TYPE` array,
+/// the field indexes `<FIELD>_INDEX` and the `rlink::api::element::RecordSchema` impl.
+///
+/// The fields are primitive numbers, `bool`, `String` or `Vec<u8>`,
+/// the `String` and `Vec<u8>` are mapped to `types::BYTES`.
+#[proc_macro_derive(RecordSchema)]
+pub fn derive_record_schema(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    TokenStream::from(record_schema::expand(input))
+}
+
 #[proc_macro_attribute]
 #[cfg(not(test))]
 pub fn main(args: TokenStream, item: TokenStream) -> TokenStream {
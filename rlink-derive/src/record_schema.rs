@@ -0,0 +1,204 @@
+use proc_macro2::{Span, TokenStream};
+use syn::{Data, DeriveInput, Fields, GenericArgument, Ident, PathArguments, Type};
+
+/// the `types::*` constant and the `BufferWriter`/`BufferReader` method suffix of a field
+struct FieldType {
+    type_name: &'static str,
+    accessor: &'static str,
+}
+
+impl FieldType {
+    fn new(type_name: &'static str, accessor: &'static str) -> Self {
+        FieldType {
+            type_name,
+            accessor,
+        }
+    }
+}
+
+fn field_type(ty: &Type) -> Option<FieldType> {
+    let segment = match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => type_path.path.segments.last()?,
+        _ => return None,
+    };
+
+    let field_type = match segment.ident.to_string().as_str() {
+        "bool" => FieldType::new("BOOL", "bool"),
+        "i8" => FieldType::new("I8", "i8"),
+        "u8" => FieldType::new("U8", "u8"),
+        "i16" => FieldType::new("I16", "i16"),
+        "u16" => FieldType::new("U16", "u16"),
+        "i32" => FieldType::new("I32", "i32"),
+        "u32" => FieldType::new("U32", "u32"),
+        "i64" => FieldType::new("I64", "i64"),
+        "u64" => FieldType::new("U64", "u64"),
+        "f32" => FieldType::new("F32", "f32"),
+        "f64" => FieldType::new("F64", "f64"),
+        "String" => FieldType::new("BYTES", "str"),
+        "Vec" => {
+            // only `Vec<u8>` is mapped to the bytes
+            let args = match &segment.arguments {
+                PathArguments::AngleBracketed(args) => args,
+                _ => return None,
+            };
+            match args.args.first() {
+                Some(GenericArgument::Type(Type::Path(arg))) if arg.path.is_ident("u8") => {
+                    FieldType::new("BYTES", "bytes")
+                }
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    Some(field_type)
+}
+
+pub(crate) fn expand(input: DeriveInput) -> TokenStream {
+    let name = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                let msg = "`RecordSchema` only supports the struct with named fields";
+                return syn::Error::new_spanned(&input.ident, msg).to_compile_error();
+            }
+        },
+        _ => {
+            let msg = "`RecordSchema` only supports the struct";
+            return syn::Error::new_spanned(&input.ident, msg).to_compile_error();
+        }
+    };
+    if !input.generics.params.is_empty() {
+        let msg = "`RecordSchema` does not support the generic struct";
+        return syn::Error::new_spanned(&input.generics, msg).to_compile_error();
+    }
+
+    let mut field_types = Vec::new();
+    let mut field_names = Vec::new();
+    let mut index_consts = Vec::new();
+    let mut writes = Vec::new();
+    let mut reads = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let ident = field.ident.as_ref().unwrap();
+        let field_name = ident.to_string().trim_start_matches("r#").to_string();
+        let field_type = match field_type(&field.ty) {
+            Some(field_type) => field_type,
+            None => {
+                let msg = "unsupported field type, expect a primitive number, `bool`, \
+                           `String` or `Vec<u8>`";
+                return syn::Error::new_spanned(&field.ty, msg).to_compile_error();
+            }
+        };
+
+        let type_name = Ident::new(field_type.type_name, Span::call_site());
+        field_types.push(quote! { rlink::api::element::types::#type_name });
+        field_names.push(field_name.clone());
+
+        let index_name = format!("{}_INDEX", field_name.to_uppercase());
+        let index_ident = Ident::new(index_name.as_str(), Span::call_site());
+        index_consts.push(quote! { pub const #index_ident: usize = #index; });
+
+        let setter = Ident::new(&format!("set_{}", field_type.accessor), Span::call_site());
+        let getter = Ident::new(&format!("get_{}", field_type.accessor), Span::call_site());
+        match field_type.accessor {
+            "str" => {
+                writes.push(quote! { writer.#setter(self.#ident.as_str()).unwrap(); });
+                reads.push(quote! { #ident: reader.#getter(#index)? });
+            }
+            "bytes" => {
+                writes.push(quote! { writer.#setter(self.#ident.as_slice()).unwrap(); });
+                reads.push(quote! { #ident: reader.#getter(#index)?.to_vec() });
+            }
+            _ => {
+                writes.push(quote! { writer.#setter(self.#ident).unwrap(); });
+                reads.push(quote! { #ident: reader.#getter(#index)? });
+            }
+        }
+    }
+
+    let field_len = field_types.len();
+    quote! {
+        impl #name {
+            /// the `types::*` of the fields in the declaration order
+            pub const FIELD_TYPE: [u8; #field_len] = [#(#field_types),*];
+
+            #(#index_consts)*
+        }
+
+        impl rlink::api::element::RecordSchema for #name {
+            const FIELD_TYPES: &'static [u8] = &#name::FIELD_TYPE;
+            const FIELD_NAMES: &'static [&'static str] = &[#(#field_names),*];
+
+            fn to_record(&self) -> rlink::api::element::Record {
+                let mut record = rlink::api::element::Record::new();
+                {
+                    let mut writer = record.get_writer(&#name::FIELD_TYPE);
+                    #(#writes)*
+                }
+                record
+            }
+
+            fn from_record(
+                record: &mut rlink::api::element::Record,
+            ) -> std::result::Result<Self, std::io::Error> {
+                let mut reader = record.get_reader(&#name::FIELD_TYPE);
+                Ok(#name {
+                    #(#reads),*
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::DeriveInput;
+
+    use crate::record_schema::expand;
+
+    fn expand_error(input: DeriveInput) -> String {
+        let tokens = expand(input).to_string();
+        assert!(tokens.starts_with("compile_error"), "{}", tokens);
+        tokens
+    }
+
+    #[test]
+    pub fn unsupported_field_type_test() {
+        let tokens = expand_error(parse_quote! {
+            struct Unsupported {
+                id: i64,
+                values: Vec<i64>,
+            }
+        });
+        assert!(tokens.contains("unsupported field type"));
+
+        let tokens = expand_error(parse_quote! {
+            struct Unsupported {
+                name: Option<String>,
+            }
+        });
+        assert!(tokens.contains("unsupported field type"));
+    }
+
+    #[test]
+    pub fn unsupported_struct_test() {
+        let tokens = expand_error(parse_quote! {
+            struct Tuple(i64, String);
+        });
+        assert!(tokens.contains("only supports the struct with named fields"));
+
+        let tokens = expand_error(parse_quote! {
+            enum Enum {
+                A,
+            }
+        });
+        assert!(tokens.contains("only supports the struct"));
+
+        let tokens = expand_error(parse_quote! {
+            struct Generic<T> {
+                value: T,
+            }
+        });
+        assert!(tokens.contains("does not support the generic struct"));
+    }
+}
@@ -0,0 +1,92 @@
+#[macro_use]
+extern crate rlink_derive;
+
+use rlink::api::element::{types, RecordSchema};
+
+#[derive(RecordSchema, Debug, PartialEq)]
+pub struct AllTypes {
+    pub flag: bool,
+    pub a_i8: i8,
+    pub a_u8: u8,
+    pub a_i16: i16,
+    pub a_u16: u16,
+    pub a_i32: i32,
+    pub a_u32: u32,
+    pub a_i64: i64,
+    pub a_u64: u64,
+    pub a_f32: f32,
+    pub a_f64: f64,
+    pub name: String,
+    pub payload: Vec<u8>,
+    pub r#type: i32,
+}
+
+#[test]
+pub fn record_schema_test() {
+    assert_eq!(
+        AllTypes::FIELD_TYPE,
+        [
+            types::BOOL,
+            types::I8,
+            types::U8,
+            types::I16,
+            types::U16,
+            types::I32,
+            types::U32,
+            types::I64,
+            types::U64,
+            types::F32,
+            types::F64,
+            types::BYTES,
+            types::BYTES,
+            types::I32,
+        ]
+    );
+    assert_eq!(AllTypes::FIELD_TYPES, &AllTypes::FIELD_TYPE);
+    assert_eq!(
+        AllTypes::FIELD_NAMES,
+        &[
+            "flag", "a_i8", "a_u8", "a_i16", "a_u16", "a_i32", "a_u32", "a_i64", "a_u64", "a_f32",
+            "a_f64", "name", "payload", "type",
+        ]
+    );
+
+    assert_eq!(AllTypes::FLAG_INDEX, 0);
+    assert_eq!(AllTypes::A_F64_INDEX, 10);
+    assert_eq!(AllTypes::NAME_INDEX, 11);
+    assert_eq!(AllTypes::PAYLOAD_INDEX, 12);
+    // the raw identifier is named without the `r#` prefix
+    assert_eq!(AllTypes::TYPE_INDEX, 13);
+}
+
+#[test]
+pub fn record_schema_round_trip_test() {
+    let value = AllTypes {
+        flag: true,
+        a_i8: -8,
+        a_u8: 8,
+        a_i16: -16,
+        a_u16: 16,
+        a_i32: -32,
+        a_u32: 32,
+        a_i64: -64,
+        a_u64: 64,
+        a_f32: 3.2,
+        a_f64: 6.4,
+        name: "rlink".to_string(),
+        payload: vec![1, 2, 3],
+        r#type: 7,
+    };
+
+    let mut record = value.to_record();
+    assert_eq!(AllTypes::from_record(&mut record).unwrap(), value);
+
+    // the record is readable by the generated field types and indexes
+    let mut reader = record.get_reader(&AllTypes::FIELD_TYPE);
+    assert_eq!(reader.get_i64(AllTypes::A_I64_INDEX).unwrap(), -64);
+    assert_eq!(reader.get_str(AllTypes::NAME_INDEX).unwrap(), "rlink");
+    assert_eq!(
+        reader.get_bytes(AllTypes::PAYLOAD_INDEX).unwrap().to_vec(),
+        vec![1, 2, 3]
+    );
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use rlink::api::data_stream::{DataStream, TDataStream};
+use rlink::api::env::StreamExecutionEnvironment;
+use rlink::api::function::InputFormat;
+use rlink::api::schema::Schema;
+
+/// A source table of the queries
+pub struct Table {
+    name: String,
+    schema: Schema,
+    parallelism: u16,
+    source: Box<dyn Fn(&mut StreamExecutionEnvironment) -> DataStream>,
+}
+
+impl Table {
+    pub fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn get_schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    pub fn get_parallelism(&self) -> u16 {
+        self.parallelism
+    }
+
+    /// register the source of the table to the `env`, the stream is typed by the table schema
+    pub(crate) fn register_source(&self, env: &mut StreamExecutionEnvironment) -> DataStream {
+        (self.source)(env)
+    }
+}
+
+/// The registered tables, the table names are case sensitive
+#[derive(Default)]
+pub struct Catalog {
+    tables: HashMap<String, Table>,
+}
+
+impl Catalog {
+    pub fn new() -> Self {
+        Catalog {
+            tables: HashMap::new(),
+        }
+    }
+
+    /// Register the table `name` read by the `InputFormat` created by `input_format_fn`,
+    /// a new `InputFormat` is created each time the table is queried.
+    /// The table registered with the same name is replaced.
+    pub fn register_table<I, F>(
+        &mut self,
+        name: &str,
+        schema: Schema,
+        parallelism: u16,
+        input_format_fn: F,
+    ) where
+        I: InputFormat + 'static,
+        F: Fn() -> I + 'static,
+    {
+        let source_schema = schema.clone();
+        let source = move |env: &mut StreamExecutionEnvironment| {
+            env.register_source(input_format_fn(), parallelism)
+                .with_schema(source_schema.clone())
+        };
+
+        let table = Table {
+            name: name.to_string(),
+            schema,
+            parallelism,
+            source: Box::new(source),
+        };
+        self.tables.insert(name.to_string(), table);
+    }
+
+    pub fn get_table(&self, name: &str) -> Option<&Table> {
+        self.tables.get(name)
+    }
+}
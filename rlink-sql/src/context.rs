@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use rlink::api::data_stream::{DataStream, TDataStream, TKeyedStream, TWindowedStream};
+use rlink::api::env::StreamExecutionEnvironment;
+use rlink::api::function::InputFormat;
+use rlink::api::schema::Schema;
+use rlink::api::watermark::BoundedOutOfOrdernessTimestampExtractor;
+use rlink::api::window::{SlidingEventTimeWindows, TumblingEventTimeWindows};
+use rlink::functions::schema_base::key_selector::SchemaBaseKeySelector;
+use rlink::functions::schema_base::project::SchemaBaseProjectFunction;
+use rlink::functions::schema_base::reduce::SchemaBaseReduceFunction;
+use rlink::functions::schema_base::timestamp_assigner::SchemaBaseTimestampAssigner;
+
+use crate::catalog::Catalog;
+use crate::error::SqlError;
+use crate::plan::{plan, WindowAggregatePlan, WindowSpec};
+
+/// Register the source tables and compile the queries to the streams of the `env`, eg:
+///
+/// ```ignore
+/// let mut sql_context = SqlContext::new();
+/// sql_context.register_table("kafka_src", schema, 3, move || create_input_format());
+/// let data_stream = sql_context.sql(
+///     env,
+///     "SELECT name, SUM(value) FROM kafka_src GROUP BY name, TUMBLE(ts, INTERVAL '1' MINUTE)",
+/// )?;
+/// data_stream.add_sink(PrintOutputFormat::new(...));
+/// ```
+pub struct SqlContext {
+    catalog: Catalog,
+    max_out_of_orderness: Duration,
+}
+
+impl SqlContext {
+    pub fn new() -> Self {
+        SqlContext {
+            catalog: Catalog::new(),
+            max_out_of_orderness: Duration::from_secs(1),
+        }
+    }
+
+    /// the watermark lags behind the max event time by `max_out_of_orderness`, 1s by default
+    pub fn set_max_out_of_orderness(&mut self, max_out_of_orderness: Duration) {
+        self.max_out_of_orderness = max_out_of_orderness;
+    }
+
+    /// see `Catalog::register_table`
+    pub fn register_table<I, F>(
+        &mut self,
+        name: &str,
+        schema: Schema,
+        parallelism: u16,
+        input_format_fn: F,
+    ) where
+        I: InputFormat + 'static,
+        F: Fn() -> I + 'static,
+    {
+        self.catalog
+            .register_table(name, schema, parallelism, input_format_fn);
+    }
+
+    pub fn get_catalog(&self) -> &Catalog {
+        &self.catalog
+    }
+
+    /// Compile the `sql` to a stream of the `env`, the schema of the stream is the
+    /// `SELECT` list. The aggregation runs with the parallelism of the source table.
+    pub fn sql(
+        &self,
+        env: &mut StreamExecutionEnvironment,
+        sql: &str,
+    ) -> Result<DataStream, SqlError> {
+        let plan = plan(sql, &self.catalog)?;
+        Ok(self.build(env, plan))
+    }
+
+    fn build(
+        &self,
+        env: &mut StreamExecutionEnvironment,
+        mut plan: WindowAggregatePlan,
+    ) -> DataStream {
+        let table = self.catalog.get_table(plan.get_table_name()).unwrap();
+        let field_types = table.get_schema().get_field_types();
+
+        let mut data_stream = table.register_source(env);
+        if let Some(filter) = plan.filter.take() {
+            data_stream = data_stream.filter(filter);
+        }
+
+        let key_selector = SchemaBaseKeySelector::new(plan.key_columns.clone(), &field_types);
+        let keyed_stream = data_stream
+            .assign_timestamps_and_watermarks(BoundedOutOfOrdernessTimestampExtractor::new(
+                self.max_out_of_orderness,
+                SchemaBaseTimestampAssigner::new(plan.time_column, &field_types),
+            ))
+            .key_by(key_selector);
+
+        let windowed_stream = match plan.window {
+            WindowSpec::Tumble { size } => {
+                keyed_stream.window(TumblingEventTimeWindows::new(size, None))
+            }
+            WindowSpec::Hop { slide, size } => {
+                keyed_stream.window(SlidingEventTimeWindows::new(size, slide, None))
+            }
+        };
+
+        let aggregations = plan.aggregates.iter().map(|x| x.to_aggregation()).collect();
+        let reduce_function = SchemaBaseReduceFunction::new(aggregations, &field_types);
+        let data_stream = windowed_stream.aggregate(reduce_function, table.get_parallelism());
+
+        if plan.is_identity_projection() {
+            return data_stream.with_schema(plan.output_schema);
+        }
+
+        // reorder and rename the columns by the `SELECT` list, eg: "$1 AS `total`"
+        let columns: Vec<String> = plan
+            .projection
+            .iter()
+            .zip(plan.output_schema.get_fields())
+            .map(|(column, field)| {
+                format!("${} AS `{}`", column, field.get_name().replace('`', "``"))
+            })
+            .collect();
+        let columns: Vec<&str> = columns.iter().map(|x| x.as_str()).collect();
+        let project_function =
+            SchemaBaseProjectFunction::new(columns.as_slice(), &plan.aggregate_schema).unwrap();
+
+        data_stream
+            .with_schema(plan.aggregate_schema)
+            .flat_map(project_function)
+    }
+}
+
+impl Default for SqlContext {
+    fn default() -> Self {
+        SqlContext::new()
+    }
+}
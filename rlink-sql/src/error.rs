@@ -0,0 +1,19 @@
+use rlink::functions::schema_base::expression::ExpressionError;
+use sqlparser::parser::ParserError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SqlError {
+    #[error("sql parse error. {0}")]
+    ParseError(#[from] ParserError),
+    #[error("unsupported sql. {0}")]
+    Unsupported(String),
+    #[error("table not found. {0}")]
+    TableNotFound(String),
+    #[error("column not found. {0}")]
+    ColumnNotFound(String),
+    #[error("invalid query. {0}")]
+    InvalidQuery(String),
+    #[error("expression error. {0}")]
+    ExpressionError(#[from] ExpressionError),
+}
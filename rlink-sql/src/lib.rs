@@ -0,0 +1,23 @@
+//! A streaming SQL front-end of rlink, compiles a windowed aggregation query, eg:
+//!
+//! ```sql
+//! SELECT name, SUM(value) AS total
+//! FROM kafka_src
+//! WHERE value > 0
+//! GROUP BY name, TUMBLE(ts, INTERVAL '1' MINUTE)
+//! ```
+//!
+//! to the pipeline `register_source` -> `filter` -> `assign_timestamps_and_watermarks`
+//! -> `key_by` -> `window` -> `aggregate` built by the `schema_base` functions.
+//!
+//! The tables are registered to the `SqlContext` with their schemas.
+//! The `WHERE` condition is evaluated by the rlink expression language,
+//! see `rlink::functions::schema_base::expression`.
+
+pub mod catalog;
+pub mod context;
+pub mod error;
+pub mod plan;
+
+pub use context::SqlContext;
+pub use error::SqlError;
@@ -0,0 +1,689 @@
+use std::time::Duration;
+
+use rlink::api::element::types;
+use rlink::api::schema::{Field, Schema};
+use rlink::functions::schema_base::expression::ast;
+use rlink::functions::schema_base::expression::{Expression, ExpressionError, Value};
+use rlink::functions::schema_base::filter::SchemaBaseFilterFunction;
+use rlink::functions::schema_base::reduce::{
+    max_f64, max_i64, min_f64, min_i64, sum_f64, sum_i64, Aggregation,
+};
+use sqlparser::ast::{
+    BinaryOperator, DataType, Expr, Function, FunctionArg, Query, Select, SelectItem, SetExpr,
+    Statement, TableFactor, UnaryOperator, Value as SqlValue,
+};
+use sqlparser::dialect::GenericDialect;
+use sqlparser::parser::Parser;
+
+use crate::catalog::{Catalog, Table};
+use crate::error::SqlError;
+
+/// The event time window of the `GROUP BY`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindowSpec {
+    /// `TUMBLE(time_column, size)`
+    Tumble { size: Duration },
+    /// `HOP(time_column, slide, size)`
+    Hop { slide: Duration, size: Duration },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AggregateKind {
+    Sum,
+    Min,
+    Max,
+}
+
+/// An aggregate function of the `SELECT` list over a column of the table
+#[derive(Clone, Debug, PartialEq)]
+pub struct AggregateCall {
+    kind: AggregateKind,
+    column: usize,
+    data_type: u8,
+}
+
+impl AggregateCall {
+    pub fn get_kind(&self) -> AggregateKind {
+        self.kind
+    }
+
+    pub fn get_column(&self) -> usize {
+        self.column
+    }
+
+    pub(crate) fn to_aggregation(&self) -> Box<dyn Aggregation> {
+        match (self.kind, self.data_type) {
+            (AggregateKind::Sum, types::I64) => sum_i64(self.column),
+            (AggregateKind::Sum, _) => sum_f64(self.column),
+            (AggregateKind::Min, types::I64) => min_i64(self.column),
+            (AggregateKind::Min, _) => min_f64(self.column),
+            (AggregateKind::Max, types::I64) => max_i64(self.column),
+            (AggregateKind::Max, _) => max_f64(self.column),
+        }
+    }
+}
+
+/// The plan of a windowed aggregation query, eg:
+/// `SELECT name, SUM(value) FROM t GROUP BY name, TUMBLE(ts, INTERVAL '1' MINUTE)`
+#[derive(Debug)]
+pub struct WindowAggregatePlan {
+    pub(crate) table_name: String,
+    pub(crate) filter: Option<SchemaBaseFilterFunction>,
+    pub(crate) time_column: usize,
+    pub(crate) window: WindowSpec,
+    pub(crate) key_columns: Vec<usize>,
+    pub(crate) aggregates: Vec<AggregateCall>,
+    /// the key columns followed by the aggregates, the output of `SchemaBaseReduceFunction`
+    pub(crate) aggregate_schema: Schema,
+    /// the columns of the `aggregate_schema` in the order of the `SELECT` list
+    pub(crate) projection: Vec<usize>,
+    pub(crate) output_schema: Schema,
+}
+
+impl WindowAggregatePlan {
+    pub fn get_table_name(&self) -> &str {
+        self.table_name.as_str()
+    }
+
+    pub fn get_time_column(&self) -> usize {
+        self.time_column
+    }
+
+    pub fn get_window(&self) -> WindowSpec {
+        self.window
+    }
+
+    pub fn get_key_columns(&self) -> &[usize] {
+        self.key_columns.as_slice()
+    }
+
+    pub fn get_aggregates(&self) -> &[AggregateCall] {
+        self.aggregates.as_slice()
+    }
+
+    /// the schema of the query result, the fields are named by the aliases of the `SELECT` list
+    pub fn get_output_schema(&self) -> &Schema {
+        &self.output_schema
+    }
+
+    /// the `SELECT` list is the key columns followed by the aggregates
+    pub(crate) fn is_identity_projection(&self) -> bool {
+        self.projection.len() == self.aggregate_schema.len()
+            && self
+                .projection
+                .iter()
+                .enumerate()
+                .all(|(index, column)| index == *column)
+    }
+}
+
+/// Plan the `sql` with the tables of the `catalog`
+pub fn plan(sql: &str, catalog: &Catalog) -> Result<WindowAggregatePlan, SqlError> {
+    let dialect = GenericDialect {};
+    let mut statements = Parser::parse_sql(&dialect, sql)?;
+    if statements.len() != 1 {
+        return Err(SqlError::Unsupported(
+            "only one statement is supported".to_string(),
+        ));
+    }
+
+    match statements.remove(0) {
+        Statement::Query(query) => plan_query(*query, catalog),
+        statement => Err(SqlError::Unsupported(statement.to_string())),
+    }
+}
+
+fn plan_query(query: Query, catalog: &Catalog) -> Result<WindowAggregatePlan, SqlError> {
+    if !query.order_by.is_empty() || query.limit.is_some() {
+        return Err(SqlError::Unsupported(
+            "ORDER BY and LIMIT on a stream".to_string(),
+        ));
+    }
+
+    let select = match query.body {
+        SetExpr::Select(select) => select,
+        body => return Err(SqlError::Unsupported(body.to_string())),
+    };
+    if select.distinct || select.having.is_some() {
+        return Err(SqlError::Unsupported("DISTINCT and HAVING".to_string()));
+    }
+
+    let table = resolve_table(&select, catalog)?;
+    let schema = table.get_schema();
+
+    let filter = match &select.selection {
+        Some(selection) => {
+            let condition = Expression::from_expr(expr_of(selection, schema)?, schema);
+            Some(SchemaBaseFilterFunction::from_expression(
+                condition, schema,
+            )?)
+        }
+        None => None,
+    };
+
+    let mut key_columns = Vec::new();
+    let mut window = None;
+    for expr in &select.group_by {
+        match expr {
+            Expr::Identifier(ident) => key_columns.push(column_index(schema, &ident.value)?),
+            Expr::Function(function) if window.is_none() => {
+                window = Some(window_of(function, schema)?)
+            }
+            Expr::Function(_function) => {
+                return Err(SqlError::InvalidQuery(
+                    "only one window is allowed in GROUP BY".to_string(),
+                ))
+            }
+            expr => return Err(SqlError::Unsupported(format!("GROUP BY {}", expr))),
+        }
+    }
+    let (time_column, window) = window.ok_or_else(|| {
+        SqlError::InvalidQuery(
+            "a window is required in GROUP BY, eg: TUMBLE(ts, INTERVAL '1' MINUTE)".to_string(),
+        )
+    })?;
+
+    // the columns of the aggregated records with the output names, by the `SELECT` list
+    let mut select_columns: Vec<(usize, String)> = Vec::new();
+    let mut aggregates = Vec::new();
+    let mut aggregate_names = Vec::new();
+    for (index, item) in select.projection.iter().enumerate() {
+        let (expr, alias) = match item {
+            SelectItem::UnnamedExpr(expr) => (expr, None),
+            SelectItem::ExprWithAlias { expr, alias } => (expr, Some(alias.value.clone())),
+            item => return Err(SqlError::Unsupported(format!("SELECT {}", item))),
+        };
+
+        match expr {
+            Expr::Identifier(ident) => {
+                let column = column_index(schema, &ident.value)?;
+                let key_index = key_columns
+                    .iter()
+                    .position(|key_column| *key_column == column)
+                    .ok_or_else(|| {
+                        SqlError::InvalidQuery(format!(
+                            "column {} must appear in GROUP BY",
+                            ident.value
+                        ))
+                    })?;
+                select_columns.push((key_index, alias.unwrap_or_else(|| ident.value.clone())));
+            }
+            Expr::Function(function) => {
+                let name = alias.unwrap_or_else(|| format!("f{}", index));
+                aggregates.push(aggregate_of(function, schema)?);
+                aggregate_names.push(name.clone());
+                select_columns.push((key_columns.len() + aggregates.len() - 1, name));
+            }
+            expr => return Err(SqlError::Unsupported(format!("SELECT {}", expr))),
+        }
+    }
+    if aggregates.is_empty() {
+        return Err(SqlError::InvalidQuery(
+            "at least one aggregate function is required".to_string(),
+        ));
+    }
+
+    let mut aggregate_fields: Vec<Field> = key_columns
+        .iter()
+        .map(|column| schema.get_fields()[*column].clone())
+        .collect();
+    for (aggregate, name) in aggregates.iter().zip(aggregate_names.iter()) {
+        aggregate_fields.push(Field::new(name.as_str(), aggregate.data_type, false));
+    }
+    let aggregate_schema = Schema::new(aggregate_fields);
+
+    let projection: Vec<usize> = select_columns.iter().map(|(column, _)| *column).collect();
+    let output_fields = select_columns
+        .iter()
+        .map(|(column, name)| {
+            let field = &aggregate_schema.get_fields()[*column];
+            Field::new(name.as_str(), field.get_data_type(), field.is_nullable())
+        })
+        .collect();
+
+    Ok(WindowAggregatePlan {
+        table_name: table.get_name().to_string(),
+        filter,
+        time_column,
+        window,
+        key_columns,
+        aggregates,
+        aggregate_schema,
+        projection,
+        output_schema: Schema::new(output_fields),
+    })
+}
+
+fn resolve_table<'a>(select: &Select, catalog: &'a Catalog) -> Result<&'a Table, SqlError> {
+    let table_with_joins = match select.from.as_slice() {
+        [table_with_joins] if table_with_joins.joins.is_empty() => table_with_joins,
+        _ => {
+            return Err(SqlError::Unsupported(
+                "only one table without joins is supported in FROM".to_string(),
+            ))
+        }
+    };
+
+    match &table_with_joins.relation {
+        TableFactor::Table { name, .. } => {
+            let names: Vec<&str> = name.0.iter().map(|ident| ident.value.as_str()).collect();
+            let name = names.join(".");
+            catalog
+                .get_table(name.as_str())
+                .ok_or(SqlError::TableNotFound(name))
+        }
+        relation => Err(SqlError::Unsupported(format!("FROM {}", relation))),
+    }
+}
+
+/// `TUMBLE(time_column, size)` or `HOP(time_column, slide, size)`,
+/// the time column is the event timestamp in milliseconds
+fn window_of(function: &Function, schema: &Schema) -> Result<(usize, WindowSpec), SqlError> {
+    let args: Vec<String> = function.args.iter().map(|arg| arg.to_string()).collect();
+    let (time_column, window) = match (function_name(function).as_str(), args.as_slice()) {
+        ("TUMBLE", [time_column, size]) => (
+            time_column,
+            WindowSpec::Tumble {
+                size: parse_interval(size)?,
+            },
+        ),
+        ("HOP", [time_column, slide, size]) => (
+            time_column,
+            WindowSpec::Hop {
+                slide: parse_interval(slide)?,
+                size: parse_interval(size)?,
+            },
+        ),
+        _ => return Err(SqlError::Unsupported(format!("GROUP BY {}", function))),
+    };
+
+    let time_column = column_index(schema, unquote(time_column))?;
+    if schema.get_fields()[time_column].get_data_type() != types::U64 {
+        return Err(SqlError::InvalidQuery(format!(
+            "the time column {} must be U64",
+            unquote(args[0].as_str())
+        )));
+    }
+
+    Ok((time_column, window))
+}
+
+fn aggregate_of(function: &Function, schema: &Schema) -> Result<AggregateCall, SqlError> {
+    let kind = match function_name(function).as_str() {
+        "SUM" if !function.distinct => AggregateKind::Sum,
+        "MIN" if !function.distinct => AggregateKind::Min,
+        "MAX" if !function.distinct => AggregateKind::Max,
+        _ => {
+            return Err(SqlError::Unsupported(format!(
+                "aggregate function {}",
+                function
+            )))
+        }
+    };
+
+    let args: Vec<String> = function.args.iter().map(|arg| arg.to_string()).collect();
+    let column = match args.as_slice() {
+        [column] => column_index(schema, unquote(column))?,
+        _ => {
+            return Err(SqlError::InvalidQuery(format!(
+                "{} requires one column",
+                function
+            )))
+        }
+    };
+
+    let data_type = schema.get_fields()[column].get_data_type();
+    if data_type != types::I64 && data_type != types::F64 {
+        return Err(SqlError::InvalidQuery(format!(
+            "{} requires an I64 or F64 column",
+            function
+        )));
+    }
+
+    Ok(AggregateCall {
+        kind,
+        column,
+        data_type,
+    })
+}
+
+/// translate the `WHERE` condition to the rlink expression over the columns of the `schema`
+fn expr_of(expr: &Expr, schema: &Schema) -> Result<ast::Expr, SqlError> {
+    let unsupported = || SqlError::Unsupported(format!("WHERE {}", expr));
+
+    let rlink_expr = match expr {
+        Expr::Identifier(ident) => ast::Expr::column(schema, column_index(schema, &ident.value)?)?,
+        Expr::Value(value) => ast::Expr::Literal(value_of(value).ok_or_else(unsupported)?),
+        Expr::Nested(expr) => expr_of(expr, schema)?,
+        Expr::UnaryOp { op, expr } => {
+            let expr = expr_of(expr, schema)?;
+            match op {
+                // fold the negative literals, eg: `-1`
+                UnaryOperator::Minus => match expr {
+                    ast::Expr::Literal(Value::Int(i)) => {
+                        ast::Expr::Literal(Value::Int(i.wrapping_neg()))
+                    }
+                    ast::Expr::Literal(Value::Float(f)) => ast::Expr::Literal(Value::Float(-f)),
+                    expr => ast::Expr::unary(ast::UnaryOp::Neg, expr)?,
+                },
+                UnaryOperator::Not => ast::Expr::unary(ast::UnaryOp::Not, expr)?,
+                UnaryOperator::Plus => expr,
+                _ => return Err(unsupported()),
+            }
+        }
+        Expr::BinaryOp { left, op, right } => {
+            let op = match op {
+                BinaryOperator::Plus => ast::BinaryOp::Add,
+                BinaryOperator::Minus => ast::BinaryOp::Sub,
+                BinaryOperator::Multiply => ast::BinaryOp::Mul,
+                BinaryOperator::Divide => ast::BinaryOp::Div,
+                BinaryOperator::Modulus => ast::BinaryOp::Mod,
+                BinaryOperator::Eq => ast::BinaryOp::Eq,
+                BinaryOperator::NotEq => ast::BinaryOp::NotEq,
+                BinaryOperator::Lt => ast::BinaryOp::Lt,
+                BinaryOperator::LtEq => ast::BinaryOp::LtEq,
+                BinaryOperator::Gt => ast::BinaryOp::Gt,
+                BinaryOperator::GtEq => ast::BinaryOp::GtEq,
+                BinaryOperator::And => ast::BinaryOp::And,
+                BinaryOperator::Or => ast::BinaryOp::Or,
+                _ => return Err(unsupported()),
+            };
+            ast::Expr::binary(op, expr_of(left, schema)?, expr_of(right, schema)?)?
+        }
+        Expr::Cast { expr, data_type } => ast::Expr::Cast {
+            expr: Box::new(expr_of(expr, schema)?),
+            data_type: data_type_of(data_type).ok_or_else(unsupported)?,
+        },
+        Expr::Function(function) if !function.distinct && function.over.is_none() => {
+            let name = function.name.to_string();
+            let scalar_function = ast::ScalarFunction::from_name(name.as_str())
+                .ok_or(ExpressionError::UnknownFunction(name))?;
+            let mut args = Vec::with_capacity(function.args.len());
+            for arg in &function.args {
+                match arg {
+                    FunctionArg::Unnamed(arg) => args.push(expr_of(arg, schema)?),
+                    FunctionArg::Named { .. } => return Err(unsupported()),
+                }
+            }
+            ast::Expr::function(scalar_function, args)?
+        }
+        _ => return Err(unsupported()),
+    };
+    Ok(rlink_expr)
+}
+
+/// the integers are `I64` and the decimals are `F64`, the `NULL` is not supported
+fn value_of(value: &SqlValue) -> Option<Value> {
+    match value {
+        SqlValue::Number(n) => n
+            .parse::<i64>()
+            .map(Value::Int)
+            .or_else(|_e| n.parse::<f64>().map(Value::Float))
+            .ok(),
+        SqlValue::SingleQuotedString(s) => Some(Value::Str(s.clone())),
+        SqlValue::Boolean(b) => Some(Value::Bool(*b)),
+        _ => None,
+    }
+}
+
+/// the `types::*` of the SQL type in `CAST`
+fn data_type_of(data_type: &DataType) -> Option<u8> {
+    match data_type {
+        DataType::Boolean => Some(types::BOOL),
+        DataType::SmallInt => Some(types::I16),
+        DataType::Int => Some(types::I32),
+        DataType::BigInt => Some(types::I64),
+        DataType::Real => Some(types::F32),
+        DataType::Float(_) | DataType::Double => Some(types::F64),
+        DataType::Char(_) | DataType::Varchar(_) | DataType::Text => Some(types::BYTES),
+        _ => None,
+    }
+}
+
+fn function_name(function: &Function) -> String {
+    function.name.to_string().to_uppercase()
+}
+
+fn column_index(schema: &Schema, name: &str) -> Result<usize, SqlError> {
+    schema
+        .index_of(name)
+        .ok_or_else(|| SqlError::ColumnNotFound(name.to_string()))
+}
+
+/// the identifier without the quotes, eg: `"name"`
+fn unquote(name: &str) -> &str {
+    name.trim_matches(|c| c == '"' || c == '`')
+}
+
+/// the interval literal of the seconds, minutes, hours or days, eg: `INTERVAL '1' MINUTE`
+fn parse_interval(text: &str) -> Result<Duration, SqlError> {
+    let invalid = || SqlError::InvalidQuery(format!("invalid interval {}", text));
+
+    let parts: Vec<&str> = text.split_whitespace().collect();
+    let (value, unit) = match parts.as_slice() {
+        [interval, value, unit] if interval.eq_ignore_ascii_case("INTERVAL") => (value, unit),
+        _ => return Err(invalid()),
+    };
+
+    let value: u64 = value.trim_matches('\'').parse().map_err(|_e| invalid())?;
+    let unit_secs = match unit.to_uppercase().as_str() {
+        "SECOND" => 1,
+        "MINUTE" => 60,
+        "HOUR" => 60 * 60,
+        "DAY" => 24 * 60 * 60,
+        _ => return Err(invalid()),
+    };
+
+    if value == 0 {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs(value * unit_secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rlink::api::element::{types, Record};
+    use rlink::api::function::{
+        Context, FilterFunction, Function, InputFormat, InputSplit, InputSplitSource,
+    };
+    use rlink::api::schema::Schema;
+
+    use crate::catalog::Catalog;
+    use crate::error::SqlError;
+    use crate::plan::{plan, AggregateKind, WindowSpec};
+
+    fn catalog() -> Catalog {
+        let mut catalog = Catalog::new();
+        catalog.register_table(
+            "kafka_src",
+            Schema::from_names(
+                &["name", "value", "price", "ts"],
+                &[types::BYTES, types::I64, types::F64, types::U64],
+            ),
+            2,
+            MyInputFormat::new,
+        );
+        catalog
+    }
+
+    #[test]
+    pub fn plan_window_aggregate_test() {
+        let catalog = catalog();
+        let tumble_plan = plan(
+            "SELECT name, SUM(value) FROM kafka_src \
+             GROUP BY name, TUMBLE(ts, INTERVAL '1' MINUTE)",
+            &catalog,
+        )
+        .unwrap();
+        assert_eq!(tumble_plan.get_table_name(), "kafka_src");
+        assert!(tumble_plan.filter.is_none());
+        assert_eq!(tumble_plan.get_time_column(), 3);
+        assert_eq!(
+            tumble_plan.get_window(),
+            WindowSpec::Tumble {
+                size: Duration::from_secs(60)
+            }
+        );
+        assert_eq!(tumble_plan.get_key_columns(), &[0]);
+        assert_eq!(
+            tumble_plan.get_aggregates()[0].get_kind(),
+            AggregateKind::Sum
+        );
+        assert!(tumble_plan.is_identity_projection());
+        assert_eq!(
+            tumble_plan.get_output_schema().to_string(),
+            "(name BYTES NOT NULL, f1 I64 NOT NULL)"
+        );
+
+        let hop_plan = plan(
+            "SELECT MAX(price) AS max_price, name AS n FROM kafka_src WHERE value > 0 \
+             GROUP BY name, HOP(ts, INTERVAL '10' SECOND, INTERVAL '1' HOUR)",
+            &catalog,
+        )
+        .unwrap();
+        assert!(hop_plan.filter.is_some());
+        assert_eq!(
+            hop_plan.get_window(),
+            WindowSpec::Hop {
+                slide: Duration::from_secs(10),
+                size: Duration::from_secs(3600)
+            }
+        );
+        assert!(!hop_plan.is_identity_projection());
+        assert_eq!(hop_plan.projection, vec![1, 0]);
+        assert_eq!(
+            hop_plan.get_output_schema().to_string(),
+            "(max_price F64 NOT NULL, n BYTES NOT NULL)"
+        );
+    }
+
+    #[test]
+    pub fn plan_error_test() {
+        let catalog = catalog();
+        let errors = vec![
+            "SELECT name, SUM(value) FROM unknown GROUP BY name, TUMBLE(ts, INTERVAL '1' MINUTE)",
+            "SELECT name, SUM(value) FROM kafka_src GROUP BY name",
+            "SELECT name, price, SUM(value) FROM kafka_src \
+             GROUP BY name, TUMBLE(ts, INTERVAL '1' MINUTE)",
+            "SELECT name, SUM(name) FROM kafka_src GROUP BY name, TUMBLE(ts, INTERVAL '1' MINUTE)",
+            "SELECT name, SUM(value) FROM kafka_src \
+             GROUP BY name, TUMBLE(value, INTERVAL '1' MINUTE)",
+        ];
+        for sql in errors {
+            assert!(plan(sql, &catalog).is_err(), "{}", sql);
+        }
+
+        match plan(
+            "SELECT name, SUM(value) FROM kafka_src WHERE name > 1 \
+             GROUP BY name, TUMBLE(ts, INTERVAL '1' MINUTE)",
+            &catalog,
+        ) {
+            Err(SqlError::ExpressionError(_)) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    fn record(name: &str, value: i64, price: f64) -> Record {
+        let mut record = Record::new();
+        let mut writer = record.get_writer(&[types::BYTES, types::I64, types::F64, types::U64]);
+        writer.set_str(name).unwrap();
+        writer.set_i64(value).unwrap();
+        writer.set_f64(price).unwrap();
+        writer.set_u64(0).unwrap();
+        record
+    }
+
+    #[test]
+    pub fn plan_where_test() {
+        let catalog = catalog();
+        let sql_plan = plan(
+            "SELECT name, SUM(value) FROM kafka_src \
+             WHERE (value + 1) * 2 > 4 AND upper(name) <> 'IT''S' \
+             AND NOT price < -1.5 AND CAST(price AS BIGINT) >= 0 \
+             GROUP BY name, TUMBLE(ts, INTERVAL '1' MINUTE)",
+            &catalog,
+        )
+        .unwrap();
+        let filter = sql_plan.filter.unwrap();
+        assert!(filter.filter(&mut record("a", 2, 0.5)));
+        assert!(!filter.filter(&mut record("a", 1, 0.5)));
+        assert!(!filter.filter(&mut record("it's", 2, 0.5)));
+        assert!(!filter.filter(&mut record("a", 2, -2.0)));
+
+        match plan(
+            "SELECT name, SUM(value) FROM kafka_src WHERE name LIKE 'a%' \
+             GROUP BY name, TUMBLE(ts, INTERVAL '1' MINUTE)",
+            &catalog,
+        ) {
+            Err(SqlError::Unsupported(_)) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    pub fn plan_where_function_test() {
+        let catalog = catalog();
+        let sql_plan = plan(
+            "SELECT name, SUM(value) FROM kafka_src \
+             WHERE contains(lower(trim(name)), 'pp') AND substr(name, 1, 1) = 'A' \
+             AND abs(value - 10) < 5 \
+             GROUP BY name, TUMBLE(ts, INTERVAL '1' MINUTE)",
+            &catalog,
+        )
+        .unwrap();
+        let filter = sql_plan.filter.unwrap();
+        assert!(filter.filter(&mut record("Apple ", 8, 0.0)));
+        assert!(!filter.filter(&mut record("apple", 8, 0.0)));
+        assert!(!filter.filter(&mut record("Apple", 20, 0.0)));
+        assert!(!filter.filter(&mut record("Avocado", 8, 0.0)));
+
+        match plan(
+            "SELECT name, SUM(value) FROM kafka_src WHERE unknown(name) \
+             GROUP BY name, TUMBLE(ts, INTERVAL '1' MINUTE)",
+            &catalog,
+        ) {
+            Err(SqlError::ExpressionError(_)) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        match plan(
+            "SELECT name, SUM(value) FROM kafka_src WHERE contains(name, p => 'a') \
+             GROUP BY name, TUMBLE(ts, INTERVAL '1' MINUTE)",
+            &catalog,
+        ) {
+            Err(SqlError::Unsupported(_)) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    pub struct MyInputFormat {}
+
+    impl MyInputFormat {
+        pub fn new() -> Self {
+            MyInputFormat {}
+        }
+    }
+
+    impl InputSplitSource for MyInputFormat {}
+
+    impl Function for MyInputFormat {
+        fn get_name(&self) -> &str {
+            "MyInputFormat"
+        }
+    }
+
+    impl InputFormat for MyInputFormat {
+        fn open(&mut self, _input_split: InputSplit, _context: &Context) -> rlink::api::Result<()> {
+            Ok(())
+        }
+
+        fn record_iter(&mut self) -> Box<dyn Iterator<Item = Record> + Send> {
+            unimplemented!()
+        }
+
+        fn close(&mut self) -> rlink::api::Result<()> {
+            Ok(())
+        }
+    }
+}
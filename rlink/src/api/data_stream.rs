@@ -1,15 +1,46 @@
 use std::fmt::Debug;
 use std::rc::Rc;
+use std::time::Duration;
 
+use crate::api::element::Record;
 use crate::api::env::StreamManager;
 use crate::api::function::{
-    CoProcessFunction, FilterFunction, FlatMapFunction, InputFormat, KeySelectorFunction,
-    OutputFormat, ReduceFunction,
+    AggregateFunction, AsyncFunction, AsyncOptions, AsyncOutputMode, BroadcastProcessFunction,
+    CoProcessFunction, Distribution, FilterFunction, FlatMapFunction, InputFormat, JoinFunction,
+    KeySelectorFunction, KeyedProcessFunction, OutputFormat, Partitioner, ProcessWindowFunction,
+    ReduceFunction,
 };
 use crate::api::operator::{FunctionCreator, StreamOperator};
+use crate::api::output_tag::OutputTag;
 use crate::api::runtime::OperatorId;
+use crate::api::schema::Schema;
+use crate::api::state::{MapStateDescriptor, TimeDomain};
+use crate::api::trigger::Trigger;
 use crate::api::watermark::WatermarkAssigner;
-use crate::api::window::WindowAssigner;
+use crate::api::window::{EventTimeWindowAssigner, WindowAssigner, WindowOptions};
+use crate::dag::ChainingStrategy;
+use crate::functions::cep::{Pattern, PatternProcessFunction};
+use crate::functions::closure::{
+    FilterFnFunction, FlatMapFnFunction, KeyByFnFunction, MapFnFunction,
+};
+use crate::functions::deduplicate::{DeduplicateFunction, DeduplicateKeep};
+use crate::functions::partitioner::{
+    BroadcastPartitioner, ForwardPartitioner, RebalancePartitioner, RescalePartitioner,
+    ShufflePartitioner,
+};
+use crate::functions::schema_base::reduce::{
+    column_aggregation, max_f64, max_i64, min_f64, min_i64, sum_f64, sum_i64,
+    SchemaBaseReduceFunction,
+};
+use crate::functions::schema_base::top_n::TopN;
+use crate::functions::system::cep_function::CepFunction;
+use crate::functions::system::interval_join_function::IntervalJoinFunction;
+use crate::functions::system::reduce_aggregate_function::ReduceAggregateFunction;
+use crate::functions::system::rolling_aggregate_function::RollingAggregateFunction;
+use crate::functions::system::top_n_function::TopNFunction;
+use crate::functions::system::union_function::UnionFunction;
+use crate::functions::system::window_join_function::WindowJoinFunction;
+use crate::functions::system::window_process_function::WindowProcessFunction;
 
 pub trait TDataStream {
     fn flat_map<F>(self, flat_mapper: F) -> DataStream
@@ -20,22 +51,96 @@ pub trait TDataStream {
     where
         F: FilterFunction + 'static;
 
+    /// Enrich the records by the `AsyncFunction` without blocking the task thread,
+    /// at most `capacity` lookups are in flight, the lookups not completed in `timeout`
+    /// are handled by `AsyncFunction::timeout`.
+    fn async_map<F>(
+        self,
+        async_fn: F,
+        capacity: usize,
+        timeout: Duration,
+        output_mode: AsyncOutputMode,
+    ) -> DataStream
+    where
+        F: AsyncFunction + 'static;
+
     fn key_by<F>(self, key_selector: F) -> KeyedStream
     where
         F: KeySelectorFunction + 'static;
 
+    /// Route the records to the tasks of the next operator by the `Partitioner`,
+    /// the next operator is always placed in a new job.
+    fn partition_custom<P>(self, partitioner: P) -> DataStream
+    where
+        P: Partitioner + 'static;
+
+    /// Distribute the records to all the tasks of the next operator by round-robin.
+    fn rebalance(self) -> DataStream;
+
+    /// Distribute the records by round-robin to a subset of the tasks of the next operator,
+    /// each task only sends to `next_parallelism / parallelism` tasks when scaling up,
+    /// or to one task when scaling down.
+    fn rescale(self) -> DataStream;
+
+    /// Distribute the records to the tasks of the next operator randomly.
+    fn shuffle(self) -> DataStream;
+
+    /// Send the records to the task of the next operator with the same task number,
+    /// the next operator must have the same parallelism.
+    fn forward(self) -> DataStream;
+
     fn assign_timestamps_and_watermarks<W>(self, timestamp_and_watermark_assigner: W) -> DataStream
     where
         W: WatermarkAssigner + 'static;
 
+    /// Set the parallelism of the current operator, it must be called before the downstream
+    /// operators are added. The operator is moved to a new job if the parallelism is
+    /// different from the upstream, and the upstream records are rebalanced to it.
+    fn set_parallelism(self, parallelism: u16) -> DataStream;
+
+    /// Start a new job from the current operator, the records are forwarded from the upstream.
+    fn start_new_chain(self) -> DataStream;
+
+    /// Neither chain the current operator with the upstream nor the downstream operators,
+    /// the records are exchanged by rescale between the jobs, so the tasks can be deployed
+    /// in different workers.
+    fn disable_chaining(self) -> DataStream;
+
+    /// Declare the output schema of the current operator,
+    /// it overrides the `Function::get_output_schema` of the operator.
+    fn with_schema(self, schema: Schema) -> DataStream;
+
+    /// The output schema of the current operator, propagated from the upstream operators.
+    /// `Schema::empty()` if it is undeclared.
+    fn get_schema(&self) -> Schema;
+
     /// Re-balance: Round-robin, Hash, Broadcast
     fn connect<F>(self, data_streams: Vec<CoStream>, f: F) -> ConnectedStreams
     where
         F: CoProcessFunction + 'static;
 
+    /// Replicate the records to all the parallel instances of the connected operator,
+    /// the map state of `descriptor` is updated by the records, see `connect_broadcast`.
+    /// The `BroadcastStream` can also be connected by `connect` as a `CoStream`.
+    fn broadcast(self, descriptor: MapStateDescriptor) -> BroadcastStream;
+
+    /// Connect with the `BroadcastStream`, the records are processed with the broadcast states
+    /// by the `BroadcastProcessFunction`. The parallelism is inherited from this stream.
+    fn connect_broadcast<F>(self, broadcast_stream: BroadcastStream, f: F) -> DataStream
+    where
+        F: BroadcastProcessFunction + 'static;
+
+    /// Union with the `DataStream`s that have the same schema,
+    /// the watermark of the union stream is the minimum of all inputs.
+    fn union(self, data_streams: Vec<DataStream>) -> DataStream;
+
     // fn multiplexing(self) -> MultiplexingStream;
 
-    fn add_sink<O>(self, output_format: O)
+    /// Create a `DataStream` that consumes the records tagged with `output_tag`
+    /// by the current operator.
+    fn get_side_output(&self, output_tag: OutputTag) -> DataStream;
+
+    fn add_sink<O>(self, output_format: O) -> SinkStream
     where
         O: OutputFormat + 'static;
 }
@@ -46,21 +151,116 @@ pub trait TConnectedStreams {
     fn key_by<F>(self, key_selector: F) -> KeyedStream
     where
         F: KeySelectorFunction + 'static;
+
+    /// Create a `DataStream` that consumes the records tagged with `output_tag`
+    /// by the `CoProcessFunction`.
+    fn get_side_output(&self, output_tag: OutputTag) -> DataStream;
 }
 
 pub trait TKeyedStream {
     fn window<W>(self, window_assigner: W) -> WindowedStream
     where
         W: WindowAssigner + 'static;
+
+    /// Process the records by key with the per-key states and timers,
+    /// the records of a key are processed by the same task of the `parallelism` tasks.
+    fn process<F>(self, keyed_process: F, parallelism: u16) -> DataStream
+    where
+        F: KeyedProcessFunction + 'static;
+
+    /// Join the records of `other` with the same key in the event time windows,
+    /// `other` is keyed by `JoinedStreams::where_key`.
+    fn join(self, other: DataStream) -> JoinedStreams;
+
+    /// Join the records of `other` with the same key in the time interval,
+    /// see `IntervalJoinedStreams::between`.
+    fn interval_join(self, other: KeyedStream) -> IntervalJoinedStreams;
+
+    /// Clear the state of the keys without any record for `retention` in the processing time,
+    /// it is applied to the rolling aggregations `reduce`, `sum`, `min` and `max`.
+    /// By default the state of the keys is kept forever.
+    fn idle_state_retention(self, retention: Duration) -> KeyedStream;
+
+    /// Reduce the records of a key across the whole stream, the key with the updated
+    /// reduced value is emitted downstream for each record as an upsert.
+    fn reduce<F>(self, reduce: F, parallelism: u16) -> DataStream
+    where
+        F: ReduceFunction + 'static;
+
+    /// Rolling sum of the `i64` or `f64` column by key, see `reduce`.
+    fn sum(self, column_index: usize, field_types: &[u8], parallelism: u16) -> DataStream;
+
+    /// Rolling minimum of the `i64` or `f64` column by key, see `reduce`.
+    fn min(self, column_index: usize, field_types: &[u8], parallelism: u16) -> DataStream;
+
+    /// Rolling maximum of the `i64` or `f64` column by key, see `reduce`.
+    fn max(self, column_index: usize, field_types: &[u8], parallelism: u16) -> DataStream;
+
+    /// Remove the duplicated records of a key, the key is remembered for `ttl` after its
+    /// first record in the `time_domain`, see `DeduplicateKeep` for the emitted record.
+    fn deduplicate(
+        self,
+        ttl: Duration,
+        keep: DeduplicateKeep,
+        time_domain: TimeDomain,
+        parallelism: u16,
+    ) -> DataStream;
+
+    /// Detect the `pattern` in the records of a key in the event time order,
+    /// the matches are processed by the `PatternProcessFunction`.
+    fn pattern<F>(self, pattern: Pattern, process: F, parallelism: u16) -> DataStream
+    where
+        F: PatternProcessFunction + 'static;
+
     fn add_sink<O>(self, output_format: O) -> SinkStream
     where
         O: OutputFormat + 'static;
 }
 
 pub trait TWindowedStream {
+    /// Set the `Trigger` that determines when a window is fired,
+    /// by default the `WindowAssigner::get_default_trigger` is used.
+    /// The trigger of the merging windows must support `Trigger::on_merge`, eg: the session windows.
+    fn trigger<T>(self, trigger: T) -> WindowedStream
+    where
+        T: Trigger + 'static;
+
+    /// Keep the window state for `allowed_lateness` after the end of the window,
+    /// the records arriving in the period re-fire the window with updated results.
+    fn allowed_lateness(self, allowed_lateness: Duration) -> WindowedStream;
+
+    /// Route the records arriving after the allowed lateness to the side output `output_tag`,
+    /// by default they are dropped.
+    fn side_output_late_data(self, output_tag: OutputTag) -> WindowedStream;
+
     fn reduce<F>(self, reduce: F, parallelism: u16) -> DataStream
     where
         F: ReduceFunction + 'static;
+
+    /// Aggregate the records of the windows by the accumulators,
+    /// `AggregateFunction::get_result` is called when the window fires.
+    fn aggregate<F>(self, aggregate: F, parallelism: u16) -> DataStream
+    where
+        F: AggregateFunction + 'static;
+
+    /// Process all the records of a key in the window by the `ProcessWindowFunction`,
+    /// the records are buffered in the keyed state until the window fires.
+    /// The custom trigger and the merging windows are not supported.
+    fn process<F>(self, process: F, parallelism: u16) -> DataStream
+    where
+        F: ProcessWindowFunction + 'static;
+
+    /// Pre-aggregate the records of a key in the window by the `ReduceFunction`,
+    /// the reduced record is processed by the `ProcessWindowFunction` when the window fires.
+    fn reduce_with_process<R, F>(self, reduce: R, process: F, parallelism: u16) -> DataStream
+    where
+        R: ReduceFunction + 'static,
+        F: ProcessWindowFunction + 'static;
+
+    /// Emit the first `n` records of each partition key in the window with the rank,
+    /// see `TopN`. The records are pre-ranked by the current key in `parallelism` tasks,
+    /// then merged by the partition key in another `parallelism` tasks.
+    fn top_n(self, top_n: TopN, parallelism: u16) -> DataStream;
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -83,6 +283,12 @@ impl From<KeyedStream> for CoStream {
     }
 }
 
+impl From<BroadcastStream> for CoStream {
+    fn from(broadcast_stream: BroadcastStream) -> Self {
+        CoStream::DataStream(DataStream::new(broadcast_stream.data_stream))
+    }
+}
+
 impl Into<StreamBuilder> for CoStream {
     fn into(self) -> StreamBuilder {
         match self {
@@ -92,7 +298,9 @@ impl Into<StreamBuilder> for CoStream {
     }
 }
 
-#[derive(Debug)]
+/// The `DataStream` can be cloned to feed multiple downstream pipelines(fan-out),
+/// the elements are replicated to all the children.
+#[derive(Debug, Clone)]
 pub struct DataStream {
     pub(crate) data_stream: StreamBuilder,
 }
@@ -101,6 +309,40 @@ impl DataStream {
     pub(crate) fn new(data_stream: StreamBuilder) -> Self {
         DataStream { data_stream }
     }
+
+    /// Map each record by the closure, see `TDataStream::flat_map`.
+    /// The closure is cloned with the `StreamApp::build_stream` on each worker,
+    /// the `name` is the operator name in the metrics.
+    pub fn map_fn<F>(self, name: &str, map_fn: F) -> DataStream
+    where
+        F: Fn(Record) -> Record + Send + Sync + Clone + 'static,
+    {
+        self.flat_map(MapFnFunction::new(name, map_fn))
+    }
+
+    /// Map each record to the records returned by the closure, see `TDataStream::flat_map`
+    pub fn flat_map_fn<F>(self, name: &str, flat_map_fn: F) -> DataStream
+    where
+        F: Fn(Record) -> Vec<Record> + Send + Sync + Clone + 'static,
+    {
+        self.flat_map(FlatMapFnFunction::new(name, flat_map_fn))
+    }
+
+    /// Keep the records that the closure returns `true`, see `TDataStream::filter`
+    pub fn filter_fn<F>(self, name: &str, filter_fn: F) -> DataStream
+    where
+        F: Fn(&mut Record) -> bool + Send + Sync + Clone + 'static,
+    {
+        self.filter(FilterFnFunction::new(name, filter_fn))
+    }
+
+    /// Key the records by the key record returned by the closure, see `TDataStream::key_by`
+    pub fn key_by_fn<F>(self, name: &str, key_fn: F) -> KeyedStream
+    where
+        F: Fn(&mut Record) -> Record + Send + Sync + Clone + 'static,
+    {
+        self.key_by(KeyByFnFunction::new(name, key_fn))
+    }
 }
 
 impl TDataStream for DataStream {
@@ -118,6 +360,20 @@ impl TDataStream for DataStream {
         self.data_stream.filter(filter)
     }
 
+    fn async_map<F>(
+        self,
+        async_fn: F,
+        capacity: usize,
+        timeout: Duration,
+        output_mode: AsyncOutputMode,
+    ) -> DataStream
+    where
+        F: AsyncFunction + 'static,
+    {
+        self.data_stream
+            .async_map(async_fn, capacity, timeout, output_mode)
+    }
+
     fn key_by<F>(self, key_selector: F) -> KeyedStream
     where
         F: KeySelectorFunction + 'static,
@@ -125,6 +381,29 @@ impl TDataStream for DataStream {
         self.data_stream.key_by(key_selector)
     }
 
+    fn partition_custom<P>(self, partitioner: P) -> DataStream
+    where
+        P: Partitioner + 'static,
+    {
+        self.data_stream.partition_custom(partitioner)
+    }
+
+    fn rebalance(self) -> DataStream {
+        self.data_stream.rebalance()
+    }
+
+    fn rescale(self) -> DataStream {
+        self.data_stream.rescale()
+    }
+
+    fn shuffle(self) -> DataStream {
+        self.data_stream.shuffle()
+    }
+
+    fn forward(self) -> DataStream {
+        self.data_stream.forward()
+    }
+
     fn assign_timestamps_and_watermarks<W>(self, timestamp_and_watermark_assigner: W) -> DataStream
     where
         W: WatermarkAssigner + 'static,
@@ -133,6 +412,26 @@ impl TDataStream for DataStream {
             .assign_timestamps_and_watermarks(timestamp_and_watermark_assigner)
     }
 
+    fn set_parallelism(self, parallelism: u16) -> DataStream {
+        self.data_stream.set_parallelism(parallelism)
+    }
+
+    fn start_new_chain(self) -> DataStream {
+        self.data_stream.start_new_chain()
+    }
+
+    fn disable_chaining(self) -> DataStream {
+        self.data_stream.disable_chaining()
+    }
+
+    fn with_schema(self, schema: Schema) -> DataStream {
+        self.data_stream.with_schema(schema)
+    }
+
+    fn get_schema(&self) -> Schema {
+        self.data_stream.get_schema()
+    }
+
     fn connect<F>(self, data_streams: Vec<CoStream>, co_process: F) -> ConnectedStreams
     where
         F: CoProcessFunction + 'static,
@@ -140,7 +439,26 @@ impl TDataStream for DataStream {
         self.data_stream.connect(data_streams, co_process)
     }
 
-    fn add_sink<O>(self, output_format: O)
+    fn broadcast(self, descriptor: MapStateDescriptor) -> BroadcastStream {
+        self.data_stream.broadcast(descriptor)
+    }
+
+    fn connect_broadcast<F>(self, broadcast_stream: BroadcastStream, f: F) -> DataStream
+    where
+        F: BroadcastProcessFunction + 'static,
+    {
+        self.data_stream.connect_broadcast(broadcast_stream, f)
+    }
+
+    fn union(self, data_streams: Vec<DataStream>) -> DataStream {
+        self.data_stream.union(data_streams)
+    }
+
+    fn get_side_output(&self, output_tag: OutputTag) -> DataStream {
+        self.data_stream.get_side_output(output_tag)
+    }
+
+    fn add_sink<O>(self, output_format: O) -> SinkStream
     where
         O: OutputFormat + 'static,
     {
@@ -170,16 +488,32 @@ impl TConnectedStreams for ConnectedStreams {
     {
         self.co_stream.key_by(key_selector)
     }
+
+    fn get_side_output(&self, output_tag: OutputTag) -> DataStream {
+        self.co_stream.get_side_output(output_tag)
+    }
 }
 
 #[derive(Debug)]
 pub struct KeyedStream {
     keyed_stream: StreamBuilder,
+    idle_state_retention: Option<u64>,
 }
 
 impl KeyedStream {
     pub(crate) fn new(keyed_stream: StreamBuilder) -> Self {
-        KeyedStream { keyed_stream }
+        KeyedStream {
+            keyed_stream,
+            idle_state_retention: None,
+        }
+    }
+
+    fn rolling_aggregate<F>(self, aggregate: F, parallelism: u16) -> DataStream
+    where
+        F: AggregateFunction + 'static,
+    {
+        self.keyed_stream
+            .rolling_aggregate(aggregate, parallelism, self.idle_state_retention)
     }
 }
 
@@ -191,6 +525,70 @@ impl TKeyedStream for KeyedStream {
         self.keyed_stream.window(window_assigner)
     }
 
+    fn process<F>(self, keyed_process: F, parallelism: u16) -> DataStream
+    where
+        F: KeyedProcessFunction + 'static,
+    {
+        TKeyedStream::process(self.keyed_stream, keyed_process, parallelism)
+    }
+
+    fn join(self, other: DataStream) -> JoinedStreams {
+        self.keyed_stream.join(other)
+    }
+
+    fn interval_join(self, other: KeyedStream) -> IntervalJoinedStreams {
+        self.keyed_stream.interval_join(other)
+    }
+
+    fn idle_state_retention(mut self, retention: Duration) -> KeyedStream {
+        self.idle_state_retention = Some(retention.as_millis() as u64);
+        self
+    }
+
+    fn reduce<F>(self, reduce: F, parallelism: u16) -> DataStream
+    where
+        F: ReduceFunction + 'static,
+    {
+        let aggregate = ReduceAggregateFunction::new(Box::new(reduce));
+        self.rolling_aggregate(aggregate, parallelism)
+    }
+
+    fn sum(self, column_index: usize, field_types: &[u8], parallelism: u16) -> DataStream {
+        let agg = column_aggregation(column_index, field_types, sum_i64, sum_f64);
+        let aggregate = SchemaBaseReduceFunction::new(vec![agg], field_types);
+        self.rolling_aggregate(aggregate, parallelism)
+    }
+
+    fn min(self, column_index: usize, field_types: &[u8], parallelism: u16) -> DataStream {
+        let agg = column_aggregation(column_index, field_types, min_i64, min_f64);
+        let aggregate = SchemaBaseReduceFunction::new(vec![agg], field_types);
+        self.rolling_aggregate(aggregate, parallelism)
+    }
+
+    fn max(self, column_index: usize, field_types: &[u8], parallelism: u16) -> DataStream {
+        let agg = column_aggregation(column_index, field_types, max_i64, max_f64);
+        let aggregate = SchemaBaseReduceFunction::new(vec![agg], field_types);
+        self.rolling_aggregate(aggregate, parallelism)
+    }
+
+    fn deduplicate(
+        self,
+        ttl: Duration,
+        keep: DeduplicateKeep,
+        time_domain: TimeDomain,
+        parallelism: u16,
+    ) -> DataStream {
+        self.keyed_stream
+            .deduplicate(ttl, keep, time_domain, parallelism)
+    }
+
+    fn pattern<F>(self, pattern: Pattern, process: F, parallelism: u16) -> DataStream
+    where
+        F: PatternProcessFunction + 'static,
+    {
+        self.keyed_stream.pattern(pattern, process, parallelism)
+    }
+
     fn add_sink<O>(self, output_format: O) -> SinkStream
     where
         O: OutputFormat + 'static,
@@ -202,20 +600,182 @@ impl TKeyedStream for KeyedStream {
 #[derive(Debug)]
 pub struct WindowedStream {
     windowed_stream: StreamBuilder,
+    window_options: WindowOptions,
 }
 
 impl WindowedStream {
     pub(crate) fn new(windowed_stream: StreamBuilder) -> Self {
-        WindowedStream { windowed_stream }
+        WindowedStream {
+            windowed_stream,
+            window_options: WindowOptions::default(),
+        }
     }
 }
 
 impl TWindowedStream for WindowedStream {
+    fn trigger<T>(mut self, trigger: T) -> WindowedStream
+    where
+        T: Trigger + 'static,
+    {
+        self.window_options.trigger = Some(Box::new(trigger));
+        self
+    }
+
+    fn allowed_lateness(mut self, allowed_lateness: Duration) -> WindowedStream {
+        self.window_options.allowed_lateness = allowed_lateness.as_millis() as u64;
+        self
+    }
+
+    fn side_output_late_data(mut self, output_tag: OutputTag) -> WindowedStream {
+        self.window_options.late_data_output_tag = Some(output_tag);
+        self
+    }
+
     fn reduce<F>(self, reduce: F, parallelism: u16) -> DataStream
     where
         F: ReduceFunction + 'static,
     {
-        self.windowed_stream.reduce(reduce, parallelism)
+        let aggregate = ReduceAggregateFunction::new(Box::new(reduce));
+        self.windowed_stream
+            .window_aggregate(aggregate, parallelism, self.window_options)
+    }
+
+    fn aggregate<F>(self, aggregate: F, parallelism: u16) -> DataStream
+    where
+        F: AggregateFunction + 'static,
+    {
+        self.windowed_stream
+            .window_aggregate(aggregate, parallelism, self.window_options)
+    }
+
+    fn process<F>(self, process: F, parallelism: u16) -> DataStream
+    where
+        F: ProcessWindowFunction + 'static,
+    {
+        self.windowed_stream.window_process(
+            None,
+            Box::new(process),
+            parallelism,
+            self.window_options,
+        )
+    }
+
+    fn reduce_with_process<R, F>(self, reduce: R, process: F, parallelism: u16) -> DataStream
+    where
+        R: ReduceFunction + 'static,
+        F: ProcessWindowFunction + 'static,
+    {
+        self.windowed_stream.window_process(
+            Some(Box::new(reduce)),
+            Box::new(process),
+            parallelism,
+            self.window_options,
+        )
+    }
+
+    fn top_n(self, top_n: TopN, parallelism: u16) -> DataStream {
+        self.windowed_stream
+            .window_top_n(top_n, parallelism, self.window_options)
+    }
+}
+
+/// The stream replicated to all the parallel instances, see `TDataStream::broadcast`
+#[derive(Debug)]
+pub struct BroadcastStream {
+    data_stream: StreamBuilder,
+    descriptor: MapStateDescriptor,
+}
+
+/// The streams joined in the windows, see `TKeyedStream::join`
+#[derive(Debug)]
+pub struct JoinedStreams {
+    left: StreamBuilder,
+    right: StreamBuilder,
+}
+
+impl JoinedStreams {
+    /// Key the other stream by `key_selector`, the records with the equal keys are joined.
+    pub fn where_key<F>(self, key_selector: F) -> KeyedJoinedStreams
+    where
+        F: KeySelectorFunction + 'static,
+    {
+        KeyedJoinedStreams {
+            left: self.left,
+            right: self.right.key_by(key_selector).keyed_stream,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct KeyedJoinedStreams {
+    left: StreamBuilder,
+    right: StreamBuilder,
+}
+
+impl KeyedJoinedStreams {
+    /// Join the records in the same window, the window is joined and evicted
+    /// once the watermark passes the end of the window,
+    /// so only the tumbling and sliding event time windows are accepted,
+    /// the session windows are never merged by the join.
+    pub fn window<W>(self, window_assigner: W) -> WindowJoinedStreams
+    where
+        W: EventTimeWindowAssigner + 'static,
+    {
+        WindowJoinedStreams {
+            left: self.left,
+            right: self.right,
+            window_assigner: Box::new(window_assigner),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct WindowJoinedStreams {
+    left: StreamBuilder,
+    right: StreamBuilder,
+    window_assigner: Box<dyn WindowAssigner>,
+}
+
+impl WindowJoinedStreams {
+    pub fn apply<F>(self, join: F, parallelism: u16) -> DataStream
+    where
+        F: JoinFunction + 'static,
+    {
+        let join_func = WindowJoinFunction::new(self.window_assigner, Box::new(join));
+        self.left
+            .keyed_join(self.right, Box::new(join_func), parallelism)
+    }
+}
+
+/// The keyed streams joined in the time interval, see `TKeyedStream::interval_join`
+#[derive(Debug)]
+pub struct IntervalJoinedStreams {
+    left: StreamBuilder,
+    right: StreamBuilder,
+    /// milliseconds
+    lower_bound: u64,
+    /// milliseconds
+    upper_bound: u64,
+}
+
+impl IntervalJoinedStreams {
+    /// Join the left record with the right records that
+    /// `left.timestamp - lower_bound <= right.timestamp <= left.timestamp + upper_bound`,
+    /// by default both bounds are zero.
+    pub fn between(mut self, lower_bound: Duration, upper_bound: Duration) -> Self {
+        self.lower_bound = lower_bound.as_millis() as u64;
+        self.upper_bound = upper_bound.as_millis() as u64;
+        self
+    }
+
+    pub fn apply<F>(self, join: F, parallelism: u16) -> DataStream
+    where
+        F: JoinFunction + 'static,
+    {
+        let join_func =
+            IntervalJoinFunction::new(self.lower_bound, self.upper_bound, Box::new(join));
+        self.left
+            .keyed_join(self.right, Box::new(join_func), parallelism)
     }
 }
 
@@ -228,12 +788,39 @@ impl SinkStream {
     pub(crate) fn new(end_stream: StreamBuilder) -> Self {
         SinkStream { end_stream }
     }
+
+    /// Set the parallelism of the sink, see `TDataStream::set_parallelism`
+    pub fn set_parallelism(self, parallelism: u16) -> SinkStream {
+        let end_stream = &self.end_stream;
+        end_stream
+            .stream_manager
+            .set_parallelism(end_stream.cur_operator_id, parallelism);
+        self
+    }
+
+    /// Start a new job from the sink, see `TDataStream::start_new_chain`
+    pub fn start_new_chain(self) -> SinkStream {
+        let end_stream = &self.end_stream;
+        end_stream
+            .stream_manager
+            .set_chaining_strategy(end_stream.cur_operator_id, ChainingStrategy::Head);
+        self
+    }
+
+    /// Run the sink in a job separated from the upstream, see `TDataStream::disable_chaining`
+    pub fn disable_chaining(self) -> SinkStream {
+        let end_stream = &self.end_stream;
+        end_stream
+            .stream_manager
+            .set_chaining_strategy(end_stream.cur_operator_id, ChainingStrategy::Never);
+        self
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct StreamBuilder {
     current_id: u32,
 
@@ -260,10 +847,11 @@ impl StreamBuilder {
 
     pub fn with_connect(
         stream_manager: Rc<StreamManager>,
+        fn_creator: FunctionCreator,
         co_process_func: Box<dyn CoProcessFunction>,
         parent_ids: Vec<OperatorId>,
     ) -> Self {
-        let co_operator = StreamOperator::new_co_process(co_process_func);
+        let co_operator = StreamOperator::new_co_process(fn_creator, co_process_func);
         let operator_id = stream_manager.add_operator(co_operator, parent_ids);
 
         StreamBuilder {
@@ -272,6 +860,184 @@ impl StreamBuilder {
             stream_manager,
         }
     }
+
+    pub fn window_aggregate<F>(
+        mut self,
+        aggregate: F,
+        parallelism: u16,
+        window_options: WindowOptions,
+    ) -> DataStream
+    where
+        F: AggregateFunction + 'static,
+    {
+        if let Some(trigger) = &window_options.trigger {
+            let (_event_time, merging) = self
+                .stream_manager
+                .get_window_properties(self.cur_operator_id);
+            if merging && !trigger.can_merge() {
+                panic!(
+                    "the trigger {} can not merge the windows of the merging window assigner",
+                    trigger.get_name()
+                );
+            }
+        }
+
+        let aggregate_func = Box::new(aggregate);
+        let stream_reduce = StreamOperator::new_reduce(parallelism, aggregate_func, window_options);
+
+        self.cur_operator_id = self
+            .stream_manager
+            .add_operator(stream_reduce, vec![self.cur_operator_id]);
+
+        DataStream::new(self)
+    }
+
+    /// aggregate the records by key across the whole stream by a system `KeyedProcessFunction`
+    pub fn rolling_aggregate<F>(
+        mut self,
+        aggregate: F,
+        parallelism: u16,
+        idle_state_retention: Option<u64>,
+    ) -> DataStream
+    where
+        F: AggregateFunction + 'static,
+    {
+        let key_schema = self.stream_manager.get_key_schema(self.cur_operator_id);
+        let rolling_aggregate_func =
+            RollingAggregateFunction::new(Box::new(aggregate), idle_state_retention, key_schema);
+        let stream_rolling_aggregate = StreamOperator::new_keyed_process(
+            parallelism,
+            FunctionCreator::System,
+            Box::new(rolling_aggregate_func),
+        );
+
+        self.cur_operator_id = self
+            .stream_manager
+            .add_operator(stream_rolling_aggregate, vec![self.cur_operator_id]);
+
+        DataStream::new(self)
+    }
+
+    /// process the windows by a system `KeyedProcessFunction` pipelined with the window assigner
+    pub fn window_process(
+        mut self,
+        reduce_func: Option<Box<dyn ReduceFunction>>,
+        process_func: Box<dyn ProcessWindowFunction>,
+        parallelism: u16,
+        window_options: WindowOptions,
+    ) -> DataStream {
+        if window_options.trigger.is_some() {
+            panic!("the custom trigger is not supported by the window process");
+        }
+
+        let (event_time, merging) = self
+            .stream_manager
+            .get_window_properties(self.cur_operator_id);
+        if merging {
+            panic!("the merging windows are not supported by the window process");
+        }
+        let time_domain = if event_time {
+            TimeDomain::EventTime
+        } else {
+            TimeDomain::ProcessingTime
+        };
+
+        let window_process_func = WindowProcessFunction::new(
+            time_domain,
+            window_options.allowed_lateness,
+            window_options.late_data_output_tag,
+            reduce_func,
+            process_func,
+        );
+        let stream_window_process = StreamOperator::new_keyed_process(
+            parallelism,
+            FunctionCreator::System,
+            Box::new(window_process_func),
+        );
+
+        self.cur_operator_id = self
+            .stream_manager
+            .add_operator(stream_window_process, vec![self.cur_operator_id]);
+
+        DataStream::new(self)
+    }
+
+    /// rank the windows in two stages, the candidates of the window process
+    /// are merged by the partition key in a system `KeyedProcessFunction`
+    pub fn window_top_n(
+        self,
+        top_n: TopN,
+        parallelism: u16,
+        window_options: WindowOptions,
+    ) -> DataStream {
+        let (event_time, _merging) = self
+            .stream_manager
+            .get_window_properties(self.cur_operator_id);
+        let time_domain = if event_time {
+            TimeDomain::EventTime
+        } else {
+            TimeDomain::ProcessingTime
+        };
+
+        let key_selector = top_n.partition_key_selector();
+        let candidates = self.window_process(
+            Some(Box::new(top_n.clone())),
+            Box::new(top_n.clone()),
+            parallelism,
+            window_options,
+        );
+
+        let mut keyed_stream = candidates.data_stream.key_by(key_selector).keyed_stream;
+        let top_n_func = TopNFunction::new(top_n, time_domain);
+        let stream_top_n = StreamOperator::new_keyed_process(
+            parallelism,
+            FunctionCreator::System,
+            Box::new(top_n_func),
+        );
+
+        keyed_stream.cur_operator_id = keyed_stream
+            .stream_manager
+            .add_operator(stream_top_n, vec![keyed_stream.cur_operator_id]);
+
+        DataStream::new(keyed_stream)
+    }
+}
+
+impl StreamBuilder {
+    /// join the keyed streams by a `KeyedProcessFunction`,
+    /// this stream is placed in the first position, the `stream_seq` of its records is 0
+    fn keyed_join(
+        mut self,
+        other: StreamBuilder,
+        keyed_process_func: Box<dyn KeyedProcessFunction>,
+        parallelism: u16,
+    ) -> DataStream {
+        let stream_join = StreamOperator::new_keyed_process(
+            parallelism,
+            FunctionCreator::System,
+            keyed_process_func,
+        );
+
+        let parent_ids = vec![self.cur_operator_id, other.cur_operator_id];
+        self.cur_operator_id = self.stream_manager.add_operator(stream_join, parent_ids);
+
+        DataStream::new(self)
+    }
+
+    fn partition(
+        mut self,
+        fn_creator: FunctionCreator,
+        partitioner: Box<dyn Partitioner>,
+        distribution: Distribution,
+    ) -> Self {
+        let stream_partition = StreamOperator::new_partition(fn_creator, partitioner, distribution);
+
+        self.cur_operator_id = self
+            .stream_manager
+            .add_operator(stream_partition, vec![self.cur_operator_id]);
+
+        self
+    }
 }
 
 impl TDataStream for StreamBuilder {
@@ -303,6 +1069,31 @@ impl TDataStream for StreamBuilder {
         DataStream::new(self)
     }
 
+    fn async_map<F>(
+        mut self,
+        async_fn: F,
+        capacity: usize,
+        timeout: Duration,
+        output_mode: AsyncOutputMode,
+    ) -> DataStream
+    where
+        F: AsyncFunction + 'static,
+    {
+        let async_func = Box::new(async_fn);
+        let async_options = AsyncOptions {
+            capacity: std::cmp::max(capacity, 1),
+            timeout,
+            output_mode,
+        };
+        let stream_async_map = StreamOperator::new_async_map(async_func, async_options);
+
+        self.cur_operator_id = self
+            .stream_manager
+            .add_operator(stream_async_map, vec![self.cur_operator_id]);
+
+        DataStream::new(self)
+    }
+
     fn key_by<F>(mut self, key_selector: F) -> KeyedStream
     where
         F: KeySelectorFunction + 'static,
@@ -317,6 +1108,38 @@ impl TDataStream for StreamBuilder {
         KeyedStream::new(self)
     }
 
+    fn partition_custom<P>(self, partitioner: P) -> DataStream
+    where
+        P: Partitioner + 'static,
+    {
+        let partitioner = Box::new(partitioner);
+        DataStream::new(self.partition(FunctionCreator::User, partitioner, Distribution::Custom))
+    }
+
+    fn rebalance(self) -> DataStream {
+        let partitioner = Box::new(RebalancePartitioner::new());
+        DataStream::new(self.partition(
+            FunctionCreator::System,
+            partitioner,
+            Distribution::ReBalance,
+        ))
+    }
+
+    fn rescale(self) -> DataStream {
+        let partitioner = Box::new(RescalePartitioner::new());
+        DataStream::new(self.partition(FunctionCreator::System, partitioner, Distribution::Rescale))
+    }
+
+    fn shuffle(self) -> DataStream {
+        let partitioner = Box::new(ShufflePartitioner::new());
+        DataStream::new(self.partition(FunctionCreator::System, partitioner, Distribution::Shuffle))
+    }
+
+    fn forward(self) -> DataStream {
+        let partitioner = Box::new(ForwardPartitioner::new());
+        DataStream::new(self.partition(FunctionCreator::System, partitioner, Distribution::Forward))
+    }
+
     fn assign_timestamps_and_watermarks<W>(
         mut self,
         timestamp_and_watermark_assigner: W,
@@ -334,6 +1157,37 @@ impl TDataStream for StreamBuilder {
         DataStream::new(self)
     }
 
+    fn set_parallelism(self, parallelism: u16) -> DataStream {
+        self.stream_manager
+            .set_parallelism(self.cur_operator_id, parallelism);
+
+        DataStream::new(self)
+    }
+
+    fn start_new_chain(self) -> DataStream {
+        self.stream_manager
+            .set_chaining_strategy(self.cur_operator_id, ChainingStrategy::Head);
+
+        DataStream::new(self)
+    }
+
+    fn disable_chaining(self) -> DataStream {
+        self.stream_manager
+            .set_chaining_strategy(self.cur_operator_id, ChainingStrategy::Never);
+
+        DataStream::new(self)
+    }
+
+    fn with_schema(self, schema: Schema) -> DataStream {
+        self.stream_manager.set_schema(self.cur_operator_id, schema);
+
+        DataStream::new(self)
+    }
+
+    fn get_schema(&self) -> Schema {
+        self.stream_manager.get_schema(self.cur_operator_id)
+    }
+
     fn connect<F>(self, data_streams: Vec<CoStream>, co_process: F) -> ConnectedStreams
     where
         F: CoProcessFunction + 'static,
@@ -351,6 +1205,7 @@ impl TDataStream for StreamBuilder {
 
         let co_stream = StreamBuilder::with_connect(
             pipeline_stream_manager,
+            FunctionCreator::User,
             Box::new(co_process),
             parent_ids.clone(),
         );
@@ -358,7 +1213,85 @@ impl TDataStream for StreamBuilder {
         ConnectedStreams::new(co_stream, parent_ids)
     }
 
-    fn add_sink<O>(mut self, output_format: O)
+    fn broadcast(self, descriptor: MapStateDescriptor) -> BroadcastStream {
+        let partitioner = Box::new(BroadcastPartitioner::new());
+        BroadcastStream {
+            data_stream: self.partition(
+                FunctionCreator::System,
+                partitioner,
+                Distribution::Broadcast,
+            ),
+            descriptor,
+        }
+    }
+
+    fn connect_broadcast<F>(mut self, broadcast_stream: BroadcastStream, f: F) -> DataStream
+    where
+        F: BroadcastProcessFunction + 'static,
+    {
+        let stream_broadcast_process =
+            StreamOperator::new_broadcast_process(Box::new(f), vec![broadcast_stream.descriptor]);
+
+        // the broadcast stream is placed in the last position
+        let parent_ids = vec![
+            self.cur_operator_id,
+            broadcast_stream.data_stream.cur_operator_id,
+        ];
+        self.cur_operator_id = self
+            .stream_manager
+            .add_operator(stream_broadcast_process, parent_ids);
+
+        DataStream::new(self)
+    }
+
+    fn union(self, data_streams: Vec<DataStream>) -> DataStream {
+        if data_streams.is_empty() {
+            return DataStream::new(self);
+        }
+
+        let schema = self.get_schema();
+        for data_stream in &data_streams {
+            let other_schema = data_stream.get_schema();
+            if !schema.is_empty() && !other_schema.is_empty() && schema != other_schema {
+                panic!(
+                    "the union streams have different schemas, {} and {}",
+                    schema, other_schema
+                );
+            }
+        }
+
+        let pipeline_stream_manager = self.stream_manager.clone();
+
+        // the same order as `connect`, `this` is placed in the last position
+        let mut parent_ids: Vec<OperatorId> = data_streams
+            .iter()
+            .map(|x| x.data_stream.cur_operator_id)
+            .collect();
+        parent_ids.push(self.cur_operator_id);
+
+        let union_stream = StreamBuilder::with_connect(
+            pipeline_stream_manager,
+            FunctionCreator::System,
+            Box::new(UnionFunction::new(schema)),
+            parent_ids,
+        );
+
+        DataStream::new(union_stream)
+    }
+
+    fn get_side_output(&self, output_tag: OutputTag) -> DataStream {
+        let operator_id = self
+            .stream_manager
+            .add_side_output(self.cur_operator_id, output_tag);
+
+        DataStream::new(StreamBuilder {
+            current_id: 0,
+            cur_operator_id: operator_id,
+            stream_manager: self.stream_manager.clone(),
+        })
+    }
+
+    fn add_sink<O>(mut self, output_format: O) -> SinkStream
     where
         O: OutputFormat + 'static,
     {
@@ -368,6 +1301,8 @@ impl TDataStream for StreamBuilder {
         self.cur_operator_id = self
             .stream_manager
             .add_operator(stream_sink, vec![self.cur_operator_id]);
+
+        SinkStream::new(self)
     }
 }
 
@@ -386,6 +1321,99 @@ impl TKeyedStream for StreamBuilder {
         WindowedStream::new(self)
     }
 
+    fn process<F>(mut self, keyed_process: F, parallelism: u16) -> DataStream
+    where
+        F: KeyedProcessFunction + 'static,
+    {
+        let keyed_process_func = Box::new(keyed_process);
+        let stream_keyed_process = StreamOperator::new_keyed_process(
+            parallelism,
+            FunctionCreator::User,
+            keyed_process_func,
+        );
+
+        self.cur_operator_id = self
+            .stream_manager
+            .add_operator(stream_keyed_process, vec![self.cur_operator_id]);
+
+        DataStream::new(self)
+    }
+
+    fn join(self, other: DataStream) -> JoinedStreams {
+        JoinedStreams {
+            left: self,
+            right: other.data_stream,
+        }
+    }
+
+    fn interval_join(self, other: KeyedStream) -> IntervalJoinedStreams {
+        IntervalJoinedStreams {
+            left: self,
+            right: other.keyed_stream,
+            lower_bound: 0,
+            upper_bound: 0,
+        }
+    }
+
+    fn idle_state_retention(self, retention: Duration) -> KeyedStream {
+        KeyedStream::new(self).idle_state_retention(retention)
+    }
+
+    fn reduce<F>(self, reduce: F, parallelism: u16) -> DataStream
+    where
+        F: ReduceFunction + 'static,
+    {
+        TKeyedStream::reduce(KeyedStream::new(self), reduce, parallelism)
+    }
+
+    fn sum(self, column_index: usize, field_types: &[u8], parallelism: u16) -> DataStream {
+        KeyedStream::new(self).sum(column_index, field_types, parallelism)
+    }
+
+    fn min(self, column_index: usize, field_types: &[u8], parallelism: u16) -> DataStream {
+        KeyedStream::new(self).min(column_index, field_types, parallelism)
+    }
+
+    fn max(self, column_index: usize, field_types: &[u8], parallelism: u16) -> DataStream {
+        KeyedStream::new(self).max(column_index, field_types, parallelism)
+    }
+
+    fn deduplicate(
+        mut self,
+        ttl: Duration,
+        keep: DeduplicateKeep,
+        time_domain: TimeDomain,
+        parallelism: u16,
+    ) -> DataStream {
+        let deduplicate_func = Box::new(DeduplicateFunction::new(ttl, keep, time_domain));
+        let stream_deduplicate = StreamOperator::new_keyed_process(
+            parallelism,
+            FunctionCreator::System,
+            deduplicate_func,
+        );
+
+        self.cur_operator_id = self
+            .stream_manager
+            .add_operator(stream_deduplicate, vec![self.cur_operator_id]);
+
+        DataStream::new(self)
+    }
+
+    fn pattern<F>(mut self, pattern: Pattern, process: F, parallelism: u16) -> DataStream
+    where
+        F: PatternProcessFunction + 'static,
+    {
+        let cep_func = Box::new(CepFunction::new(pattern, Box::new(process)));
+        let stream_cep =
+            StreamOperator::new_keyed_process(parallelism, FunctionCreator::System, cep_func);
+
+        self.cur_operator_id = self
+            .stream_manager
+            .add_operator(stream_cep, vec![self.cur_operator_id]);
+
+        DataStream::new(self)
+    }
+
     fn add_sink<O>(mut self, output_format: O) -> SinkStream
     where
         O: OutputFormat + 'static,
@@ -402,17 +1430,51 @@ impl TKeyedStream for StreamBuilder {
 }
 
 impl TWindowedStream for StreamBuilder {
-    fn reduce<F>(mut self, reduce: F, parallelism: u16) -> DataStream
+    fn trigger<T>(self, trigger: T) -> WindowedStream
+    where
+        T: Trigger + 'static,
+    {
+        WindowedStream::new(self).trigger(trigger)
+    }
+
+    fn allowed_lateness(self, allowed_lateness: Duration) -> WindowedStream {
+        WindowedStream::new(self).allowed_lateness(allowed_lateness)
+    }
+
+    fn side_output_late_data(self, output_tag: OutputTag) -> WindowedStream {
+        WindowedStream::new(self).side_output_late_data(output_tag)
+    }
+
+    fn reduce<F>(self, reduce: F, parallelism: u16) -> DataStream
     where
         F: ReduceFunction + 'static,
     {
-        let reduce_func = Box::new(reduce);
-        let stream_reduce = StreamOperator::new_reduce(parallelism, reduce_func);
+        WindowedStream::new(self).reduce(reduce, parallelism)
+    }
 
-        self.cur_operator_id = self
-            .stream_manager
-            .add_operator(stream_reduce, vec![self.cur_operator_id]);
+    fn aggregate<F>(self, aggregate: F, parallelism: u16) -> DataStream
+    where
+        F: AggregateFunction + 'static,
+    {
+        WindowedStream::new(self).aggregate(aggregate, parallelism)
+    }
 
-        DataStream::new(self)
+    fn process<F>(self, process: F, parallelism: u16) -> DataStream
+    where
+        F: ProcessWindowFunction + 'static,
+    {
+        WindowedStream::new(self).process(process, parallelism)
+    }
+
+    fn reduce_with_process<R, F>(self, reduce: R, process: F, parallelism: u16) -> DataStream
+    where
+        R: ReduceFunction + 'static,
+        F: ProcessWindowFunction + 'static,
+    {
+        WindowedStream::new(self).reduce_with_process(reduce, process, parallelism)
+    }
+
+    fn top_n(self, top_n: TopN, parallelism: u16) -> DataStream {
+        WindowedStream::new(self).top_n(top_n, parallelism)
     }
 }
@@ -5,7 +5,9 @@ use std::fmt::Debug;
 
 use bytes::{Buf, BufMut, BytesMut};
 
+use crate::api::output_tag::OutputTag;
 use crate::api::runtime::{ChannelKey, CheckpointId};
+use crate::api::schema::Schema;
 use crate::api::window::Window;
 
 lazy_static! {
@@ -47,6 +49,8 @@ pub struct Record {
     pub(crate) channel_key: ChannelKey,
     pub(crate) location_windows: Option<Vec<Window>>,
     pub(crate) trigger_window: Option<Window>,
+    // the side output of the record, `None` is the main output. it is not serialized
+    pub(crate) output_tag: Option<OutputTag>,
 
     pub(crate) values: Buffer,
 }
@@ -59,6 +63,7 @@ impl Record {
             channel_key: ChannelKey::default(),
             location_windows: None,
             trigger_window: None,
+            output_tag: None,
             values: Buffer::new(),
         }
     }
@@ -70,6 +75,7 @@ impl Record {
             channel_key: ChannelKey::default(),
             location_windows: None,
             trigger_window: None,
+            output_tag: None,
             values: Buffer::with_capacity(capacity),
         }
     }
@@ -118,6 +124,16 @@ impl Record {
         self.trigger_window.clone()
     }
 
+    /// Emit the record to the side output `output_tag` instead of the main output.
+    /// The record is dropped if there is no `DataStream::get_side_output` with the same tag.
+    pub fn set_output_tag(&mut self, output_tag: OutputTag) {
+        self.output_tag = Some(output_tag);
+    }
+
+    pub fn get_output_tag(&self) -> Option<&OutputTag> {
+        self.output_tag.as_ref()
+    }
+
     pub fn as_buffer(&mut self) -> &mut Buffer {
         self.values.borrow_mut()
     }
@@ -173,6 +189,7 @@ impl Serde for Record {
             channel_key: ChannelKey::default(),
             location_windows: None,
             trigger_window: None,
+            output_tag: None,
             values: Buffer::from(values),
         }
     }
@@ -186,6 +203,25 @@ impl PartialEq for Record {
     }
 }
 
+/// Map a plain struct to the fields of a `Record` in the declaration order,
+/// it is implemented by `#[derive(RecordSchema)]` of `rlink-derive`.
+pub trait RecordSchema
+where
+    Self: Sized,
+{
+    /// the `types::*` of the fields
+    const FIELD_TYPES: &'static [u8];
+    const FIELD_NAMES: &'static [&'static str];
+
+    fn to_record(&self) -> Record;
+    fn from_record(record: &mut Record) -> Result<Self, std::io::Error>;
+
+    /// the non-null fields named by the struct fields
+    fn schema() -> Schema {
+        Schema::from_names(Self::FIELD_NAMES, Self::FIELD_TYPES)
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
 pub struct Watermark {
     // for partition routing
@@ -481,6 +517,14 @@ impl Element {
         }
     }
 
+    /// Checks whether this element is the stream status that marks the end of the input.
+    pub(crate) fn is_end_of_input(&self) -> bool {
+        match self {
+            Element::StreamStatus(stream_status) => stream_status.end,
+            _ => false,
+        }
+    }
+
     /// Checks whether this element is a Barrier.
     ///	return `True`, if this element is a barrier, false otherwise.
     pub(crate) fn is_barrier(&self) -> bool {
@@ -4,9 +4,11 @@ use std::rc::Rc;
 use crate::api::data_stream::{DataStream, StreamBuilder};
 use crate::api::function::InputFormat;
 use crate::api::operator::StreamOperator;
+use crate::api::output_tag::OutputTag;
 use crate::api::properties::Properties;
 use crate::api::runtime::OperatorId;
-use crate::dag::RawStreamGraph;
+use crate::api::schema::Schema;
+use crate::dag::{ChainingStrategy, RawStreamGraph};
 use crate::runtime;
 
 /// define a stream application
@@ -88,4 +90,64 @@ impl StreamManager {
             .add_operator(operator, parent_operator_ids)
             .expect("add operator error")
     }
+
+    pub fn set_parallelism(&self, operator_id: OperatorId, parallelism: u16) {
+        self.stream_graph
+            .borrow_mut()
+            .set_parallelism(operator_id, parallelism)
+            .expect("set parallelism error")
+    }
+
+    pub fn set_schema(&self, operator_id: OperatorId, schema: Schema) {
+        self.stream_graph
+            .borrow_mut()
+            .set_schema(operator_id, schema)
+            .expect("set schema error")
+    }
+
+    pub fn get_schema(&self, operator_id: OperatorId) -> Schema {
+        self.stream_graph
+            .borrow()
+            .get_schema(operator_id)
+            .expect("get schema error")
+    }
+
+    /// the key schema of the nearest `KeyBy` upstream of the operator
+    pub fn get_key_schema(&self, operator_id: OperatorId) -> Schema {
+        self.stream_graph.borrow().get_key_schema(operator_id)
+    }
+
+    pub fn set_chaining_strategy(
+        &self,
+        operator_id: OperatorId,
+        chaining_strategy: ChainingStrategy,
+    ) {
+        self.stream_graph
+            .borrow_mut()
+            .set_chaining_strategy(operator_id, chaining_strategy)
+            .expect("set chaining strategy error")
+    }
+
+    /// Returns `(is_event_time, is_merging)` of the `WindowAssigner` operator
+    pub fn get_window_properties(&self, operator_id: OperatorId) -> (bool, bool) {
+        let stream_graph = self.stream_graph.borrow();
+        let window_assigner = stream_graph
+            .get_window_assigner(operator_id)
+            .expect("window assigner not found");
+        (
+            window_assigner.is_event_time(),
+            window_assigner.is_merging(),
+        )
+    }
+
+    pub fn add_side_output(
+        &self,
+        parent_operator_id: OperatorId,
+        output_tag: OutputTag,
+    ) -> OperatorId {
+        self.stream_graph
+            .borrow_mut()
+            .add_side_output(parent_operator_id, output_tag)
+            .expect("add side output error")
+    }
 }
@@ -1,14 +1,35 @@
 use std::fmt::Debug;
+use std::time::Duration;
+
+use futures::future::BoxFuture;
 
 use crate::api::checkpoint::{CheckpointHandle, CheckpointedFunction, FunctionSnapshotContext};
 use crate::api::element::{Element, Record};
 use crate::api::properties::Properties;
 use crate::api::runtime::{CheckpointId, OperatorId, TaskId};
+use crate::api::schema::Schema;
+use crate::api::state::{
+    BroadcastContext, KeyedProcessContext, ReadOnlyBroadcastContext, TimeDomain,
+};
+use crate::api::window::TimeWindow;
 use crate::dag::execution_graph::{ExecutionEdge, ExecutionNode};
 
 /// Base class of all operators in the Rust API.
 pub trait Function {
     fn get_name(&self) -> &str;
+
+    /// The schema of the records emitted by the function with the `input_schema` of
+    /// the upstream, it is the key of the `KeySelectorFunction` and the reduced values
+    /// of the window `AggregateFunction`. `Schema::empty()` if it is undeclared.
+    fn get_output_schema(&self, _input_schema: &Schema) -> Schema {
+        Schema::empty()
+    }
+
+    /// The schema of the records consumed by the function,
+    /// it is checked with the output schema of the upstream when the graph is built.
+    fn get_input_schema(&self) -> Schema {
+        Schema::empty()
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -147,6 +168,7 @@ where
     Self: Function,
 {
     fn open(&mut self, context: &Context) -> crate::api::Result<()>;
+    /// The records tagged by `Record::set_output_tag` are emitted to the side output.
     fn flat_map(&mut self, record: Record) -> Box<dyn Iterator<Item = Record>>;
     fn flat_map_element(&mut self, element: Element) -> Box<dyn Iterator<Item = Element>> {
         let iterator = self.flat_map(element.into_record());
@@ -164,6 +186,42 @@ where
     fn close(&mut self) -> crate::api::Result<()>;
 }
 
+/// Enrich the records by the external lookups without blocking the task thread,
+/// the lookups run on the tokio runtime shared by the tasks of the worker.
+pub trait AsyncFunction
+where
+    Self: Function,
+{
+    fn open(&mut self, context: &Context) -> crate::api::Result<()>;
+    /// This method is called for each record, the returned future is spawned on the tokio runtime
+    /// and its records are emitted once it completes.
+    fn async_invoke(&self, record: Record) -> BoxFuture<'static, Vec<Record>>;
+    /// This method is called if the lookup of `record` is not completed in the timeout,
+    /// the record is dropped by default.
+    fn timeout(&self, _record: Record) -> Vec<Record> {
+        vec![]
+    }
+    fn close(&mut self) -> crate::api::Result<()>;
+}
+
+/// The order of the records emitted by the `AsyncFunction`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AsyncOutputMode {
+    /// the records are emitted in the order of the input
+    Ordered,
+    /// the records are emitted as soon as the lookups complete,
+    /// but never across the watermarks and barriers
+    Unordered,
+}
+
+#[derive(Clone, Debug)]
+pub struct AsyncOptions {
+    /// the maximum number of the in-flight lookups
+    pub(crate) capacity: usize,
+    pub(crate) timeout: Duration,
+    pub(crate) output_mode: AsyncOutputMode,
+}
+
 pub trait KeySelectorFunction
 where
     Self: Function,
@@ -173,6 +231,38 @@ where
     fn close(&mut self) -> crate::api::Result<()>;
 }
 
+/// How the records of the upstream tasks are distributed to the tasks of the child job,
+/// it decides which tasks are connected in the `ExecutionGraph`
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum Distribution {
+    /// the records of a key are sent to the same task, see `DataStream::key_by`
+    Hash,
+    /// each task sends to the task of the same task number, see `DataStream::forward`
+    Forward,
+    /// each task sends to a subset of the child tasks, see `DataStream::rescale`
+    Rescale,
+    /// round-robin to all the child tasks, see `DataStream::rebalance`
+    ReBalance,
+    /// randomly to all the child tasks, see `DataStream::shuffle`
+    Shuffle,
+    /// every record is replicated to all the child tasks, see `DataStream::broadcast`
+    Broadcast,
+    /// by the user `Partitioner`, see `DataStream::partition_custom`
+    Custom,
+}
+
+/// Select the partition of the child job's tasks for each record,
+/// see `DataStream::partition_custom`
+pub trait Partitioner
+where
+    Self: Function,
+{
+    fn open(&mut self, context: &Context) -> crate::api::Result<()>;
+    /// Return the partition of the record in `0..num_partitions`.
+    fn partition(&mut self, record: &mut Record, num_partitions: u16) -> u16;
+    fn close(&mut self) -> crate::api::Result<()>;
+}
+
 pub trait ReduceFunction
 where
     Self: Function,
@@ -180,6 +270,47 @@ where
     fn open(&mut self, context: &Context) -> crate::api::Result<()>;
     ///
     fn reduce(&self, value: Option<&mut Record>, record: &mut Record) -> Record;
+    /// Merge two reduced values into one, called when the merging windows(eg: session windows)
+    /// are merged
+    fn merge(&self, value: &mut Record, other: &mut Record) -> Record;
+    fn close(&mut self) -> crate::api::Result<()>;
+}
+
+/// Aggregate the records of a window into an accumulator, the accumulator is kept in the
+/// window state and may have a different shape from both the input and the output records.
+pub trait AggregateFunction
+where
+    Self: Function,
+{
+    fn open(&mut self, context: &Context) -> crate::api::Result<()>;
+    /// Create a new accumulator for the first record of a key in the window
+    fn create_accumulator(&self) -> Record;
+    /// Add the `record` into the `accumulator`
+    fn add(&self, accumulator: &mut Record, record: &mut Record);
+    /// Merge the `other` accumulator into the `accumulator`,
+    /// called when the merging windows(eg: session windows) are merged
+    fn merge(&self, accumulator: &mut Record, other: &mut Record);
+    /// Get the result from the `accumulator`, only called when the window fires
+    fn get_result(&self, accumulator: Record) -> Record;
+    fn close(&mut self) -> crate::api::Result<()>;
+}
+
+/// Process all the records of a key in a window when the window fires
+pub trait ProcessWindowFunction
+where
+    Self: Function,
+{
+    fn open(&mut self, context: &Context) -> crate::api::Result<()>;
+    /// Called for each key of the fired `window`, `elements` are the buffered records
+    /// of the `key` in the window, or the pre-aggregated record if a `ReduceFunction` is given.
+    ///
+    /// The records tagged by `Record::set_output_tag` are emitted to the side output.
+    fn process(
+        &mut self,
+        key: &Record,
+        window: &TimeWindow,
+        elements: Box<dyn Iterator<Item = Record>>,
+    ) -> Box<dyn Iterator<Item = Record>>;
     fn close(&mut self) -> crate::api::Result<()>;
 }
 
@@ -191,11 +322,76 @@ where
     /// This method is called for each element in the first of the connected streams.
     ///
     /// `stream_seq` is the `DataStream` index
+    ///
+    /// The records tagged by `Record::set_output_tag` are emitted to the side output.
     fn process_left(&self, record: Record) -> Box<dyn Iterator<Item = Record>>;
     fn process_right(&self, stream_seq: usize, record: Record) -> Box<dyn Iterator<Item = Record>>;
     fn close(&mut self) -> crate::api::Result<()>;
 }
 
+/// Process the records of a `DataStream` connected with a `BroadcastStream`,
+/// the broadcast stream updates the replicated map states read by the data stream.
+pub trait BroadcastProcessFunction
+where
+    Self: Function,
+{
+    fn open(&mut self, context: &Context) -> crate::api::Result<()>;
+    /// This method is called for each record of the data stream, the broadcast states are read-only.
+    ///
+    /// The records tagged by `Record::set_output_tag` are emitted to the side output.
+    fn process_element(
+        &mut self,
+        record: Record,
+        context: &ReadOnlyBroadcastContext,
+    ) -> Box<dyn Iterator<Item = Record>>;
+    /// This method is called for each record of the broadcast stream in all parallel instances,
+    /// so the broadcast states must be updated by the record only to keep them consistent.
+    fn process_broadcast_element(
+        &mut self,
+        record: Record,
+        context: &mut BroadcastContext,
+    ) -> Box<dyn Iterator<Item = Record>>;
+    fn close(&mut self) -> crate::api::Result<()>;
+}
+
+/// Process the records of a `KeyedStream` with the per-key states and timers,
+/// the states and timers accessed by `KeyedProcessContext` are scoped to the current key.
+pub trait KeyedProcessFunction
+where
+    Self: Function,
+{
+    fn open(&mut self, context: &Context) -> crate::api::Result<()>;
+    /// This method is called for each record of the keyed stream.
+    ///
+    /// The records tagged by `Record::set_output_tag` are emitted to the side output.
+    fn process_element(
+        &mut self,
+        record: Record,
+        context: &mut KeyedProcessContext,
+    ) -> Box<dyn Iterator<Item = Record>>;
+    /// Called when a timer registered by `TimerService` fires, `timestamp` is the timer's time
+    fn on_timer(
+        &mut self,
+        _timestamp: u64,
+        _time_domain: TimeDomain,
+        _context: &mut KeyedProcessContext,
+    ) -> Box<dyn Iterator<Item = Record>> {
+        Box::new(std::iter::empty())
+    }
+    fn close(&mut self) -> crate::api::Result<()>;
+}
+
+/// Join the records of two keyed streams with the same key
+pub trait JoinFunction
+where
+    Self: Function,
+{
+    fn open(&mut self, context: &Context) -> crate::api::Result<()>;
+    /// This method is called for each pair of the joined records.
+    fn join(&self, left: &mut Record, right: &mut Record) -> Record;
+    fn close(&mut self) -> crate::api::Result<()>;
+}
+
 pub(crate) struct ElementIterator<T>
 where
     T: Iterator<Item = Record>,
@@ -7,8 +7,12 @@ pub mod env;
 pub mod error;
 pub mod function;
 pub mod operator;
+pub mod output_tag;
 pub mod properties;
 pub mod runtime;
+pub mod schema;
+pub mod state;
+pub mod trigger;
 pub mod watermark;
 pub mod window;
 
@@ -1,11 +1,14 @@
 use std::fmt::Debug;
 
 use crate::api::function::{
-    CoProcessFunction, FilterFunction, FlatMapFunction, Function, InputFormat, KeySelectorFunction,
-    OutputFormat, ReduceFunction,
+    AggregateFunction, AsyncFunction, AsyncOptions, BroadcastProcessFunction, CoProcessFunction,
+    Distribution, FilterFunction, FlatMapFunction, Function, InputFormat, KeySelectorFunction,
+    KeyedProcessFunction, OutputFormat, Partitioner,
 };
+use crate::api::schema::Schema;
+use crate::api::state::MapStateDescriptor;
 use crate::api::watermark::WatermarkAssigner;
-use crate::api::window::WindowAssigner;
+use crate::api::window::{WindowAssigner, WindowOptions};
 
 pub const DEFAULT_PARALLELISM: u16 = 0;
 
@@ -19,6 +22,8 @@ pub trait TStreamOperator: Debug {
     fn get_operator_name(&self) -> &str;
     fn get_parallelism(&self) -> u16;
     fn get_fn_creator(&self) -> FunctionCreator;
+    fn get_output_schema(&self, input_schema: &Schema) -> Schema;
+    fn get_input_schema(&self) -> Schema;
 }
 
 pub struct DefaultStreamOperator<T>
@@ -58,6 +63,14 @@ where
     fn get_fn_creator(&self) -> FunctionCreator {
         self.fn_creator.clone()
     }
+
+    fn get_output_schema(&self, input_schema: &Schema) -> Schema {
+        self.operator_fn.get_output_schema(input_schema)
+    }
+
+    fn get_input_schema(&self) -> Schema {
+        self.operator_fn.get_input_schema()
+    }
 }
 
 impl<T> Debug for DefaultStreamOperator<T>
@@ -78,9 +91,17 @@ pub enum StreamOperator {
     StreamSource(DefaultStreamOperator<dyn InputFormat>),
     StreamFlatMap(DefaultStreamOperator<dyn FlatMapFunction>),
     StreamFilter(DefaultStreamOperator<dyn FilterFunction>),
+    StreamAsyncMap(DefaultStreamOperator<dyn AsyncFunction>, AsyncOptions),
     StreamCoProcess(DefaultStreamOperator<dyn CoProcessFunction>),
+    StreamBroadcastProcess(
+        DefaultStreamOperator<dyn BroadcastProcessFunction>,
+        Vec<MapStateDescriptor>,
+    ),
     StreamKeyBy(DefaultStreamOperator<dyn KeySelectorFunction>),
-    StreamReduce(DefaultStreamOperator<dyn ReduceFunction>),
+    StreamPartition(DefaultStreamOperator<dyn Partitioner>, Distribution),
+    /// the window aggregation, the `ReduceFunction` runs as an `AggregateFunction`
+    StreamReduce(DefaultStreamOperator<dyn AggregateFunction>, WindowOptions),
+    StreamKeyedProcess(DefaultStreamOperator<dyn KeyedProcessFunction>),
     StreamWatermarkAssigner(DefaultStreamOperator<dyn WatermarkAssigner>),
     StreamWindowAssigner(DefaultStreamOperator<dyn WindowAssigner>),
     StreamSink(DefaultStreamOperator<dyn OutputFormat>),
@@ -108,21 +129,63 @@ impl StreamOperator {
         StreamOperator::StreamFilter(operator)
     }
 
-    pub fn new_co_process(co_process_fn: Box<dyn CoProcessFunction>) -> Self {
+    pub fn new_async_map(async_fn: Box<dyn AsyncFunction>, async_options: AsyncOptions) -> Self {
         let operator =
-            DefaultStreamOperator::new(DEFAULT_PARALLELISM, FunctionCreator::User, co_process_fn);
+            DefaultStreamOperator::new(DEFAULT_PARALLELISM, FunctionCreator::User, async_fn);
+        StreamOperator::StreamAsyncMap(operator, async_options)
+    }
+
+    pub fn new_co_process(
+        fn_creator: FunctionCreator,
+        co_process_fn: Box<dyn CoProcessFunction>,
+    ) -> Self {
+        let operator = DefaultStreamOperator::new(DEFAULT_PARALLELISM, fn_creator, co_process_fn);
         StreamOperator::StreamCoProcess(operator)
     }
 
+    pub fn new_broadcast_process(
+        broadcast_process_fn: Box<dyn BroadcastProcessFunction>,
+        descriptors: Vec<MapStateDescriptor>,
+    ) -> Self {
+        let operator = DefaultStreamOperator::new(
+            DEFAULT_PARALLELISM,
+            FunctionCreator::User,
+            broadcast_process_fn,
+        );
+        StreamOperator::StreamBroadcastProcess(operator, descriptors)
+    }
+
     pub fn new_key_by(key_by_fn: Box<dyn KeySelectorFunction>) -> Self {
         let operator =
             DefaultStreamOperator::new(DEFAULT_PARALLELISM, FunctionCreator::User, key_by_fn);
         StreamOperator::StreamKeyBy(operator)
     }
 
-    pub fn new_reduce(parallelism: u16, reduce_fn: Box<dyn ReduceFunction>) -> Self {
-        let operator = DefaultStreamOperator::new(parallelism, FunctionCreator::User, reduce_fn);
-        StreamOperator::StreamReduce(operator)
+    pub fn new_partition(
+        fn_creator: FunctionCreator,
+        partitioner: Box<dyn Partitioner>,
+        distribution: Distribution,
+    ) -> Self {
+        let operator = DefaultStreamOperator::new(DEFAULT_PARALLELISM, fn_creator, partitioner);
+        StreamOperator::StreamPartition(operator, distribution)
+    }
+
+    pub fn new_reduce(
+        parallelism: u16,
+        aggregate_fn: Box<dyn AggregateFunction>,
+        window_options: WindowOptions,
+    ) -> Self {
+        let operator = DefaultStreamOperator::new(parallelism, FunctionCreator::User, aggregate_fn);
+        StreamOperator::StreamReduce(operator, window_options)
+    }
+
+    pub fn new_keyed_process(
+        parallelism: u16,
+        fn_creator: FunctionCreator,
+        keyed_process_fn: Box<dyn KeyedProcessFunction>,
+    ) -> Self {
+        let operator = DefaultStreamOperator::new(parallelism, fn_creator, keyed_process_fn);
+        StreamOperator::StreamKeyedProcess(operator)
     }
 
     pub fn new_watermark_assigner(watermark_assigner: Box<dyn WatermarkAssigner>) -> Self {
@@ -166,8 +229,30 @@ impl StreamOperator {
         false
     }
 
+    pub fn is_partition(&self) -> bool {
+        if let StreamOperator::StreamPartition(_stream_partition, _distribution) = self {
+            return true;
+        }
+        false
+    }
+
+    /// the distribution of the partition operator, `None` for the other operators
+    pub fn get_distribution(&self) -> Option<Distribution> {
+        match self {
+            StreamOperator::StreamPartition(_stream_partition, distribution) => Some(*distribution),
+            _ => None,
+        }
+    }
+
     pub fn is_reduce(&self) -> bool {
-        if let StreamOperator::StreamReduce(_stream_reduce) = self {
+        if let StreamOperator::StreamReduce(_stream_reduce, _window_options) = self {
+            return true;
+        }
+        false
+    }
+
+    pub fn is_keyed_process(&self) -> bool {
+        if let StreamOperator::StreamKeyedProcess(_stream_keyed_process) = self {
             return true;
         }
         false
@@ -194,6 +279,22 @@ impl StreamOperator {
         false
     }
 
+    pub fn is_broadcast_process(&self) -> bool {
+        if let StreamOperator::StreamBroadcastProcess(_stream_broadcast_process, _descriptors) =
+            self
+        {
+            return true;
+        }
+        false
+    }
+
+    pub fn is_async_map(&self) -> bool {
+        if let StreamOperator::StreamAsyncMap(_stream_async_map, _async_options) = self {
+            return true;
+        }
+        false
+    }
+
     pub fn is_connect(&self) -> bool {
         if let StreamOperator::StreamCoProcess(_stream_source) = self {
             return true;
@@ -208,9 +309,13 @@ impl TStreamOperator for StreamOperator {
             StreamOperator::StreamSource(op) => op.get_operator_name(),
             StreamOperator::StreamFlatMap(op) => op.get_operator_name(),
             StreamOperator::StreamFilter(op) => op.get_operator_name(),
+            StreamOperator::StreamAsyncMap(op, _) => op.get_operator_name(),
             StreamOperator::StreamCoProcess(op) => op.get_operator_name(),
+            StreamOperator::StreamBroadcastProcess(op, _) => op.get_operator_name(),
             StreamOperator::StreamKeyBy(op) => op.get_operator_name(),
-            StreamOperator::StreamReduce(op) => op.get_operator_name(),
+            StreamOperator::StreamPartition(op, _) => op.get_operator_name(),
+            StreamOperator::StreamReduce(op, _) => op.get_operator_name(),
+            StreamOperator::StreamKeyedProcess(op) => op.get_operator_name(),
             StreamOperator::StreamWatermarkAssigner(op) => op.get_operator_name(),
             StreamOperator::StreamWindowAssigner(op) => op.get_operator_name(),
             StreamOperator::StreamSink(op) => op.get_operator_name(),
@@ -222,9 +327,13 @@ impl TStreamOperator for StreamOperator {
             StreamOperator::StreamSource(op) => op.get_parallelism(),
             StreamOperator::StreamFlatMap(op) => op.get_parallelism(),
             StreamOperator::StreamFilter(op) => op.get_parallelism(),
+            StreamOperator::StreamAsyncMap(op, _) => op.get_parallelism(),
             StreamOperator::StreamCoProcess(op) => op.get_parallelism(),
+            StreamOperator::StreamBroadcastProcess(op, _) => op.get_parallelism(),
             StreamOperator::StreamKeyBy(op) => op.get_parallelism(),
-            StreamOperator::StreamReduce(op) => op.get_parallelism(),
+            StreamOperator::StreamPartition(op, _) => op.get_parallelism(),
+            StreamOperator::StreamReduce(op, _) => op.get_parallelism(),
+            StreamOperator::StreamKeyedProcess(op) => op.get_parallelism(),
             StreamOperator::StreamWatermarkAssigner(op) => op.get_parallelism(),
             StreamOperator::StreamWindowAssigner(op) => op.get_parallelism(),
             StreamOperator::StreamSink(op) => op.get_parallelism(),
@@ -236,12 +345,52 @@ impl TStreamOperator for StreamOperator {
             StreamOperator::StreamSource(op) => op.get_fn_creator(),
             StreamOperator::StreamFlatMap(op) => op.get_fn_creator(),
             StreamOperator::StreamFilter(op) => op.get_fn_creator(),
+            StreamOperator::StreamAsyncMap(op, _) => op.get_fn_creator(),
             StreamOperator::StreamCoProcess(op) => op.get_fn_creator(),
+            StreamOperator::StreamBroadcastProcess(op, _) => op.get_fn_creator(),
             StreamOperator::StreamKeyBy(op) => op.get_fn_creator(),
-            StreamOperator::StreamReduce(op) => op.get_fn_creator(),
+            StreamOperator::StreamPartition(op, _) => op.get_fn_creator(),
+            StreamOperator::StreamReduce(op, _) => op.get_fn_creator(),
+            StreamOperator::StreamKeyedProcess(op) => op.get_fn_creator(),
             StreamOperator::StreamWatermarkAssigner(op) => op.get_fn_creator(),
             StreamOperator::StreamWindowAssigner(op) => op.get_fn_creator(),
             StreamOperator::StreamSink(op) => op.get_fn_creator(),
         }
     }
+
+    fn get_output_schema(&self, input_schema: &Schema) -> Schema {
+        match self {
+            StreamOperator::StreamSource(op) => op.get_output_schema(input_schema),
+            StreamOperator::StreamFlatMap(op) => op.get_output_schema(input_schema),
+            StreamOperator::StreamFilter(op) => op.get_output_schema(input_schema),
+            StreamOperator::StreamAsyncMap(op, _) => op.get_output_schema(input_schema),
+            StreamOperator::StreamCoProcess(op) => op.get_output_schema(input_schema),
+            StreamOperator::StreamBroadcastProcess(op, _) => op.get_output_schema(input_schema),
+            StreamOperator::StreamKeyBy(op) => op.get_output_schema(input_schema),
+            StreamOperator::StreamPartition(op, _) => op.get_output_schema(input_schema),
+            StreamOperator::StreamReduce(op, _) => op.get_output_schema(input_schema),
+            StreamOperator::StreamKeyedProcess(op) => op.get_output_schema(input_schema),
+            StreamOperator::StreamWatermarkAssigner(op) => op.get_output_schema(input_schema),
+            StreamOperator::StreamWindowAssigner(op) => op.get_output_schema(input_schema),
+            StreamOperator::StreamSink(op) => op.get_output_schema(input_schema),
+        }
+    }
+
+    fn get_input_schema(&self) -> Schema {
+        match self {
+            StreamOperator::StreamSource(op) => op.get_input_schema(),
+            StreamOperator::StreamFlatMap(op) => op.get_input_schema(),
+            StreamOperator::StreamFilter(op) => op.get_input_schema(),
+            StreamOperator::StreamAsyncMap(op, _) => op.get_input_schema(),
+            StreamOperator::StreamCoProcess(op) => op.get_input_schema(),
+            StreamOperator::StreamBroadcastProcess(op, _) => op.get_input_schema(),
+            StreamOperator::StreamKeyBy(op) => op.get_input_schema(),
+            StreamOperator::StreamPartition(op, _) => op.get_input_schema(),
+            StreamOperator::StreamReduce(op, _) => op.get_input_schema(),
+            StreamOperator::StreamKeyedProcess(op) => op.get_input_schema(),
+            StreamOperator::StreamWatermarkAssigner(op) => op.get_input_schema(),
+            StreamOperator::StreamWindowAssigner(op) => op.get_input_schema(),
+            StreamOperator::StreamSink(op) => op.get_input_schema(),
+        }
+    }
 }
@@ -0,0 +1,19 @@
+/// Identify a side output of an operator.
+/// The records tagged with an `OutputTag` are routed to the `DataStream` created by
+/// `get_side_output` with the same tag instead of the main output.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct OutputTag {
+    tag_id: String,
+}
+
+impl OutputTag {
+    pub fn new(tag_id: &str) -> Self {
+        OutputTag {
+            tag_id: tag_id.to_string(),
+        }
+    }
+
+    pub fn get_tag_id(&self) -> &str {
+        self.tag_id.as_str()
+    }
+}
@@ -0,0 +1,245 @@
+use std::fmt::{Display, Formatter};
+
+use crate::api::element::types;
+use crate::api::Error;
+
+/// A named and typed field of the `Record`
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Field {
+    name: String,
+    /// the `types::*` of the field
+    data_type: u8,
+    nullable: bool,
+}
+
+impl Field {
+    pub fn new(name: &str, data_type: u8, nullable: bool) -> Self {
+        Field {
+            name: name.to_string(),
+            data_type,
+            nullable,
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn get_data_type(&self) -> u8 {
+        self.data_type
+    }
+
+    pub fn is_nullable(&self) -> bool {
+        self.nullable
+    }
+}
+
+/// The fields of the records emitted by an operator, in the order of the `Record` columns.
+///
+/// The schema is declared by the `Function::get_output_schema` of each operator
+/// and propagated along the stream graph, the empty schema stands for an undeclared one.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Schema {
+    fields: Vec<Field>,
+}
+
+impl Schema {
+    pub fn new(fields: Vec<Field>) -> Self {
+        Schema { fields }
+    }
+
+    pub fn empty() -> Self {
+        Schema { fields: Vec::new() }
+    }
+
+    /// the non-null fields named by their indexes, eg: `f0`, `f1`
+    pub fn from_types(field_types: &[u8]) -> Self {
+        let fields = field_types
+            .iter()
+            .enumerate()
+            .map(|(index, data_type)| {
+                let name = format!("f{}", index);
+                Field::new(name.as_str(), *data_type, false)
+            })
+            .collect();
+        Schema { fields }
+    }
+
+    /// the non-null fields, `field_names` and `field_types` are in the same order
+    pub fn from_names(field_names: &[&str], field_types: &[u8]) -> Self {
+        let fields = field_names
+            .iter()
+            .zip(field_types.iter())
+            .map(|(name, data_type)| Field::new(name, *data_type, false))
+            .collect();
+        Schema { fields }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn get_fields(&self) -> &[Field] {
+        self.fields.as_slice()
+    }
+
+    pub fn get_field(&self, index: usize) -> Option<&Field> {
+        self.fields.get(index)
+    }
+
+    /// the index of the field `name`
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.fields.iter().position(|field| field.name.eq(name))
+    }
+
+    /// the `types::*` of the fields, used by the `BufferReader` and `BufferWriter`
+    pub fn get_field_types(&self) -> Vec<u8> {
+        self.fields.iter().map(|field| field.data_type).collect()
+    }
+
+    /// the fields at the `indexes` in order, eg: the key fields.
+    /// return an error if some index is out of the fields
+    pub fn project(&self, indexes: &[usize]) -> crate::api::Result<Schema> {
+        let mut fields = Vec::with_capacity(indexes.len());
+        for index in indexes {
+            match self.fields.get(*index) {
+                Some(field) => fields.push(field.clone()),
+                None => {
+                    return Err(Error::msg(format!(
+                        "schema mismatch. the index {} is out of the schema {}",
+                        index, self
+                    )));
+                }
+            }
+        }
+        Ok(Schema { fields })
+    }
+
+    /// the fields of `other` appended to this schema, eg: the key with the reduced values
+    pub fn join(&self, other: &Schema) -> Schema {
+        let mut fields = self.fields.clone();
+        fields.extend_from_slice(other.fields.as_slice());
+        Schema { fields }
+    }
+
+    /// The records of this schema can be consumed by the `expected` one,
+    /// if the types are the same column by column and no nullable field
+    /// is consumed as a non-null one. The names are not checked.
+    pub fn is_compatible(&self, expected: &Schema) -> bool {
+        self.fields.len() == expected.fields.len()
+            && self
+                .fields
+                .iter()
+                .zip(expected.fields.iter())
+                .all(|(field, expected_field)| {
+                    field.data_type == expected_field.data_type
+                        && (!field.nullable || expected_field.nullable)
+                })
+    }
+}
+
+impl Display for Schema {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|field| {
+                let nullable = if field.nullable { "" } else { " NOT NULL" };
+                format!("{} {}{}", field.name, type_name(field.data_type), nullable)
+            })
+            .collect();
+        write!(f, "({})", fields.join(", "))
+    }
+}
+
+pub(crate) fn type_name(data_type: u8) -> &'static str {
+    match data_type {
+        types::BOOL => "BOOL",
+        types::I8 => "I8",
+        types::U8 => "U8",
+        types::I16 => "I16",
+        types::U16 => "U16",
+        types::I32 => "I32",
+        types::U32 => "U32",
+        types::I64 => "I64",
+        types::U64 => "U64",
+        types::F32 => "F32",
+        types::F64 => "F64",
+        types::BYTES => "BYTES",
+        _ => "UNKNOWN",
+    }
+}
+
+/// the `types::*` of the type name, eg: `I64`, the `STRING` is the alias of `BYTES`
+pub(crate) fn data_type_of(name: &str) -> Option<u8> {
+    let data_type = match name.to_uppercase().as_str() {
+        "BOOL" | "BOOLEAN" => types::BOOL,
+        "I8" => types::I8,
+        "U8" => types::U8,
+        "I16" => types::I16,
+        "U16" => types::U16,
+        "I32" => types::I32,
+        "U32" => types::U32,
+        "I64" => types::I64,
+        "U64" => types::U64,
+        "F32" => types::F32,
+        "F64" => types::F64,
+        "BYTES" | "STRING" => types::BYTES,
+        _ => return None,
+    };
+    Some(data_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::element::types;
+    use crate::api::schema::{Field, Schema};
+
+    #[test]
+    pub fn schema_compatible_test() {
+        let schema = Schema::from_names(&["name", "value"], &[types::BYTES, types::I64]);
+        assert_eq!(schema.index_of("value"), Some(1));
+        assert_eq!(schema.get_field_types(), vec![types::BYTES, types::I64]);
+
+        // the names are not checked
+        assert!(schema.is_compatible(&Schema::from_types(&[types::BYTES, types::I64])));
+        assert!(!schema.is_compatible(&Schema::from_types(&[types::BYTES, types::U64])));
+        assert!(!schema.is_compatible(&Schema::from_types(&[types::BYTES])));
+
+        let nullable = Schema::new(vec![
+            Field::new("name", types::BYTES, true),
+            Field::new("value", types::I64, false),
+        ]);
+        assert!(schema.is_compatible(&nullable));
+        assert!(!nullable.is_compatible(&schema));
+
+        let key_value = schema
+            .project(&[0])
+            .unwrap()
+            .join(&Schema::from_types(&[types::I64]));
+        assert_eq!(
+            key_value.to_string(),
+            "(name BYTES NOT NULL, f0 I64 NOT NULL)"
+        );
+    }
+
+    #[test]
+    pub fn schema_project_test() {
+        let schema = Schema::from_names(&["name", "value"], &[types::BYTES, types::I64]);
+        assert_eq!(
+            schema.project(&[1, 0]).unwrap().to_string(),
+            "(value I64 NOT NULL, name BYTES NOT NULL)"
+        );
+
+        let e = schema.project(&[0, 2]).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "schema mismatch. the index 2 is out of the schema \
+             (name BYTES NOT NULL, value I64 NOT NULL)"
+        );
+    }
+}
@@ -0,0 +1,764 @@
+use std::collections::hash_map::Iter;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use bytes::BytesMut;
+
+use crate::api::element::{Buffer, Record};
+
+/// The per-key states of a `KeyedProcessFunction`, the key is the record of `KeySelectorFunction`
+#[derive(Debug, Default)]
+pub(crate) struct KeyedStateStore {
+    value_states: HashMap<String, HashMap<Record, Record>>,
+    map_states: HashMap<String, HashMap<Record, HashMap<Record, Record>>>,
+    list_states: HashMap<String, HashMap<Record, Vec<Record>>>,
+}
+
+impl KeyedStateStore {
+    pub fn new() -> Self {
+        KeyedStateStore::default()
+    }
+
+    /// the list states are kept in memory only,
+    /// their records carry the fields not kept by `Record::values`, eg: the location windows
+    pub(crate) fn snapshot(&self) -> KeyedStateSnapshot {
+        let value_states = self
+            .value_states
+            .iter()
+            .map(|(name, values)| {
+                let entries = values
+                    .iter()
+                    .map(|(key, value)| (to_bytes(key), to_bytes(value)))
+                    .collect();
+                (name.clone(), entries)
+            })
+            .collect();
+        let map_states = self
+            .map_states
+            .iter()
+            .map(|(name, maps)| {
+                let entries = maps
+                    .iter()
+                    .map(|(key, map)| {
+                        let map_entries = map
+                            .iter()
+                            .map(|(map_key, value)| (to_bytes(map_key), to_bytes(value)))
+                            .collect();
+                        (to_bytes(key), map_entries)
+                    })
+                    .collect();
+                (name.clone(), entries)
+            })
+            .collect();
+        KeyedStateSnapshot {
+            value_states,
+            map_states,
+        }
+    }
+
+    pub(crate) fn restore(&mut self, snapshot: KeyedStateSnapshot) {
+        for (name, entries) in snapshot.value_states {
+            let values = entries
+                .into_iter()
+                .map(|(key, value)| (from_bytes(key), from_bytes(value)))
+                .collect();
+            self.value_states.insert(name, values);
+        }
+        for (name, entries) in snapshot.map_states {
+            let maps = entries
+                .into_iter()
+                .map(|(key, map_entries)| {
+                    let map = map_entries
+                        .into_iter()
+                        .map(|(map_key, value)| (from_bytes(map_key), from_bytes(value)))
+                        .collect();
+                    (from_bytes(key), map)
+                })
+                .collect();
+            self.map_states.insert(name, maps);
+        }
+    }
+}
+
+impl Default for KeyedStateStore {
+    fn default() -> Self {
+        KeyedStateStore::new()
+    }
+}
+
+/// the checkpoint of the `KeyedStateStore`, the records are kept as the bytes of `Record::values`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct KeyedStateSnapshot {
+    value_states: Vec<(String, Vec<(Vec<u8>, Vec<u8>)>)>,
+    map_states: Vec<(String, Vec<(Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>)>)>,
+}
+
+pub(crate) fn to_bytes(record: &Record) -> Vec<u8> {
+    record.values.as_slice().to_vec()
+}
+
+pub(crate) fn from_bytes(bytes: Vec<u8>) -> Record {
+    let mut record = Record::new();
+    record.values = Buffer::from(BytesMut::from(bytes.as_slice()));
+    record
+}
+
+/// A single value state scoped to the current key, see flink `ValueState`
+#[derive(Debug)]
+pub struct ValueState<'a> {
+    key: &'a Record,
+    values: &'a mut HashMap<Record, Record>,
+}
+
+impl<'a> ValueState<'a> {
+    pub fn value(&self) -> Option<&Record> {
+        self.values.get(self.key)
+    }
+
+    pub fn update(&mut self, value: Record) {
+        self.values.insert(self.key.clone(), value);
+    }
+
+    pub fn clear(&mut self) {
+        self.values.remove(self.key);
+    }
+}
+
+/// A key-value map state scoped to the current key, see flink `MapState`
+#[derive(Debug)]
+pub struct MapState<'a> {
+    key: &'a Record,
+    maps: &'a mut HashMap<Record, HashMap<Record, Record>>,
+}
+
+impl<'a> MapState<'a> {
+    pub fn get(&self, map_key: &Record) -> Option<&Record> {
+        self.maps.get(self.key).and_then(|map| map.get(map_key))
+    }
+
+    pub fn contains(&self, map_key: &Record) -> bool {
+        self.get(map_key).is_some()
+    }
+
+    pub fn put(&mut self, map_key: Record, value: Record) {
+        self.maps
+            .entry(self.key.clone())
+            .or_default()
+            .insert(map_key, value);
+    }
+
+    pub fn remove(&mut self, map_key: &Record) -> Option<Record> {
+        let (value, empty) = match self.maps.get_mut(self.key) {
+            Some(map) => (map.remove(map_key), map.is_empty()),
+            None => (None, false),
+        };
+
+        if empty {
+            self.maps.remove(self.key);
+        }
+        value
+    }
+
+    pub fn iter(&self) -> Option<Iter<Record, Record>> {
+        self.maps.get(self.key).map(|map| map.iter())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.maps.contains_key(self.key)
+    }
+
+    pub fn clear(&mut self) {
+        self.maps.remove(self.key);
+    }
+}
+
+/// An appending list state scoped to the current key, see flink `ListState`
+#[derive(Debug)]
+pub struct ListState<'a> {
+    key: &'a Record,
+    lists: &'a mut HashMap<Record, Vec<Record>>,
+}
+
+impl<'a> ListState<'a> {
+    pub fn get(&self) -> &[Record] {
+        self.lists
+            .get(self.key)
+            .map(|list| list.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub fn add(&mut self, value: Record) {
+        self.lists.entry(self.key.clone()).or_default().push(value);
+    }
+
+    pub fn update(&mut self, values: Vec<Record>) {
+        if values.is_empty() {
+            self.clear();
+        } else {
+            self.lists.insert(self.key.clone(), values);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.lists.remove(self.key);
+    }
+}
+
+/// The time domain of a fired timer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeDomain {
+    EventTime,
+    ProcessingTime,
+}
+
+/// Register the timers of the current key, a key has at most one timer at a given time.
+/// The event time timers are fired by the aligned watermark,
+/// the processing time timers are fired by the `WindowTimer`.
+#[derive(Debug)]
+pub struct TimerService {
+    current_key: Record,
+    pub(crate) current_watermark: u64,
+    pub(crate) current_processing_time: u64,
+
+    event_time_timers: BTreeMap<u64, HashSet<Record>>,
+    processing_time_timers: BTreeMap<u64, HashSet<Record>>,
+}
+
+impl TimerService {
+    pub(crate) fn new() -> Self {
+        TimerService {
+            current_key: Record::new(),
+            current_watermark: 0,
+            current_processing_time: 0,
+            event_time_timers: BTreeMap::new(),
+            processing_time_timers: BTreeMap::new(),
+        }
+    }
+
+    pub fn get_current_watermark(&self) -> u64 {
+        self.current_watermark
+    }
+
+    pub fn get_current_processing_time(&self) -> u64 {
+        self.current_processing_time
+    }
+
+    pub fn register_event_time_timer(&mut self, time: u64) {
+        register_timer(&mut self.event_time_timers, &self.current_key, time);
+    }
+
+    pub fn delete_event_time_timer(&mut self, time: u64) {
+        delete_timer(&mut self.event_time_timers, &self.current_key, time);
+    }
+
+    pub fn register_processing_time_timer(&mut self, time: u64) {
+        register_timer(&mut self.processing_time_timers, &self.current_key, time);
+    }
+
+    pub fn delete_processing_time_timer(&mut self, time: u64) {
+        delete_timer(&mut self.processing_time_timers, &self.current_key, time);
+    }
+
+    pub(crate) fn set_current_key(&mut self, key: Record) {
+        self.current_key = key;
+    }
+
+    /// remove and return the event time timers that `timer <= time`, ordered by timer
+    pub(crate) fn poll_event_time_timers(&mut self, time: u64) -> Vec<(u64, Record)> {
+        poll_timers(&mut self.event_time_timers, time)
+    }
+
+    /// remove and return the processing time timers that `timer <= time`, ordered by timer
+    pub(crate) fn poll_processing_time_timers(&mut self, time: u64) -> Vec<(u64, Record)> {
+        poll_timers(&mut self.processing_time_timers, time)
+    }
+
+    pub(crate) fn snapshot(&self) -> TimerSnapshot {
+        TimerSnapshot {
+            current_watermark: self.current_watermark,
+            event_time_timers: snapshot_timers(&self.event_time_timers),
+            processing_time_timers: snapshot_timers(&self.processing_time_timers),
+        }
+    }
+
+    pub(crate) fn restore(&mut self, snapshot: TimerSnapshot) {
+        self.current_watermark = snapshot.current_watermark;
+        restore_timers(&mut self.event_time_timers, snapshot.event_time_timers);
+        restore_timers(
+            &mut self.processing_time_timers,
+            snapshot.processing_time_timers,
+        );
+    }
+}
+
+/// the checkpoint of the `TimerService`, the keys are kept as the bytes of `Record::values`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct TimerSnapshot {
+    current_watermark: u64,
+    event_time_timers: Vec<(u64, Vec<u8>)>,
+    processing_time_timers: Vec<(u64, Vec<u8>)>,
+}
+
+fn register_timer(timers: &mut BTreeMap<u64, HashSet<Record>>, key: &Record, time: u64) {
+    let keys = timers.entry(time).or_default();
+    if !keys.contains(key) {
+        keys.insert(key.clone());
+    }
+}
+
+fn delete_timer(timers: &mut BTreeMap<u64, HashSet<Record>>, key: &Record, time: u64) {
+    let empty = match timers.get_mut(&time) {
+        Some(keys) => {
+            keys.remove(key);
+            keys.is_empty()
+        }
+        None => false,
+    };
+
+    if empty {
+        timers.remove(&time);
+    }
+}
+
+fn poll_timers(timers: &mut BTreeMap<u64, HashSet<Record>>, time: u64) -> Vec<(u64, Record)> {
+    let pending_timers = timers.split_off(&(time + 1));
+    let expired_timers = std::mem::replace(timers, pending_timers);
+
+    let mut expired = Vec::new();
+    for (timer, keys) in expired_timers {
+        for key in keys {
+            expired.push((timer, key));
+        }
+    }
+
+    expired
+}
+
+fn snapshot_timers(timers: &BTreeMap<u64, HashSet<Record>>) -> Vec<(u64, Vec<u8>)> {
+    timers
+        .iter()
+        .flat_map(|(timer, keys)| {
+            keys.iter()
+                .map(move |key| (*timer, key.values.as_slice().to_vec()))
+        })
+        .collect()
+}
+
+fn restore_timers(timers: &mut BTreeMap<u64, HashSet<Record>>, snapshot: Vec<(u64, Vec<u8>)>) {
+    for (timer, key) in snapshot {
+        let mut record = Record::new();
+        record.values = Buffer::from(BytesMut::from(key.as_slice()));
+        register_timer(timers, &record, timer);
+    }
+}
+
+/// The context of `KeyedProcessFunction`, access the states and timers of the current key
+#[derive(Debug)]
+pub struct KeyedProcessContext<'a> {
+    timestamp: u64,
+    stream_seq: usize,
+    state_store: &'a mut KeyedStateStore,
+    timer_service: &'a mut TimerService,
+}
+
+impl<'a> KeyedProcessContext<'a> {
+    pub(crate) fn new(
+        timestamp: u64,
+        stream_seq: usize,
+        state_store: &'a mut KeyedStateStore,
+        timer_service: &'a mut TimerService,
+    ) -> Self {
+        KeyedProcessContext {
+            timestamp,
+            stream_seq,
+            state_store,
+            timer_service,
+        }
+    }
+
+    /// the timestamp of the processing record or the firing timer
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// the index of the keyed stream that the record comes from, eg: the left side of a join is 0
+    pub(crate) fn get_stream_seq(&self) -> usize {
+        self.stream_seq
+    }
+
+    pub fn get_current_key(&self) -> &Record {
+        &self.timer_service.current_key
+    }
+
+    pub fn timer_service(&mut self) -> &mut TimerService {
+        self.timer_service
+    }
+
+    pub fn get_value_state(&mut self, name: &str) -> ValueState {
+        let values = self
+            .state_store
+            .value_states
+            .entry(name.to_string())
+            .or_default();
+        ValueState {
+            key: &self.timer_service.current_key,
+            values,
+        }
+    }
+
+    pub fn get_map_state(&mut self, name: &str) -> MapState {
+        let maps = self
+            .state_store
+            .map_states
+            .entry(name.to_string())
+            .or_default();
+        MapState {
+            key: &self.timer_service.current_key,
+            maps,
+        }
+    }
+
+    pub fn get_list_state(&mut self, name: &str) -> ListState {
+        let lists = self
+            .state_store
+            .list_states
+            .entry(name.to_string())
+            .or_default();
+        ListState {
+            key: &self.timer_service.current_key,
+            lists,
+        }
+    }
+}
+
+/// Describe a broadcast map state, the state is declared by `DataStream::broadcast`
+#[derive(Clone, Debug, PartialEq)]
+pub struct MapStateDescriptor {
+    name: String,
+}
+
+impl MapStateDescriptor {
+    pub fn new(name: &str) -> Self {
+        MapStateDescriptor {
+            name: name.to_string(),
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+/// The map states replicated to all the parallel instances of a `BroadcastProcessFunction`,
+/// only the broadcast stream can update them, so all the instances keep the same states.
+#[derive(Debug, Default)]
+pub(crate) struct BroadcastStateStore {
+    map_states: HashMap<String, HashMap<Record, Record>>,
+}
+
+impl BroadcastStateStore {
+    pub fn new(descriptors: &[MapStateDescriptor]) -> Self {
+        let mut map_states = HashMap::new();
+        for descriptor in descriptors {
+            map_states.insert(descriptor.name.clone(), HashMap::new());
+        }
+        BroadcastStateStore { map_states }
+    }
+
+    fn get_map_state(&self, descriptor: &MapStateDescriptor) -> &HashMap<Record, Record> {
+        self.map_states.get(descriptor.get_name()).expect(
+            format!(
+                "the broadcast state `{}` is not declared by `DataStream::broadcast`",
+                descriptor.get_name()
+            )
+            .as_str(),
+        )
+    }
+
+    fn get_map_state_mut(
+        &mut self,
+        descriptor: &MapStateDescriptor,
+    ) -> &mut HashMap<Record, Record> {
+        self.map_states.get_mut(descriptor.get_name()).expect(
+            format!(
+                "the broadcast state `{}` is not declared by `DataStream::broadcast`",
+                descriptor.get_name()
+            )
+            .as_str(),
+        )
+    }
+
+    pub(crate) fn snapshot(&self) -> BroadcastStateSnapshot {
+        let map_states = self
+            .map_states
+            .iter()
+            .map(|(name, map)| {
+                let entries = map
+                    .iter()
+                    .map(|(key, value)| {
+                        (
+                            key.values.as_slice().to_vec(),
+                            value.values.as_slice().to_vec(),
+                        )
+                    })
+                    .collect();
+                (name.clone(), entries)
+            })
+            .collect();
+        BroadcastStateSnapshot { map_states }
+    }
+
+    pub(crate) fn restore(&mut self, snapshot: BroadcastStateSnapshot) {
+        for (name, entries) in snapshot.map_states {
+            // the states no longer declared are dropped
+            if let Some(map) = self.map_states.get_mut(&name) {
+                map.clear();
+                for (key, value) in entries {
+                    let mut key_record = Record::new();
+                    key_record.values = Buffer::from(BytesMut::from(key.as_slice()));
+                    let mut value_record = Record::new();
+                    value_record.values = Buffer::from(BytesMut::from(value.as_slice()));
+                    map.insert(key_record, value_record);
+                }
+            }
+        }
+    }
+}
+
+/// the checkpoint of the `BroadcastStateStore`, the records are kept as the bytes of `Record::values`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct BroadcastStateSnapshot {
+    map_states: Vec<(String, Vec<(Vec<u8>, Vec<u8>)>)>,
+}
+
+/// The read-write broadcast map state, updated by the broadcast stream only
+#[derive(Debug)]
+pub struct BroadcastState<'a> {
+    map: &'a mut HashMap<Record, Record>,
+}
+
+impl<'a> BroadcastState<'a> {
+    pub fn get(&self, key: &Record) -> Option<&Record> {
+        self.map.get(key)
+    }
+
+    pub fn contains(&self, key: &Record) -> bool {
+        self.map.contains_key(key)
+    }
+
+    pub fn put(&mut self, key: Record, value: Record) {
+        self.map.insert(key, value);
+    }
+
+    pub fn remove(&mut self, key: &Record) -> Option<Record> {
+        self.map.remove(key)
+    }
+
+    pub fn iter(&self) -> Iter<Record, Record> {
+        self.map.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+}
+
+/// The read-only view of the broadcast map state for the data stream
+#[derive(Debug)]
+pub struct ReadOnlyBroadcastState<'a> {
+    map: &'a HashMap<Record, Record>,
+}
+
+impl<'a> ReadOnlyBroadcastState<'a> {
+    pub fn get(&self, key: &Record) -> Option<&Record> {
+        self.map.get(key)
+    }
+
+    pub fn contains(&self, key: &Record) -> bool {
+        self.map.contains_key(key)
+    }
+
+    pub fn iter(&self) -> Iter<Record, Record> {
+        self.map.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+/// The context of `BroadcastProcessFunction::process_broadcast_element`
+#[derive(Debug)]
+pub struct BroadcastContext<'a> {
+    timestamp: u64,
+    state_store: &'a mut BroadcastStateStore,
+}
+
+impl<'a> BroadcastContext<'a> {
+    pub(crate) fn new(timestamp: u64, state_store: &'a mut BroadcastStateStore) -> Self {
+        BroadcastContext {
+            timestamp,
+            state_store,
+        }
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    pub fn get_broadcast_state(&mut self, descriptor: &MapStateDescriptor) -> BroadcastState {
+        BroadcastState {
+            map: self.state_store.get_map_state_mut(descriptor),
+        }
+    }
+}
+
+/// The context of `BroadcastProcessFunction::process_element`
+#[derive(Debug)]
+pub struct ReadOnlyBroadcastContext<'a> {
+    timestamp: u64,
+    state_store: &'a BroadcastStateStore,
+}
+
+impl<'a> ReadOnlyBroadcastContext<'a> {
+    pub(crate) fn new(timestamp: u64, state_store: &'a BroadcastStateStore) -> Self {
+        ReadOnlyBroadcastContext {
+            timestamp,
+            state_store,
+        }
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    pub fn get_broadcast_state(&self, descriptor: &MapStateDescriptor) -> ReadOnlyBroadcastState {
+        ReadOnlyBroadcastState {
+            map: self.state_store.get_map_state(descriptor),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+
+    use crate::api::element::{Buffer, Record};
+    use crate::api::state::{
+        BroadcastContext, BroadcastStateStore, KeyedProcessContext, KeyedStateStore,
+        MapStateDescriptor, ReadOnlyBroadcastContext, TimerService,
+    };
+
+    fn key(k: u8) -> Record {
+        let mut record = Record::new();
+        record.values = Buffer::from(BytesMut::from(&[k][..]));
+        record
+    }
+
+    #[test]
+    pub fn keyed_state_scope_test() {
+        let mut state_store = KeyedStateStore::new();
+        let mut timer_service = TimerService::new();
+
+        timer_service.set_current_key(key(1));
+        {
+            let mut ctx = KeyedProcessContext::new(0, 0, &mut state_store, &mut timer_service);
+            ctx.get_value_state("v").update(key(10));
+            ctx.get_list_state("l").add(key(11));
+            ctx.get_map_state("m").put(key(12), key(13));
+        }
+
+        timer_service.set_current_key(key(2));
+        {
+            let mut ctx = KeyedProcessContext::new(0, 0, &mut state_store, &mut timer_service);
+            assert!(ctx.get_value_state("v").value().is_none());
+            assert_eq!(ctx.get_list_state("l").get().len(), 0);
+            assert!(ctx.get_map_state("m").is_empty());
+        }
+
+        timer_service.set_current_key(key(1));
+        {
+            let mut ctx = KeyedProcessContext::new(0, 0, &mut state_store, &mut timer_service);
+            assert_eq!(ctx.get_value_state("v").value(), Some(&key(10)));
+            assert_eq!(ctx.get_list_state("l").get(), &[key(11)][..]);
+            assert_eq!(ctx.get_map_state("m").remove(&key(12)), Some(key(13)));
+            assert!(ctx.get_map_state("m").is_empty());
+        }
+    }
+
+    #[test]
+    pub fn timer_service_test() {
+        let mut timer_service = TimerService::new();
+
+        timer_service.set_current_key(key(1));
+        timer_service.register_event_time_timer(1000);
+        timer_service.register_event_time_timer(1000);
+        timer_service.register_event_time_timer(3000);
+        timer_service.set_current_key(key(2));
+        timer_service.register_event_time_timer(2000);
+        timer_service.register_event_time_timer(3000);
+        timer_service.delete_event_time_timer(3000);
+
+        let mut restored = TimerService::new();
+        restored.restore(timer_service.snapshot());
+
+        for timer_service in vec![&mut timer_service, &mut restored] {
+            assert_eq!(
+                timer_service.poll_event_time_timers(2000),
+                vec![(1000, key(1)), (2000, key(2))]
+            );
+            assert_eq!(
+                timer_service.poll_event_time_timers(5000),
+                vec![(3000, key(1))]
+            );
+            assert_eq!(timer_service.poll_processing_time_timers(5000).len(), 0);
+        }
+    }
+
+    #[test]
+    pub fn keyed_state_snapshot_test() {
+        let mut state_store = KeyedStateStore::new();
+        let mut timer_service = TimerService::new();
+
+        timer_service.set_current_key(key(1));
+        {
+            let mut ctx = KeyedProcessContext::new(0, 0, &mut state_store, &mut timer_service);
+            ctx.get_value_state("v").update(key(10));
+            ctx.get_map_state("m").put(key(12), key(13));
+            ctx.get_list_state("l").add(key(11));
+        }
+
+        let mut restored = KeyedStateStore::new();
+        restored.restore(state_store.snapshot());
+
+        let mut ctx = KeyedProcessContext::new(0, 0, &mut restored, &mut timer_service);
+        assert_eq!(ctx.get_value_state("v").value(), Some(&key(10)));
+        assert_eq!(ctx.get_map_state("m").get(&key(12)), Some(&key(13)));
+        // the list states are not checkpointed
+        assert_eq!(ctx.get_list_state("l").get().len(), 0);
+    }
+
+    #[test]
+    pub fn broadcast_state_snapshot_test() {
+        let rules = MapStateDescriptor::new("rules");
+        let mut state_store = BroadcastStateStore::new(&[rules.clone()]);
+        {
+            let mut ctx = BroadcastContext::new(0, &mut state_store);
+            let mut state = ctx.get_broadcast_state(&rules);
+            state.put(key(1), key(10));
+            state.put(key(2), key(20));
+            state.remove(&key(2));
+        }
+
+        let mut restored = BroadcastStateStore::new(&[rules.clone()]);
+        restored.restore(state_store.snapshot());
+
+        let ctx = ReadOnlyBroadcastContext::new(0, &restored);
+        let state = ctx.get_broadcast_state(&rules);
+        assert_eq!(state.get(&key(1)), Some(&key(10)));
+        assert!(!state.contains(&key(2)));
+    }
+}
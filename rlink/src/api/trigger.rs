@@ -0,0 +1,886 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Debug;
+use std::time::Duration;
+
+use crate::api::element::Record;
+use crate::api::function::Function;
+use crate::api::state::{from_bytes, to_bytes};
+use crate::api::window::{TWindow, Window};
+
+/// The result of a `Trigger` method, see flink `TriggerResult`
+#[derive(Clone, Debug, PartialEq)]
+pub enum TriggerResult {
+    /// No action is taken on the window.
+    Continue,
+    /// The window is evaluated and the result is emitted, the window's state is kept.
+    Fire,
+    /// The window's state is cleared without emitting.
+    Purge,
+    /// The window is evaluated and the result is emitted, then the window's state is cleared.
+    FireAndPurge,
+}
+
+impl TriggerResult {
+    pub fn is_fire(&self) -> bool {
+        match self {
+            TriggerResult::Fire | TriggerResult::FireAndPurge => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_purge(&self) -> bool {
+        match self {
+            TriggerResult::Purge | TriggerResult::FireAndPurge => true,
+            _ => false,
+        }
+    }
+}
+
+/// The timers, states and clocks of the window operator,
+/// the timers and states are scoped to the current key and window.
+#[derive(Debug)]
+pub struct TriggerContext {
+    current_key: Record,
+    pub(crate) current_watermark: u64,
+    pub(crate) current_processing_time: u64,
+
+    event_time_timers: BTreeMap<u64, HashSet<(Record, Window)>>,
+    processing_time_timers: BTreeMap<u64, HashSet<(Record, Window)>>,
+    states: HashMap<(Record, Window), HashMap<String, u64>>,
+}
+
+impl TriggerContext {
+    pub fn new() -> Self {
+        TriggerContext {
+            current_key: Record::new(),
+            current_watermark: 0,
+            current_processing_time: 0,
+            event_time_timers: BTreeMap::new(),
+            processing_time_timers: BTreeMap::new(),
+            states: HashMap::new(),
+        }
+    }
+
+    pub fn get_current_key(&self) -> &Record {
+        &self.current_key
+    }
+
+    pub fn get_current_watermark(&self) -> u64 {
+        self.current_watermark
+    }
+
+    pub fn get_current_processing_time(&self) -> u64 {
+        self.current_processing_time
+    }
+
+    pub fn register_event_time_timer(&mut self, window: &Window, time: u64) {
+        register_timer(&mut self.event_time_timers, &self.current_key, window, time);
+    }
+
+    pub fn delete_event_time_timer(&mut self, window: &Window, time: u64) {
+        delete_timer(&mut self.event_time_timers, &self.current_key, window, time);
+    }
+
+    pub fn register_processing_time_timer(&mut self, window: &Window, time: u64) {
+        register_timer(
+            &mut self.processing_time_timers,
+            &self.current_key,
+            window,
+            time,
+        );
+    }
+
+    pub fn delete_processing_time_timer(&mut self, window: &Window, time: u64) {
+        delete_timer(
+            &mut self.processing_time_timers,
+            &self.current_key,
+            window,
+            time,
+        );
+    }
+
+    /// the state `name` of the current key in the `window`, eg: the count of `CountTrigger`
+    pub fn get_state(&self, window: &Window, name: &str) -> Option<u64> {
+        self.states
+            .get(&(self.current_key.clone(), window.clone()))
+            .and_then(|states| states.get(name))
+            .map(|value| *value)
+    }
+
+    pub fn update_state(&mut self, window: &Window, name: &str, value: u64) {
+        self.states
+            .entry((self.current_key.clone(), window.clone()))
+            .or_default()
+            .insert(name.to_string(), value);
+    }
+
+    pub fn clear_state(&mut self, window: &Window, name: &str) {
+        let state_key = (self.current_key.clone(), window.clone());
+        let empty = match self.states.get_mut(&state_key) {
+            Some(states) => {
+                states.remove(name);
+                states.is_empty()
+            }
+            None => false,
+        };
+
+        if empty {
+            self.states.remove(&state_key);
+        }
+    }
+
+    pub(crate) fn set_current_key(&mut self, key: Record) {
+        self.current_key = key;
+    }
+
+    /// remove and return the event time timers that `timer <= time`, ordered by timer
+    pub(crate) fn poll_event_time_timers(&mut self, time: u64) -> Vec<(u64, Record, Window)> {
+        poll_timers(&mut self.event_time_timers, time)
+    }
+
+    /// remove and return the processing time timers that `timer <= time`, ordered by timer
+    pub(crate) fn poll_processing_time_timers(&mut self, time: u64) -> Vec<(u64, Record, Window)> {
+        poll_timers(&mut self.processing_time_timers, time)
+    }
+
+    pub(crate) fn snapshot(&self) -> TriggerSnapshot {
+        let states = self
+            .states
+            .iter()
+            .map(|((key, window), states)| {
+                let states = states
+                    .iter()
+                    .map(|(name, value)| (name.clone(), *value))
+                    .collect();
+                (to_bytes(key), window.clone(), states)
+            })
+            .collect();
+        TriggerSnapshot {
+            current_watermark: self.current_watermark,
+            event_time_timers: snapshot_timers(&self.event_time_timers),
+            processing_time_timers: snapshot_timers(&self.processing_time_timers),
+            states,
+        }
+    }
+
+    pub(crate) fn restore(&mut self, snapshot: TriggerSnapshot) {
+        self.current_watermark = snapshot.current_watermark;
+        restore_timers(&mut self.event_time_timers, snapshot.event_time_timers);
+        restore_timers(
+            &mut self.processing_time_timers,
+            snapshot.processing_time_timers,
+        );
+        for (key, window, states) in snapshot.states {
+            self.states
+                .insert((from_bytes(key), window), states.into_iter().collect());
+        }
+    }
+}
+
+impl Default for TriggerContext {
+    fn default() -> Self {
+        TriggerContext::new()
+    }
+}
+
+/// the checkpoint of the `TriggerContext`, the keys are kept as the bytes of `Record::values`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct TriggerSnapshot {
+    current_watermark: u64,
+    event_time_timers: Vec<(u64, Vec<u8>, Window)>,
+    processing_time_timers: Vec<(u64, Vec<u8>, Window)>,
+    states: Vec<(Vec<u8>, Window, Vec<(String, u64)>)>,
+}
+
+fn register_timer(
+    timers: &mut BTreeMap<u64, HashSet<(Record, Window)>>,
+    key: &Record,
+    window: &Window,
+    time: u64,
+) {
+    let timer_keys = timers.entry(time).or_default();
+    let timer_key = (key.clone(), window.clone());
+    if !timer_keys.contains(&timer_key) {
+        timer_keys.insert(timer_key);
+    }
+}
+
+fn delete_timer(
+    timers: &mut BTreeMap<u64, HashSet<(Record, Window)>>,
+    key: &Record,
+    window: &Window,
+    time: u64,
+) {
+    let empty = match timers.get_mut(&time) {
+        Some(timer_keys) => {
+            timer_keys.remove(&(key.clone(), window.clone()));
+            timer_keys.is_empty()
+        }
+        None => false,
+    };
+
+    if empty {
+        timers.remove(&time);
+    }
+}
+
+fn poll_timers(
+    timers: &mut BTreeMap<u64, HashSet<(Record, Window)>>,
+    time: u64,
+) -> Vec<(u64, Record, Window)> {
+    let pending_timers = timers.split_off(&(time + 1));
+    let expired_timers = std::mem::replace(timers, pending_timers);
+
+    let mut expired = Vec::new();
+    for (timer, timer_keys) in expired_timers {
+        let mut timer_keys: Vec<(Record, Window)> = timer_keys.into_iter().collect();
+        timer_keys.sort_by_key(|(_key, window)| window.max_timestamp());
+
+        for (key, window) in timer_keys {
+            expired.push((timer, key, window));
+        }
+    }
+
+    expired
+}
+
+fn snapshot_timers(
+    timers: &BTreeMap<u64, HashSet<(Record, Window)>>,
+) -> Vec<(u64, Vec<u8>, Window)> {
+    let mut snapshot = Vec::new();
+    for (timer, timer_keys) in timers {
+        for (key, window) in timer_keys {
+            snapshot.push((*timer, to_bytes(key), window.clone()));
+        }
+    }
+    snapshot
+}
+
+fn restore_timers(
+    timers: &mut BTreeMap<u64, HashSet<(Record, Window)>>,
+    snapshot: Vec<(u64, Vec<u8>, Window)>,
+) {
+    for (timer, key, window) in snapshot {
+        register_timer(timers, &from_bytes(key), &window, timer);
+    }
+}
+
+/// Determines when a window is evaluated to emit the results, see flink `Trigger`.
+///
+/// The trigger works on the current key of the `TriggerContext` and the given window,
+/// a fired window emits the results of the keys fired in this window.
+/// The state of the trigger is kept in the `TriggerContext` to be checkpointed.
+pub trait Trigger
+where
+    Self: Function + Debug,
+{
+    /// Called for every element that gets added to a window.
+    fn on_element(
+        &mut self,
+        timestamp: u64,
+        window: &Window,
+        context: &mut TriggerContext,
+    ) -> TriggerResult;
+
+    /// Called when a registered event time timer fires.
+    fn on_event_time(
+        &mut self,
+        time: u64,
+        window: &Window,
+        context: &mut TriggerContext,
+    ) -> TriggerResult;
+
+    /// Called when a registered processing time timer fires.
+    fn on_processing_time(
+        &mut self,
+        time: u64,
+        window: &Window,
+        context: &mut TriggerContext,
+    ) -> TriggerResult;
+
+    /// Returns `true` if the trigger supports merging the trigger state of the windows,
+    /// the trigger of a merging `WindowAssigner` must support it, eg: the session windows.
+    fn can_merge(&self) -> bool {
+        false
+    }
+
+    /// Called when the `merged_windows` of the current key are merged into the `window`,
+    /// the trigger state of the `merged_windows` is moved into the `window` and their timers
+    /// are re-registered for it. The `merged_windows` are cleared after this call.
+    fn on_merge(
+        &mut self,
+        _window: &Window,
+        _merged_windows: &[Window],
+        _context: &mut TriggerContext,
+    ) {
+        unimplemented!("the trigger does not support merging windows")
+    }
+
+    /// Clears the timers and state of the key in the window, called when the window is purged.
+    fn clear(&mut self, window: &Window, context: &mut TriggerContext);
+}
+
+/// Fire once the watermark passes the end of the window,
+/// the default trigger of the event time `WindowAssigner`.
+#[derive(Debug)]
+pub struct EventTimeTrigger {}
+
+impl EventTimeTrigger {
+    pub fn new() -> Self {
+        EventTimeTrigger {}
+    }
+}
+
+impl Default for EventTimeTrigger {
+    fn default() -> Self {
+        EventTimeTrigger::new()
+    }
+}
+
+impl Trigger for EventTimeTrigger {
+    fn on_element(
+        &mut self,
+        _timestamp: u64,
+        window: &Window,
+        context: &mut TriggerContext,
+    ) -> TriggerResult {
+        if window.max_timestamp() <= context.get_current_watermark() {
+            // the watermark is already past the window, fire immediately
+            TriggerResult::Fire
+        } else {
+            context.register_event_time_timer(window, window.max_timestamp());
+            TriggerResult::Continue
+        }
+    }
+
+    fn on_event_time(
+        &mut self,
+        time: u64,
+        window: &Window,
+        _context: &mut TriggerContext,
+    ) -> TriggerResult {
+        if time == window.max_timestamp() {
+            TriggerResult::Fire
+        } else {
+            TriggerResult::Continue
+        }
+    }
+
+    fn on_processing_time(
+        &mut self,
+        _time: u64,
+        _window: &Window,
+        _context: &mut TriggerContext,
+    ) -> TriggerResult {
+        TriggerResult::Continue
+    }
+
+    fn can_merge(&self) -> bool {
+        true
+    }
+
+    fn on_merge(
+        &mut self,
+        window: &Window,
+        _merged_windows: &[Window],
+        context: &mut TriggerContext,
+    ) {
+        // the merged window is fired by the next element if the watermark is past it
+        if window.max_timestamp() > context.get_current_watermark() {
+            context.register_event_time_timer(window, window.max_timestamp());
+        }
+    }
+
+    fn clear(&mut self, window: &Window, context: &mut TriggerContext) {
+        context.delete_event_time_timer(window, window.max_timestamp());
+    }
+}
+
+impl Function for EventTimeTrigger {
+    fn get_name(&self) -> &str {
+        "EventTimeTrigger"
+    }
+}
+
+/// Fire once the processing time passes the end of the window,
+/// the default trigger of the processing time `WindowAssigner`.
+#[derive(Debug)]
+pub struct ProcessingTimeTrigger {}
+
+impl ProcessingTimeTrigger {
+    pub fn new() -> Self {
+        ProcessingTimeTrigger {}
+    }
+}
+
+impl Default for ProcessingTimeTrigger {
+    fn default() -> Self {
+        ProcessingTimeTrigger::new()
+    }
+}
+
+impl Trigger for ProcessingTimeTrigger {
+    fn on_element(
+        &mut self,
+        _timestamp: u64,
+        window: &Window,
+        context: &mut TriggerContext,
+    ) -> TriggerResult {
+        context.register_processing_time_timer(window, window.max_timestamp());
+        TriggerResult::Continue
+    }
+
+    fn on_event_time(
+        &mut self,
+        _time: u64,
+        _window: &Window,
+        _context: &mut TriggerContext,
+    ) -> TriggerResult {
+        TriggerResult::Continue
+    }
+
+    fn on_processing_time(
+        &mut self,
+        _time: u64,
+        _window: &Window,
+        _context: &mut TriggerContext,
+    ) -> TriggerResult {
+        TriggerResult::Fire
+    }
+
+    fn can_merge(&self) -> bool {
+        true
+    }
+
+    fn on_merge(
+        &mut self,
+        window: &Window,
+        _merged_windows: &[Window],
+        context: &mut TriggerContext,
+    ) {
+        context.register_processing_time_timer(window, window.max_timestamp());
+    }
+
+    fn clear(&mut self, window: &Window, context: &mut TriggerContext) {
+        context.delete_processing_time_timer(window, window.max_timestamp());
+    }
+}
+
+impl Function for ProcessingTimeTrigger {
+    fn get_name(&self) -> &str {
+        "ProcessingTimeTrigger"
+    }
+}
+
+const COUNT_STATE: &str = "count";
+
+/// Fire once the count of elements of a key in a window reaches the given count.
+#[derive(Debug)]
+pub struct CountTrigger {
+    max_count: u64,
+}
+
+impl CountTrigger {
+    pub fn of(max_count: u64) -> Self {
+        if max_count == 0 {
+            panic!("CountTrigger parameters must satisfy max_count > 0")
+        }
+
+        CountTrigger { max_count }
+    }
+}
+
+impl Trigger for CountTrigger {
+    fn on_element(
+        &mut self,
+        _timestamp: u64,
+        window: &Window,
+        context: &mut TriggerContext,
+    ) -> TriggerResult {
+        let count = context.get_state(window, COUNT_STATE).unwrap_or(0) + 1;
+
+        if count >= self.max_count {
+            context.clear_state(window, COUNT_STATE);
+            TriggerResult::Fire
+        } else {
+            context.update_state(window, COUNT_STATE, count);
+            TriggerResult::Continue
+        }
+    }
+
+    fn on_event_time(
+        &mut self,
+        _time: u64,
+        _window: &Window,
+        _context: &mut TriggerContext,
+    ) -> TriggerResult {
+        TriggerResult::Continue
+    }
+
+    fn on_processing_time(
+        &mut self,
+        _time: u64,
+        _window: &Window,
+        _context: &mut TriggerContext,
+    ) -> TriggerResult {
+        TriggerResult::Continue
+    }
+
+    fn can_merge(&self) -> bool {
+        true
+    }
+
+    fn on_merge(
+        &mut self,
+        window: &Window,
+        merged_windows: &[Window],
+        context: &mut TriggerContext,
+    ) {
+        // the counts of the merged windows are summed up, the window fires on the next element
+        // if the sum reaches the `max_count`
+        let count: u64 = std::iter::once(window)
+            .chain(merged_windows.iter())
+            .filter_map(|window| context.get_state(window, COUNT_STATE))
+            .sum();
+        if count > 0 {
+            context.update_state(window, COUNT_STATE, count);
+        }
+    }
+
+    fn clear(&mut self, window: &Window, context: &mut TriggerContext) {
+        context.clear_state(window, COUNT_STATE);
+    }
+}
+
+impl Function for CountTrigger {
+    fn get_name(&self) -> &str {
+        "CountTrigger"
+    }
+}
+
+const FIRE_TIMESTAMP_STATE: &str = "fire_timestamp";
+
+/// Fire continuously by the given event time interval, and fire once more
+/// when the watermark passes the end of the window.
+#[derive(Debug)]
+pub struct ContinuousEventTimeTrigger {
+    interval: u64,
+}
+
+impl ContinuousEventTimeTrigger {
+    pub fn of(interval: Duration) -> Self {
+        let interval = interval.as_millis() as u64;
+        if interval == 0 {
+            panic!("ContinuousEventTimeTrigger parameters must satisfy interval > 0")
+        }
+
+        ContinuousEventTimeTrigger { interval }
+    }
+}
+
+impl Trigger for ContinuousEventTimeTrigger {
+    fn on_element(
+        &mut self,
+        timestamp: u64,
+        window: &Window,
+        context: &mut TriggerContext,
+    ) -> TriggerResult {
+        if window.max_timestamp() <= context.get_current_watermark() {
+            // the watermark is already past the window, fire immediately
+            return TriggerResult::Fire;
+        }
+
+        context.register_event_time_timer(window, window.max_timestamp());
+
+        if context.get_state(window, FIRE_TIMESTAMP_STATE).is_none() {
+            let start = timestamp - (timestamp % self.interval);
+            let next_fire_timestamp = start + self.interval;
+
+            context.register_event_time_timer(window, next_fire_timestamp);
+            context.update_state(window, FIRE_TIMESTAMP_STATE, next_fire_timestamp);
+        }
+
+        TriggerResult::Continue
+    }
+
+    fn on_event_time(
+        &mut self,
+        time: u64,
+        window: &Window,
+        context: &mut TriggerContext,
+    ) -> TriggerResult {
+        if time == window.max_timestamp() {
+            return TriggerResult::Fire;
+        }
+
+        match context.get_state(window, FIRE_TIMESTAMP_STATE) {
+            Some(fire_timestamp) if fire_timestamp == time => {
+                let next_fire_timestamp = time + self.interval;
+
+                context.register_event_time_timer(window, next_fire_timestamp);
+                context.update_state(window, FIRE_TIMESTAMP_STATE, next_fire_timestamp);
+
+                TriggerResult::Fire
+            }
+            _ => TriggerResult::Continue,
+        }
+    }
+
+    fn on_processing_time(
+        &mut self,
+        _time: u64,
+        _window: &Window,
+        _context: &mut TriggerContext,
+    ) -> TriggerResult {
+        TriggerResult::Continue
+    }
+
+    fn can_merge(&self) -> bool {
+        true
+    }
+
+    fn on_merge(
+        &mut self,
+        window: &Window,
+        merged_windows: &[Window],
+        context: &mut TriggerContext,
+    ) {
+        if window.max_timestamp() > context.get_current_watermark() {
+            context.register_event_time_timer(window, window.max_timestamp());
+        }
+
+        // the earliest pending fire timestamp of the merged windows is kept
+        let fire_timestamp = std::iter::once(window)
+            .chain(merged_windows.iter())
+            .filter_map(|window| context.get_state(window, FIRE_TIMESTAMP_STATE))
+            .min();
+        if let Some(fire_timestamp) = fire_timestamp {
+            context.register_event_time_timer(window, fire_timestamp);
+            context.update_state(window, FIRE_TIMESTAMP_STATE, fire_timestamp);
+        }
+    }
+
+    fn clear(&mut self, window: &Window, context: &mut TriggerContext) {
+        if let Some(fire_timestamp) = context.get_state(window, FIRE_TIMESTAMP_STATE) {
+            context.delete_event_time_timer(window, fire_timestamp);
+            context.clear_state(window, FIRE_TIMESTAMP_STATE);
+        }
+        context.delete_event_time_timer(window, window.max_timestamp());
+    }
+}
+
+impl Function for ContinuousEventTimeTrigger {
+    fn get_name(&self) -> &str {
+        "ContinuousEventTimeTrigger"
+    }
+}
+
+/// Turn the `Fire` of the nested trigger into `FireAndPurge`.
+#[derive(Debug)]
+pub struct PurgingTrigger<T>
+where
+    T: Trigger,
+{
+    nested_trigger: T,
+}
+
+impl<T> PurgingTrigger<T>
+where
+    T: Trigger,
+{
+    pub fn of(nested_trigger: T) -> Self {
+        PurgingTrigger { nested_trigger }
+    }
+
+    fn purging(trigger_result: TriggerResult) -> TriggerResult {
+        if trigger_result.is_fire() {
+            TriggerResult::FireAndPurge
+        } else {
+            trigger_result
+        }
+    }
+}
+
+impl<T> Trigger for PurgingTrigger<T>
+where
+    T: Trigger,
+{
+    fn on_element(
+        &mut self,
+        timestamp: u64,
+        window: &Window,
+        context: &mut TriggerContext,
+    ) -> TriggerResult {
+        let trigger_result = self.nested_trigger.on_element(timestamp, window, context);
+        Self::purging(trigger_result)
+    }
+
+    fn on_event_time(
+        &mut self,
+        time: u64,
+        window: &Window,
+        context: &mut TriggerContext,
+    ) -> TriggerResult {
+        let trigger_result = self.nested_trigger.on_event_time(time, window, context);
+        Self::purging(trigger_result)
+    }
+
+    fn on_processing_time(
+        &mut self,
+        time: u64,
+        window: &Window,
+        context: &mut TriggerContext,
+    ) -> TriggerResult {
+        let trigger_result = self
+            .nested_trigger
+            .on_processing_time(time, window, context);
+        Self::purging(trigger_result)
+    }
+
+    fn can_merge(&self) -> bool {
+        self.nested_trigger.can_merge()
+    }
+
+    fn on_merge(
+        &mut self,
+        window: &Window,
+        merged_windows: &[Window],
+        context: &mut TriggerContext,
+    ) {
+        self.nested_trigger
+            .on_merge(window, merged_windows, context);
+    }
+
+    fn clear(&mut self, window: &Window, context: &mut TriggerContext) {
+        self.nested_trigger.clear(window, context);
+    }
+}
+
+impl<T> Function for PurgingTrigger<T>
+where
+    T: Trigger,
+{
+    fn get_name(&self) -> &str {
+        "PurgingTrigger"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bytes::BytesMut;
+
+    use crate::api::element::{Buffer, Record};
+    use crate::api::trigger::{
+        ContinuousEventTimeTrigger, CountTrigger, EventTimeTrigger, PurgingTrigger, Trigger,
+        TriggerContext, TriggerResult,
+    };
+    use crate::api::window::{TimeWindow, Window};
+
+    fn key(k: u8) -> Record {
+        let mut record = Record::new();
+        record.values = Buffer::from(BytesMut::from(&[k][..]));
+        record
+    }
+
+    #[test]
+    pub fn event_time_trigger_test() {
+        // 2020-07-01 15:00:00:000
+        let base_timestamp = 1593586800000u64;
+        let window = Window::TimeWindow(TimeWindow::new(base_timestamp, base_timestamp + 60000));
+
+        let mut context = TriggerContext::new();
+        let mut trigger = EventTimeTrigger::new();
+
+        let result = trigger.on_element(base_timestamp + 1000, &window, &mut context);
+        assert_eq!(result, TriggerResult::Continue);
+
+        let timers = context.poll_event_time_timers(base_timestamp + 59999);
+        assert_eq!(timers.len(), 0);
+
+        let timers = context.poll_event_time_timers(base_timestamp + 60000);
+        assert_eq!(timers.len(), 1);
+
+        let (time, _key, window) = &timers[0];
+        let result = trigger.on_event_time(*time, window, &mut context);
+        assert_eq!(result, TriggerResult::Fire);
+    }
+
+    #[test]
+    pub fn count_trigger_test() {
+        let window = Window::TimeWindow(TimeWindow::new(0, 60000));
+
+        let mut context = TriggerContext::new();
+        let mut trigger = PurgingTrigger::of(CountTrigger::of(2));
+
+        let result = trigger.on_element(1000, &window, &mut context);
+        assert_eq!(result, TriggerResult::Continue);
+
+        let result = trigger.on_element(2000, &window, &mut context);
+        assert_eq!(result, TriggerResult::FireAndPurge);
+
+        let result = trigger.on_element(3000, &window, &mut context);
+        assert_eq!(result, TriggerResult::Continue);
+    }
+
+    #[test]
+    pub fn count_trigger_key_scope_test() {
+        let window = Window::TimeWindow(TimeWindow::new(0, 60000));
+
+        let mut context = TriggerContext::new();
+        let mut trigger = CountTrigger::of(2);
+
+        // the counts of the keys are kept apart
+        context.set_current_key(key(1));
+        assert_eq!(
+            trigger.on_element(1000, &window, &mut context),
+            TriggerResult::Continue
+        );
+        context.set_current_key(key(2));
+        assert_eq!(
+            trigger.on_element(2000, &window, &mut context),
+            TriggerResult::Continue
+        );
+
+        // the count is restored from the checkpoint
+        let mut restored = TriggerContext::new();
+        restored.restore(context.snapshot());
+
+        restored.set_current_key(key(1));
+        assert_eq!(
+            trigger.on_element(3000, &window, &mut restored),
+            TriggerResult::Fire
+        );
+        restored.set_current_key(key(2));
+        trigger.clear(&window, &mut restored);
+        assert_eq!(
+            trigger.on_element(4000, &window, &mut restored),
+            TriggerResult::Continue
+        );
+    }
+
+    #[test]
+    pub fn continuous_event_time_trigger_test() {
+        let window = Window::TimeWindow(TimeWindow::new(0, 60000));
+
+        let mut context = TriggerContext::new();
+        let mut trigger = ContinuousEventTimeTrigger::of(Duration::from_secs(10));
+
+        let result = trigger.on_element(1000, &window, &mut context);
+        assert_eq!(result, TriggerResult::Continue);
+
+        let timers = context.poll_event_time_timers(30000);
+        assert_eq!(timers.len(), 1);
+        assert_eq!(timers[0].0, 10000);
+
+        let result = trigger.on_event_time(timers[0].0, &timers[0].2, &mut context);
+        assert_eq!(result, TriggerResult::Fire);
+
+        // the next interval timer and the end of window timer
+        let timers = context.poll_event_time_timers(60000);
+        assert_eq!(timers.len(), 2);
+        assert_eq!(timers[0].0, 20000);
+        assert_eq!(timers[1].0, 60000);
+    }
+}
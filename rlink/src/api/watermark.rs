@@ -3,6 +3,7 @@ use std::time::Duration;
 
 use crate::api::element::{Element, Record};
 use crate::api::function::Function;
+use crate::api::schema::Schema;
 use crate::utils::date_time::timestamp_str;
 
 pub const MAX_WATERMARK: Watermark = Watermark {
@@ -132,4 +133,8 @@ where
     fn get_name(&self) -> &str {
         "BoundedOutOfOrdernessTimestampExtractor"
     }
+
+    fn get_input_schema(&self) -> Schema {
+        self.extract_timestamp.get_input_schema()
+    }
 }
@@ -2,7 +2,10 @@ use std::cmp::{max, min};
 use std::fmt::Debug;
 use std::time::Duration;
 
+use crate::api::element::Record;
 use crate::api::function::Function;
+use crate::api::output_tag::OutputTag;
+use crate::api::trigger::{EventTimeTrigger, ProcessingTimeTrigger, Trigger};
 use crate::utils;
 
 pub trait TWindow: Debug + Clone {
@@ -91,6 +94,81 @@ where
     Self: Function + Debug,
 {
     fn assign_windows(&self, timestamp: u64, context: WindowAssignerContext) -> Vec<Window>;
+
+    /// Assign windows to a `Record`, default by the record's timestamp.
+    fn assign_record_windows(
+        &self,
+        record: &mut Record,
+        context: WindowAssignerContext,
+    ) -> Vec<Window> {
+        self.assign_windows(record.timestamp, context)
+    }
+
+    /// Returns `true` if elements are assigned to windows based on event time,
+    /// `false` otherwise. Processing time windows are fired by the `WindowTimer`.
+    fn is_event_time(&self) -> bool {
+        true
+    }
+
+    /// Returns `true` if the windows of a key can be merged, eg: session windows.
+    /// Merging windows require the `AggregateFunction::merge` or the `ReduceFunction::merge`
+    /// implementation.
+    fn is_merging(&self) -> bool {
+        false
+    }
+
+    /// Returns the default `Trigger` of this assigner, it is used when
+    /// no `Trigger` is set by `WindowedStream::trigger`.
+    fn get_default_trigger(&self) -> Box<dyn Trigger> {
+        if self.is_event_time() {
+            Box::new(EventTimeTrigger::new())
+        } else {
+            Box::new(ProcessingTimeTrigger::new())
+        }
+    }
+}
+
+/// The marker of the `WindowAssigner` assigning elements to the fixed event time windows,
+/// it restricts the operators that are driven by the watermark only and never merge windows,
+/// eg: the window join. so the session windows are not included.
+pub trait EventTimeWindowAssigner: WindowAssigner {}
+
+impl EventTimeWindowAssigner for SlidingEventTimeWindows {}
+
+impl EventTimeWindowAssigner for TumblingEventTimeWindows {}
+
+/// The options of the window function operator
+#[derive(Debug, Default)]
+pub struct WindowOptions {
+    pub(crate) trigger: Option<Box<dyn Trigger>>,
+    /// milliseconds
+    pub(crate) allowed_lateness: u64,
+    pub(crate) late_data_output_tag: Option<OutputTag>,
+}
+
+fn sliding_windows(timestamp: u64, size: u64, slide: u64, offset: i64) -> Vec<Window> {
+    let mut windows = Vec::with_capacity((size / slide) as usize);
+    let last_start = TimeWindow::get_window_start_with_offset(timestamp, offset, slide);
+
+    let mut start = last_start;
+    loop {
+        if start > timestamp - size {
+            let window = TimeWindow::new(start, start + size);
+            // info!("Create window: {}", window);
+            windows.push(Window::TimeWindow(window));
+            start -= slide;
+        } else {
+            break;
+        }
+    }
+
+    windows.sort_by_key(|x| x.min_timestamp());
+    windows
+}
+
+fn tumbling_window(timestamp: u64, size: u64, offset: i64) -> Vec<Window> {
+    let start = TimeWindow::get_window_start_with_offset(timestamp, offset, size);
+    vec![Window::TimeWindow(TimeWindow::new(start, start + size))]
 }
 
 #[derive(Debug)]
@@ -121,24 +199,7 @@ impl SlidingEventTimeWindows {
 
 impl WindowAssigner for SlidingEventTimeWindows {
     fn assign_windows(&self, timestamp: u64, _context: WindowAssignerContext) -> Vec<Window> {
-        let mut windows = Vec::with_capacity((self.size / self.slide) as usize);
-        let last_start =
-            TimeWindow::get_window_start_with_offset(timestamp, self.offset, self.slide);
-
-        let mut start = last_start;
-        loop {
-            if start > timestamp - self.size {
-                let window = TimeWindow::new(start, start + self.size);
-                // info!("Create window: {}", window);
-                windows.push(Window::TimeWindow(window));
-                start -= self.slide;
-            } else {
-                break;
-            }
-        }
-
-        windows.sort_by_key(|x| x.min_timestamp());
-        windows
+        sliding_windows(timestamp, self.size, self.slide, self.offset)
     }
 }
 
@@ -147,3 +208,291 @@ impl Function for SlidingEventTimeWindows {
         "SlidingEventTimeWindows"
     }
 }
+
+#[derive(Debug)]
+pub struct SlidingProcessingTimeWindows {
+    size: u64,
+    slide: u64,
+    offset: i64,
+}
+
+impl SlidingProcessingTimeWindows {
+    pub fn new(size: Duration, slide: Duration, offset: Option<Duration>) -> Self {
+        let size = size.as_millis() as u64;
+        let slide = slide.as_millis() as u64;
+        let offset = offset.map(|x| x.as_millis() as i64).unwrap_or(0);
+
+        if offset.abs() as u64 >= slide || size <= 0 {
+            panic!(
+                "SlidingProcessingTimeWindows parameters must satisfy offset.abs() < slide and size > 0"
+            )
+        }
+        SlidingProcessingTimeWindows {
+            size,
+            slide,
+            offset,
+        }
+    }
+}
+
+impl WindowAssigner for SlidingProcessingTimeWindows {
+    fn assign_windows(&self, _timestamp: u64, context: WindowAssignerContext) -> Vec<Window> {
+        let timestamp = context.get_current_processing_time();
+        sliding_windows(timestamp, self.size, self.slide, self.offset)
+    }
+
+    fn is_event_time(&self) -> bool {
+        false
+    }
+}
+
+impl Function for SlidingProcessingTimeWindows {
+    fn get_name(&self) -> &str {
+        "SlidingProcessingTimeWindows"
+    }
+}
+
+#[derive(Debug)]
+pub struct TumblingEventTimeWindows {
+    size: u64,
+    offset: i64,
+}
+
+impl TumblingEventTimeWindows {
+    pub fn new(size: Duration, offset: Option<Duration>) -> Self {
+        let size = size.as_millis() as u64;
+        let offset = offset.map(|x| x.as_millis() as i64).unwrap_or(0);
+
+        if offset.abs() as u64 >= size || size <= 0 {
+            panic!(
+                "TumblingEventTimeWindows parameters must satisfy offset.abs() < size and size > 0"
+            )
+        }
+        TumblingEventTimeWindows { size, offset }
+    }
+}
+
+impl WindowAssigner for TumblingEventTimeWindows {
+    fn assign_windows(&self, timestamp: u64, _context: WindowAssignerContext) -> Vec<Window> {
+        tumbling_window(timestamp, self.size, self.offset)
+    }
+}
+
+impl Function for TumblingEventTimeWindows {
+    fn get_name(&self) -> &str {
+        "TumblingEventTimeWindows"
+    }
+}
+
+#[derive(Debug)]
+pub struct TumblingProcessingTimeWindows {
+    size: u64,
+    offset: i64,
+}
+
+impl TumblingProcessingTimeWindows {
+    pub fn new(size: Duration, offset: Option<Duration>) -> Self {
+        let size = size.as_millis() as u64;
+        let offset = offset.map(|x| x.as_millis() as i64).unwrap_or(0);
+
+        if offset.abs() as u64 >= size || size <= 0 {
+            panic!(
+                "TumblingProcessingTimeWindows parameters must satisfy offset.abs() < size and size > 0"
+            )
+        }
+        TumblingProcessingTimeWindows { size, offset }
+    }
+}
+
+impl WindowAssigner for TumblingProcessingTimeWindows {
+    fn assign_windows(&self, _timestamp: u64, context: WindowAssignerContext) -> Vec<Window> {
+        let timestamp = context.get_current_processing_time();
+        tumbling_window(timestamp, self.size, self.offset)
+    }
+
+    fn is_event_time(&self) -> bool {
+        false
+    }
+}
+
+impl Function for TumblingProcessingTimeWindows {
+    fn get_name(&self) -> &str {
+        "TumblingProcessingTimeWindows"
+    }
+}
+
+/// Event time session windows with a static gap,
+/// the session is closed by a period of inactivity greater than the `gap`.
+#[derive(Debug)]
+pub struct EventTimeSessionWindows {
+    gap: u64,
+}
+
+impl EventTimeSessionWindows {
+    pub fn new(gap: Duration) -> Self {
+        let gap = gap.as_millis() as u64;
+        if gap <= 0 {
+            panic!("EventTimeSessionWindows parameters must satisfy gap > 0")
+        }
+        EventTimeSessionWindows { gap }
+    }
+}
+
+impl WindowAssigner for EventTimeSessionWindows {
+    fn assign_windows(&self, timestamp: u64, _context: WindowAssignerContext) -> Vec<Window> {
+        vec![Window::TimeWindow(TimeWindow::new(
+            timestamp,
+            timestamp + self.gap,
+        ))]
+    }
+
+    fn is_merging(&self) -> bool {
+        true
+    }
+}
+
+impl Function for EventTimeSessionWindows {
+    fn get_name(&self) -> &str {
+        "EventTimeSessionWindows"
+    }
+}
+
+/// Extract the session gap from a `Record`
+pub trait SessionWindowTimeGapExtractor
+where
+    Self: Function + Debug,
+{
+    fn extract(&self, record: &mut Record) -> Duration;
+}
+
+/// Event time session windows with a dynamic gap extracted from each record.
+#[derive(Debug)]
+pub struct DynamicEventTimeSessionWindows<E>
+where
+    E: SessionWindowTimeGapExtractor,
+{
+    gap_extractor: E,
+}
+
+impl<E> DynamicEventTimeSessionWindows<E>
+where
+    E: SessionWindowTimeGapExtractor,
+{
+    pub fn new(gap_extractor: E) -> Self {
+        DynamicEventTimeSessionWindows { gap_extractor }
+    }
+}
+
+impl<E> WindowAssigner for DynamicEventTimeSessionWindows<E>
+where
+    E: SessionWindowTimeGapExtractor,
+{
+    fn assign_windows(&self, timestamp: u64, _context: WindowAssignerContext) -> Vec<Window> {
+        // the `Watermark` has no gap, only the window start is used to fire sessions
+        vec![Window::TimeWindow(TimeWindow::new(timestamp, timestamp))]
+    }
+
+    fn assign_record_windows(
+        &self,
+        record: &mut Record,
+        _context: WindowAssignerContext,
+    ) -> Vec<Window> {
+        let gap = self.gap_extractor.extract(record).as_millis() as u64;
+        if gap <= 0 {
+            panic!("DynamicEventTimeSessionWindows extracted gap must satisfy gap > 0")
+        }
+
+        vec![Window::TimeWindow(TimeWindow::new(
+            record.timestamp,
+            record.timestamp + gap,
+        ))]
+    }
+
+    fn is_merging(&self) -> bool {
+        true
+    }
+}
+
+impl<E> Function for DynamicEventTimeSessionWindows<E>
+where
+    E: SessionWindowTimeGapExtractor,
+{
+    fn get_name(&self) -> &str {
+        "DynamicEventTimeSessionWindows"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::api::window::{
+        EventTimeSessionWindows, SlidingEventTimeWindows, TWindow, TimeWindow,
+        TumblingEventTimeWindows, WindowAssigner, WindowAssignerContext,
+    };
+
+    #[test]
+    pub fn tumbling_event_time_windows_test() {
+        // 2020-07-01 15:00:00:000
+        let base_timestamp = 1593586800000u64;
+        let assigner = TumblingEventTimeWindows::new(Duration::from_secs(60), None);
+
+        let windows = assigner.assign_windows(base_timestamp + 1000, WindowAssignerContext {});
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].min_timestamp(), base_timestamp);
+        assert_eq!(windows[0].max_timestamp(), base_timestamp + 60000);
+
+        let windows = assigner.assign_windows(base_timestamp + 60000, WindowAssignerContext {});
+        assert_eq!(windows[0].min_timestamp(), base_timestamp + 60000);
+    }
+
+    #[test]
+    pub fn tumbling_event_time_windows_offset_test() {
+        // 2020-07-01 15:00:00:000
+        let base_timestamp = 1593586800000u64;
+        let assigner =
+            TumblingEventTimeWindows::new(Duration::from_secs(60), Some(Duration::from_secs(10)));
+
+        let windows = assigner.assign_windows(base_timestamp + 1000, WindowAssignerContext {});
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].min_timestamp(), base_timestamp - 50000);
+        assert_eq!(windows[0].max_timestamp(), base_timestamp + 10000);
+    }
+
+    #[test]
+    pub fn sliding_event_time_windows_test() {
+        // 2020-07-01 15:00:00:000
+        let base_timestamp = 1593586800000u64;
+        let assigner =
+            SlidingEventTimeWindows::new(Duration::from_secs(60), Duration::from_secs(20), None);
+
+        let windows = assigner.assign_windows(base_timestamp + 1000, WindowAssignerContext {});
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows[0].min_timestamp(), base_timestamp - 40000);
+        assert_eq!(windows[2].min_timestamp(), base_timestamp);
+        assert_eq!(windows[2].max_timestamp(), base_timestamp + 60000);
+    }
+
+    #[test]
+    pub fn event_time_session_windows_test() {
+        // 2020-07-01 15:00:00:000
+        let base_timestamp = 1593586800000u64;
+        let assigner = EventTimeSessionWindows::new(Duration::from_secs(10));
+
+        let windows = assigner.assign_windows(base_timestamp, WindowAssignerContext {});
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].min_timestamp(), base_timestamp);
+        assert_eq!(windows[0].max_timestamp(), base_timestamp + 10000);
+    }
+
+    #[test]
+    pub fn time_window_merge_test() {
+        let w1 = TimeWindow::new(1000, 2000);
+        let w2 = TimeWindow::new(1500, 2500);
+        let w3 = TimeWindow::new(3000, 4000);
+
+        assert!(w1.intersects(w2.clone()));
+        assert!(!w1.intersects(w3.clone()));
+        assert_eq!(w1.cover(w2), TimeWindow::new(1000, 2500));
+    }
+}
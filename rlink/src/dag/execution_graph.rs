@@ -3,13 +3,14 @@ use std::ops::Index;
 
 use daggy::{Dag, EdgeIndex, NodeIndex, Walker};
 
-use crate::api::function::InputSplit;
+use crate::api::function::{Distribution, InputSplit};
 use crate::api::operator::StreamOperator;
 use crate::api::runtime::{JobId, OperatorId};
 use crate::dag::job_graph::{JobEdge, JobGraph};
 use crate::dag::stream_graph::StreamNode;
 use crate::dag::utils::JsonDag;
 use crate::dag::{DagError, Label, TaskId};
+use crate::functions::partitioner::rescale_partitions;
 
 #[derive(Copy, Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub(crate) enum ExecutionEdge {
@@ -192,7 +193,24 @@ impl ExecutionGraph {
                                 .unwrap();
                         }
                     }
-                    JobEdge::ReBalance => {
+                    JobEdge::ReBalance(Distribution::Rescale) => {
+                        // build the execution edges to the partitions owned by each task
+                        let num_tasks = execution_node_indies.len() as u16;
+                        let num_partitions = child_execution_node_indies.len() as u16;
+                        for number in 0..execution_node_indies.len() {
+                            let node_index = execution_node_indies[number];
+                            for partition in
+                                rescale_partitions(number as u16, num_tasks, num_partitions)
+                            {
+                                let child_node_index =
+                                    child_execution_node_indies[partition as usize];
+                                self.dag
+                                    .add_edge(node_index, child_node_index, ExecutionEdge::Network)
+                                    .unwrap();
+                            }
+                        }
+                    }
+                    JobEdge::ReBalance(_distribution) => {
                         // build cartesian product execution edge
                         for node_index in execution_node_indies {
                             for child_node_index in child_execution_node_indies {
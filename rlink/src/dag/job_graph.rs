@@ -4,6 +4,7 @@ use std::ops::{Index, IndexMut};
 
 use daggy::{Dag, EdgeIndex, NodeIndex, Walker};
 
+use crate::api::function::Distribution;
 use crate::api::operator::DEFAULT_PARALLELISM;
 use crate::api::runtime::{JobId, OperatorId};
 use crate::dag::stream_graph::{StreamGraph, StreamNode};
@@ -12,10 +13,10 @@ use crate::dag::{utils, DagError, Label, OperatorType};
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub enum JobEdge {
-    /// Forward
-    Forward = 1,
-    /// Hash
-    ReBalance = 2,
+    /// Forward, the task sends to the child task of the same task number in memory
+    Forward,
+    /// the tasks send to the child tasks by network, which are connected by the distribution
+    ReBalance(Distribution),
 }
 
 impl Label for JobEdge {
@@ -64,6 +65,32 @@ impl JobNode {
             .is_some()
     }
 
+    fn is_keyed_process_job(&self) -> bool {
+        self.stream_nodes
+            .iter()
+            .find(|stream_node| stream_node.operator_type == OperatorType::KeyedProcess)
+            .is_some()
+    }
+
+    fn is_broadcast_process_job(&self) -> bool {
+        self.stream_nodes
+            .iter()
+            .find(|stream_node| stream_node.operator_type == OperatorType::BroadcastProcess)
+            .is_some()
+    }
+
+    /// the records of a key are partitioned to the same task of the keyed job
+    fn is_keyed_job(&self) -> bool {
+        self.is_reduce_job() || self.is_keyed_process_job()
+    }
+
+    /// the distribution of the `Partitioner` of the job, the records are routed by it
+    fn get_distribution(&self) -> Option<Distribution> {
+        self.stream_nodes
+            .iter()
+            .find_map(|stream_node| stream_node.distribution)
+    }
+
     fn get_stream_node(&self, operator_id: OperatorId) -> Option<&StreamNode> {
         self.stream_nodes.iter().find(|x| x.id == operator_id)
     }
@@ -136,36 +163,54 @@ impl JobGraph {
 
     pub fn build_job_edges(&mut self) -> Result<(), DagError> {
         let node_indies: Vec<NodeIndex> = self.job_node_indies.values().map(|x| *x).collect();
-        node_indies.into_iter().for_each(|job_node_index| {
-            self.build_job_edge(job_node_index);
-        });
+        for job_node_index in node_indies {
+            self.build_job_edge(job_node_index)?;
+        }
 
         Ok(())
     }
 
-    fn build_job_edge(&mut self, job_node_index: NodeIndex) {
+    fn build_job_edge(&mut self, job_node_index: NodeIndex) -> Result<(), DagError> {
         let job_node = self.dag.index(job_node_index).clone();
         if job_node.child_job_ids.len() == 0 {
-            return;
+            return Ok(());
         }
 
         for child_job_id in &job_node.child_job_ids {
             let child_node_index = self.job_node_indies.get(child_job_id).unwrap();
             let child_job_node = self.dag.index(*child_node_index);
 
-            let job_edge = if child_job_node.is_reduce_job() {
-                JobEdge::ReBalance
-            } else if job_node.is_reduce_job() {
-                if job_node.parallelism != child_job_node.parallelism {
-                    unimplemented!("unsupported")
+            let job_edge = match job_node.get_distribution() {
+                Some(Distribution::Forward) => {
+                    if job_node.parallelism != child_job_node.parallelism {
+                        return Err(DagError::ParallelismMismatch(format!(
+                            "the forward partition of {:?} requires the same parallelism, {} != {}",
+                            job_node.job_id, job_node.parallelism, child_job_node.parallelism
+                        )));
+                    }
+                    JobEdge::Forward
                 }
+                Some(distribution) => JobEdge::ReBalance(distribution),
+                None if child_job_node.is_keyed_job() => JobEdge::ReBalance(Distribution::Hash),
+                None if job_node.is_reduce_job() => {
+                    // the window state is consumed by the task of the same task number,
+                    // the child with another parallelism is rebalanced after the
+                    // `KeyedStateFlatMapFunction` by `StreamGraph::set_parallelism`
+                    if job_node.parallelism != child_job_node.parallelism {
+                        return Err(DagError::ParallelismMismatch(format!(
+                            "the child of the reduce {:?} requires the same parallelism, {} != {}",
+                            job_node.job_id, job_node.parallelism, child_job_node.parallelism
+                        )));
+                    }
 
-                JobEdge::Forward
-            } else {
-                if job_node.parallelism == child_job_node.parallelism {
                     JobEdge::Forward
-                } else {
-                    JobEdge::ReBalance
+                }
+                None => {
+                    if job_node.parallelism == child_job_node.parallelism {
+                        JobEdge::Forward
+                    } else {
+                        JobEdge::ReBalance(Distribution::ReBalance)
+                    }
                 }
             };
 
@@ -173,6 +218,8 @@ impl JobGraph {
                 .add_edge(job_node_index, *child_node_index, job_edge)
                 .unwrap();
         }
+
+        Ok(())
     }
 
     pub fn build_job_nodes(&mut self, stream_graph: &StreamGraph) -> Result<(), DagError> {
@@ -206,11 +253,19 @@ impl JobGraph {
 
             // update parallelism
             let job_node = self.dag.index(*node_index).clone();
-            if job_node.is_co_process_job() {
-                // the latest OperatorId is the left OperatorId
+            if (job_node.is_co_process_job() || job_node.is_broadcast_process_job())
+                && job_node.parallelism == DEFAULT_PARALLELISM
+            {
+                // the parallelism is inherited unless it is set by `DataStream::set_parallelism`,
+                // the latest OperatorId is the left OperatorId,
+                // but the data stream of the `BroadcastProcess` is the first one
                 let left_parent_id = {
                     let source_parent_ids = &job_node.stream_nodes[0].parent_ids;
-                    *source_parent_ids.get(source_parent_ids.len() - 1).unwrap()
+                    if job_node.is_broadcast_process_job() {
+                        source_parent_ids[0]
+                    } else {
+                        *source_parent_ids.get(source_parent_ids.len() - 1).unwrap()
+                    }
                 };
 
                 // update the parallelism with parent left operator's parallelism
@@ -241,9 +296,47 @@ impl JobGraph {
             }
         }
 
+        self.inherit_parallelism();
+
         Ok(())
     }
 
+    /// the job with `DEFAULT_PARALLELISM` inherits the parallelism of its parent job,
+    /// eg: the jobs split from a `CoProcess` pipeline by the side outputs
+    fn inherit_parallelism(&mut self) {
+        loop {
+            let inherited_jobs: Vec<(NodeIndex, u16)> = self
+                .job_node_indies
+                .values()
+                .filter_map(|node_index| {
+                    let job_node = self.dag.index(*node_index);
+                    if job_node.parallelism != DEFAULT_PARALLELISM {
+                        return None;
+                    }
+
+                    job_node
+                        .parent_job_ids
+                        .iter()
+                        .map(|parent_job_id| {
+                            let parent_node_index =
+                                self.job_node_indies.get(parent_job_id).unwrap();
+                            self.dag.index(*parent_node_index).parallelism
+                        })
+                        .find(|parallelism| *parallelism != DEFAULT_PARALLELISM)
+                        .map(|parallelism| (*node_index, parallelism))
+                })
+                .collect();
+
+            if inherited_jobs.is_empty() {
+                break;
+            }
+
+            for (node_index, parallelism) in inherited_jobs {
+                self.dag.index_mut(node_index).parallelism = parallelism;
+            }
+        }
+    }
+
     pub fn build_job_node(
         &mut self,
         source_node_index: NodeIndex,
@@ -43,9 +43,13 @@ pub(crate) enum OperatorType {
     Source,
     FlatMap,
     Filter,
+    AsyncMap,
     CoProcess,
+    BroadcastProcess,
     KeyBy,
+    Partition,
     Reduce,
+    KeyedProcess,
     WatermarkAssigner,
     WindowAssigner,
     Sink,
@@ -57,9 +61,13 @@ impl<'a> From<&'a StreamOperator> for OperatorType {
             StreamOperator::StreamSource(_) => OperatorType::Source,
             StreamOperator::StreamFlatMap(_) => OperatorType::FlatMap,
             StreamOperator::StreamFilter(_) => OperatorType::Filter,
+            StreamOperator::StreamAsyncMap(_, _) => OperatorType::AsyncMap,
             StreamOperator::StreamCoProcess(_) => OperatorType::CoProcess,
+            StreamOperator::StreamBroadcastProcess(_, _) => OperatorType::BroadcastProcess,
             StreamOperator::StreamKeyBy(_) => OperatorType::KeyBy,
-            StreamOperator::StreamReduce(_) => OperatorType::Reduce,
+            StreamOperator::StreamPartition(_, _) => OperatorType::Partition,
+            StreamOperator::StreamReduce(_, _) => OperatorType::Reduce,
+            StreamOperator::StreamKeyedProcess(_) => OperatorType::KeyedProcess,
             StreamOperator::StreamWatermarkAssigner(_) => OperatorType::WatermarkAssigner,
             StreamOperator::StreamWindowAssigner(_) => OperatorType::WindowAssigner,
             StreamOperator::StreamSink(_) => OperatorType::Sink,
@@ -73,9 +81,13 @@ impl std::fmt::Display for OperatorType {
             OperatorType::Source => write!(f, "Source"),
             OperatorType::FlatMap => write!(f, "Map"),
             OperatorType::Filter => write!(f, "Filter"),
+            OperatorType::AsyncMap => write!(f, "AsyncMap"),
             OperatorType::CoProcess => write!(f, "CoProcess"),
+            OperatorType::BroadcastProcess => write!(f, "BroadcastProcess"),
             OperatorType::KeyBy => write!(f, "KeyBy"),
+            OperatorType::Partition => write!(f, "Partition"),
             OperatorType::Reduce => write!(f, "Reduce"),
+            OperatorType::KeyedProcess => write!(f, "KeyedProcess"),
             OperatorType::WatermarkAssigner => write!(f, "WatermarkAssigner"),
             OperatorType::WindowAssigner => write!(f, "WindowAssigner"),
             OperatorType::Sink => write!(f, "Sink"),
@@ -83,6 +95,24 @@ impl std::fmt::Display for OperatorType {
     }
 }
 
+/// How the operator is chained with its upstream and downstream operators in a job
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub(crate) enum ChainingStrategy {
+    /// chain with the upstream and downstream operators whenever possible
+    Always,
+    /// start a new job from the operator, see `DataStream::start_new_chain`
+    Head,
+    /// neither chain with the upstream nor the downstream operators,
+    /// see `DataStream::disable_chaining`
+    Never,
+}
+
+impl Default for ChainingStrategy {
+    fn default() -> Self {
+        ChainingStrategy::Always
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum DagError {
     #[error("source not found")]
@@ -93,14 +123,22 @@ pub enum DagError {
     SinkNotAtEnding,
     #[error("the operator is not combine operator")]
     NotCombineOperator,
+    #[error("operator not found")]
+    OperatorNotFound,
     #[error("parent operator not found")]
     ParentOperatorNotFound,
     #[error("child not found in a pipeline job")]
     ChildNotFoundInPipeline,
     #[error("multi-children in a pipeline job")]
     MultiChildrenInPipeline,
+    #[error("the children of the operator have been added")]
+    ChildrenAlreadyAdded,
     #[error("illegal Vec<InputSplit> len. {0}")]
     IllegalInputSplitSize(String),
+    #[error("schema mismatch. {0}")]
+    SchemaMismatch(String),
+    #[error("parallelism mismatch. {0}")]
+    ParallelismMismatch(String),
 }
 
 pub(crate) trait Label {
@@ -197,21 +235,37 @@ impl DagManager {
 mod tests {
     use std::time::Duration;
 
+    use futures::future::BoxFuture;
+
     use crate::api;
     use crate::api::data_stream::CoStream;
     use crate::api::data_stream::{TConnectedStreams, TKeyedStream};
     use crate::api::data_stream::{TDataStream, TWindowedStream};
-    use crate::api::element::Record;
+    use crate::api::element::{types, Record};
     use crate::api::env::StreamExecutionEnvironment;
     use crate::api::function::{
-        CoProcessFunction, Context, FlatMapFunction, Function, InputFormat, InputSplit,
-        InputSplitSource, KeySelectorFunction, OutputFormat, ReduceFunction,
+        AsyncFunction, AsyncOutputMode, BroadcastProcessFunction, CoProcessFunction, Context,
+        Distribution, FlatMapFunction, Function, InputFormat, InputSplit, InputSplitSource,
+        JoinFunction, KeySelectorFunction, KeyedProcessFunction, OutputFormat,
+        ProcessWindowFunction, ReduceFunction,
     };
+    use crate::api::output_tag::OutputTag;
     use crate::api::properties::Properties;
+    use crate::api::schema::Schema;
+    use crate::api::state::{
+        BroadcastContext, KeyedProcessContext, MapStateDescriptor, ReadOnlyBroadcastContext,
+        TimeDomain,
+    };
     use crate::api::watermark::{BoundedOutOfOrdernessTimestampExtractor, TimestampAssigner};
-    use crate::api::window::SlidingEventTimeWindows;
+    use crate::api::window::{SlidingEventTimeWindows, TimeWindow};
+    use crate::dag::job_graph::JobEdge;
     use crate::dag::utils::JsonDag;
-    use crate::dag::DagManager;
+    use crate::dag::{DagManager, OperatorType};
+    use crate::functions::schema_base::key_selector::SchemaBaseKeySelector;
+    use crate::functions::schema_base::print_output_format::PrintOutputFormat;
+    use crate::functions::schema_base::reduce::{sum_i64, SchemaBaseReduceFunction};
+    use crate::functions::schema_base::timestamp_assigner::SchemaBaseTimestampAssigner;
+    use crate::functions::schema_base::top_n::{SortOrder, TopN};
 
     #[test]
     pub fn data_stream_test() {
@@ -294,6 +348,740 @@ mod tests {
         println!("{:?}", &dag_manager.physic_graph());
     }
 
+    #[test]
+    pub fn data_stream_side_output_test() {
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        let late_data_tag = OutputTag::new("late_data");
+        let ds = env
+            .register_source(MyInputFormat::new(), 2)
+            .assign_timestamps_and_watermarks(BoundedOutOfOrdernessTimestampExtractor::new(
+                Duration::from_secs(1),
+                MyTimestampAssigner::new(),
+            ))
+            .key_by(MyKeySelectorFunction::new())
+            .window(SlidingEventTimeWindows::new(
+                Duration::from_secs(60),
+                Duration::from_secs(20),
+                None,
+            ))
+            .allowed_lateness(Duration::from_secs(30))
+            .side_output_late_data(late_data_tag.clone())
+            .reduce(MyReduceFunction::new(), 3);
+
+        ds.get_side_output(late_data_tag)
+            .flat_map(MyFlatMapFunction::new())
+            .add_sink(MyOutputFormat::new(Properties::new()));
+        ds.add_sink(MyOutputFormat::new(Properties::new()));
+
+        let dag_manager = DagManager::new(&env.stream_manager.stream_graph.borrow());
+        let stream_dag = &dag_manager.stream_graph().dag;
+        println!(
+            "{}",
+            serde_json::to_string(&JsonDag::dag_json(stream_dag)).unwrap()
+        );
+
+        // the main output and the side output share the virtual sink of the reduce job
+        let side_output_nodes = stream_dag
+            .raw_nodes()
+            .iter()
+            .filter(|node| node.weight.output_tag.is_some())
+            .count();
+        assert_eq!(side_output_nodes, 1);
+        assert_eq!(dag_manager.job_graph().dag.node_count(), 4);
+    }
+
+    #[test]
+    pub fn data_stream_co_process_side_output_test() {
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        let ds = env.register_source(MyInputFormat::new(), 1);
+
+        let side_tag = OutputTag::new("side");
+        let co_stream = env
+            .register_source(MyInputFormat::new(), 2)
+            .flat_map(MyFlatMapFunction::new())
+            .connect(vec![CoStream::from(ds)], MyCoProcessFunction {});
+
+        co_stream
+            .get_side_output(side_tag)
+            .add_sink(MyOutputFormat::new(Properties::new()));
+        co_stream
+            .key_by(MyKeySelectorFunction::new())
+            .window(SlidingEventTimeWindows::new(
+                Duration::from_secs(60),
+                Duration::from_secs(20),
+                None,
+            ))
+            .reduce(MyReduceFunction::new(), 3)
+            .add_sink(MyOutputFormat::new(Properties::new()));
+
+        let dag_manager = DagManager::new(&env.stream_manager.stream_graph.borrow());
+        let job_dag = &dag_manager.job_graph().dag;
+        println!(
+            "{}",
+            serde_json::to_string(&JsonDag::dag_json(job_dag)).unwrap()
+        );
+
+        // the side output job and the `key_by` job inherit the parallelism of `CoProcess` job
+        for job_node in job_dag.raw_nodes() {
+            assert_ne!(job_node.weight.parallelism, 0);
+        }
+    }
+
+    #[test]
+    pub fn data_stream_union_test() {
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        let ds = env
+            .register_source(MyInputFormat::new(), 1)
+            .assign_timestamps_and_watermarks(BoundedOutOfOrdernessTimestampExtractor::new(
+                Duration::from_secs(1),
+                MyTimestampAssigner::new(),
+            ));
+
+        env.register_source(MyInputFormat::new(), 2)
+            .assign_timestamps_and_watermarks(BoundedOutOfOrdernessTimestampExtractor::new(
+                Duration::from_secs(1),
+                MyTimestampAssigner::new(),
+            ))
+            .union(vec![ds])
+            .key_by(MyKeySelectorFunction::new())
+            .window(SlidingEventTimeWindows::new(
+                Duration::from_secs(60),
+                Duration::from_secs(20),
+                None,
+            ))
+            .reduce(MyReduceFunction::new(), 3)
+            .add_sink(MyOutputFormat::new(Properties::new()));
+
+        let dag_manager = DagManager::new(&env.stream_manager.stream_graph.borrow());
+        let job_dag = &dag_manager.job_graph().dag;
+        println!(
+            "{}",
+            serde_json::to_string(&JsonDag::dag_json(job_dag)).unwrap()
+        );
+
+        assert_eq!(job_dag.node_count(), 5);
+    }
+
+    #[test]
+    pub fn data_stream_fan_out_test() {
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        let ds = env
+            .register_source(MyInputFormat::new(), 2)
+            .flat_map(MyFlatMapFunction::new());
+        ds.clone().add_sink(MyOutputFormat::new(Properties::new()));
+
+        let reduced = ds
+            .assign_timestamps_and_watermarks(BoundedOutOfOrdernessTimestampExtractor::new(
+                Duration::from_secs(1),
+                MyTimestampAssigner::new(),
+            ))
+            .key_by(MyKeySelectorFunction::new())
+            .window(SlidingEventTimeWindows::new(
+                Duration::from_secs(60),
+                Duration::from_secs(20),
+                None,
+            ))
+            .reduce(MyReduceFunction::new(), 3);
+        reduced
+            .clone()
+            .add_sink(MyOutputFormat::new(Properties::new()));
+        reduced
+            .flat_map(MyFlatMapFunction::new())
+            .add_sink(MyOutputFormat::new(Properties::new()));
+
+        let dag_manager = DagManager::new(&env.stream_manager.stream_graph.borrow());
+        let job_dag = &dag_manager.job_graph().dag;
+        println!(
+            "{}",
+            serde_json::to_string(&JsonDag::dag_json(job_dag)).unwrap()
+        );
+
+        // source, sink, key_by, reduce, keyed state, sink, flat_map->sink
+        assert_eq!(job_dag.node_count(), 7);
+        for job_node in job_dag.raw_nodes() {
+            assert_ne!(job_node.weight.parallelism, 0);
+        }
+    }
+
+    #[test]
+    pub fn data_stream_keyed_process_test() {
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        env.register_source(MyInputFormat::new(), 2)
+            .key_by(MyKeySelectorFunction::new())
+            .process(MyKeyedProcessFunction {}, 3)
+            .flat_map(MyFlatMapFunction::new())
+            .add_sink(MyOutputFormat::new(Properties::new()));
+
+        let dag_manager = DagManager::new(&env.stream_manager.stream_graph.borrow());
+        let job_dag = &dag_manager.job_graph().dag;
+        println!(
+            "{}",
+            serde_json::to_string(&JsonDag::dag_json(job_dag)).unwrap()
+        );
+
+        // source->key_by, keyed_process->flat_map->sink
+        assert_eq!(job_dag.node_count(), 2);
+        for job_node in job_dag.raw_nodes() {
+            let stream_nodes = &job_node.weight.stream_nodes;
+            if stream_nodes
+                .iter()
+                .any(|x| x.operator_type == OperatorType::KeyedProcess)
+            {
+                assert_eq!(job_node.weight.parallelism, 3);
+            }
+        }
+        for job_edge in job_dag.raw_edges() {
+            match job_edge.weight {
+                JobEdge::ReBalance(distribution) => assert_eq!(distribution, Distribution::Hash),
+                JobEdge::Forward => panic!("the keyed process job must be partitioned by key"),
+            }
+        }
+    }
+
+    #[test]
+    pub fn data_stream_window_process_test() {
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        env.register_source(MyInputFormat::new(), 2)
+            .assign_timestamps_and_watermarks(BoundedOutOfOrdernessTimestampExtractor::new(
+                Duration::from_secs(1),
+                MyTimestampAssigner::new(),
+            ))
+            .key_by(MyKeySelectorFunction::new())
+            .window(SlidingEventTimeWindows::new(
+                Duration::from_secs(60),
+                Duration::from_secs(20),
+                None,
+            ))
+            .reduce_with_process(MyReduceFunction::new(), MyProcessWindowFunction {}, 3)
+            .add_sink(MyOutputFormat::new(Properties::new()));
+
+        let dag_manager = DagManager::new(&env.stream_manager.stream_graph.borrow());
+        let job_dag = &dag_manager.job_graph().dag;
+        println!(
+            "{}",
+            serde_json::to_string(&JsonDag::dag_json(job_dag)).unwrap()
+        );
+
+        // source->watermark->key_by, window_assigner->window_process->sink
+        assert_eq!(job_dag.node_count(), 2);
+        let window_job = job_dag
+            .raw_nodes()
+            .iter()
+            .find(|job_node| {
+                job_node
+                    .weight
+                    .stream_nodes
+                    .iter()
+                    .any(|x| x.operator_type == OperatorType::WindowAssigner)
+            })
+            .unwrap();
+        assert_eq!(window_job.weight.parallelism, 3);
+        assert!(window_job
+            .weight
+            .stream_nodes
+            .iter()
+            .any(|x| x.operator_type == OperatorType::KeyedProcess));
+    }
+
+    #[test]
+    pub fn data_stream_rolling_reduce_test() {
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        env.register_source(MyInputFormat::new(), 2)
+            .key_by(MyKeySelectorFunction::new())
+            .idle_state_retention(Duration::from_secs(3600))
+            .reduce(MyReduceFunction::new(), 3)
+            .add_sink(MyOutputFormat::new(Properties::new()));
+
+        let dag_manager = DagManager::new(&env.stream_manager.stream_graph.borrow());
+        let job_dag = &dag_manager.job_graph().dag;
+
+        // source->key_by, rolling_reduce->sink
+        assert_eq!(job_dag.node_count(), 2);
+        let reduce_job = job_dag
+            .raw_nodes()
+            .iter()
+            .find(|job_node| {
+                job_node
+                    .weight
+                    .stream_nodes
+                    .iter()
+                    .any(|x| x.operator_type == OperatorType::KeyedProcess)
+            })
+            .unwrap();
+        assert_eq!(reduce_job.weight.parallelism, 3);
+    }
+
+    #[test]
+    pub fn data_stream_top_n_test() {
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        let top_n = TopN::new(
+            10,
+            vec![(1, SortOrder::Desc)],
+            vec![0],
+            &[types::I64, types::I64],
+        );
+        env.register_source(MyInputFormat::new(), 2)
+            .assign_timestamps_and_watermarks(BoundedOutOfOrdernessTimestampExtractor::new(
+                Duration::from_secs(1),
+                MyTimestampAssigner::new(),
+            ))
+            .key_by(MyKeySelectorFunction::new())
+            .window(SlidingEventTimeWindows::new(
+                Duration::from_secs(60),
+                Duration::from_secs(20),
+                None,
+            ))
+            .top_n(top_n, 3)
+            .add_sink(MyOutputFormat::new(Properties::new()));
+
+        let dag_manager = DagManager::new(&env.stream_manager.stream_graph.borrow());
+        let job_dag = &dag_manager.job_graph().dag;
+
+        // source->watermark->key_by, window_assigner->window_process->key_by, top_n->sink
+        assert_eq!(job_dag.node_count(), 3);
+        let keyed_process_jobs: Vec<u16> = job_dag
+            .raw_nodes()
+            .iter()
+            .filter(|job_node| {
+                job_node
+                    .weight
+                    .stream_nodes
+                    .iter()
+                    .any(|x| x.operator_type == OperatorType::KeyedProcess)
+            })
+            .map(|job_node| job_node.weight.parallelism)
+            .collect();
+        assert_eq!(keyed_process_jobs, vec![3, 3]);
+    }
+
+    #[test]
+    pub fn data_stream_schema_test() {
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        let field_types = [types::BYTES, types::I64, types::U64];
+        let data_stream = env
+            .register_source(MyInputFormat::new(), 2)
+            .with_schema(Schema::from_names(&["name", "value", "ts"], &field_types))
+            .assign_timestamps_and_watermarks(BoundedOutOfOrdernessTimestampExtractor::new(
+                Duration::from_secs(1),
+                SchemaBaseTimestampAssigner::new(2, &field_types),
+            ))
+            .key_by(SchemaBaseKeySelector::new(vec![0], &field_types))
+            .window(SlidingEventTimeWindows::new(
+                Duration::from_secs(60),
+                Duration::from_secs(20),
+                None,
+            ))
+            .aggregate(
+                SchemaBaseReduceFunction::new(vec![sum_i64(1)], &field_types),
+                3,
+            );
+
+        // the key followed by the reduced values
+        let schema = data_stream.get_schema();
+        assert_eq!(
+            schema,
+            Schema::from_names(&["name", "value"], &[types::BYTES, types::I64])
+        );
+        data_stream.add_sink(PrintOutputFormat::new(schema.get_field_types().as_slice()));
+
+        let dag_manager = DagManager::new(&env.stream_manager.stream_graph.borrow());
+        let dag_json =
+            serde_json::to_string(&JsonDag::dag_json(&dag_manager.stream_graph().dag)).unwrap();
+        assert!(dag_json.contains(r#""name":"value","data_type":"#));
+    }
+
+    #[test]
+    #[should_panic(expected = "SchemaMismatch")]
+    pub fn data_stream_schema_mismatch_test() {
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        env.register_source(MyInputFormat::new(), 2)
+            .with_schema(Schema::from_types(&[types::BYTES, types::I64]))
+            .key_by(SchemaBaseKeySelector::new(
+                vec![0],
+                &[types::BYTES, types::U64],
+            ));
+    }
+
+    #[test]
+    pub fn data_stream_join_test() {
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        let ds = env
+            .register_source(MyInputFormat::new(), 2)
+            .assign_timestamps_and_watermarks(BoundedOutOfOrdernessTimestampExtractor::new(
+                Duration::from_secs(1),
+                MyTimestampAssigner::new(),
+            ));
+
+        env.register_source(MyInputFormat::new(), 1)
+            .assign_timestamps_and_watermarks(BoundedOutOfOrdernessTimestampExtractor::new(
+                Duration::from_secs(1),
+                MyTimestampAssigner::new(),
+            ))
+            .key_by(MyKeySelectorFunction::new())
+            .join(ds)
+            .where_key(MyKeySelectorFunction::new())
+            .window(SlidingEventTimeWindows::new(
+                Duration::from_secs(60),
+                Duration::from_secs(20),
+                None,
+            ))
+            .apply(MyJoinFunction {}, 3)
+            .add_sink(MyOutputFormat::new(Properties::new()));
+
+        let dag_manager = DagManager::new(&env.stream_manager.stream_graph.borrow());
+        let job_dag = &dag_manager.job_graph().dag;
+        println!(
+            "{}",
+            serde_json::to_string(&JsonDag::dag_json(job_dag)).unwrap()
+        );
+
+        // source->watermark->key_by, source->watermark->key_by, join->sink
+        assert_eq!(job_dag.node_count(), 3);
+        assert_eq!(job_dag.edge_count(), 2);
+        for job_edge in job_dag.raw_edges() {
+            match job_edge.weight {
+                JobEdge::ReBalance(distribution) => assert_eq!(distribution, Distribution::Hash),
+                JobEdge::Forward => panic!("the join job must be partitioned by key"),
+            }
+        }
+    }
+
+    #[test]
+    pub fn data_stream_async_map_test() {
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        env.register_source(MyInputFormat::new(), 2)
+            .async_map(
+                MyAsyncFunction {},
+                100,
+                Duration::from_secs(3),
+                AsyncOutputMode::Ordered,
+            )
+            .flat_map(MyFlatMapFunction::new())
+            .add_sink(MyOutputFormat::new(Properties::new()));
+
+        let dag_manager = DagManager::new(&env.stream_manager.stream_graph.borrow());
+        let job_dag = &dag_manager.job_graph().dag;
+
+        // the async map is chained with the source
+        assert_eq!(job_dag.node_count(), 1);
+        let stream_nodes = &job_dag.raw_nodes()[0].weight.stream_nodes;
+        assert_eq!(stream_nodes[1].operator_type, OperatorType::AsyncMap);
+    }
+
+    #[test]
+    pub fn data_stream_broadcast_test() {
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        let broadcast_stream = env
+            .register_source(MyInputFormat::new(), 1)
+            .broadcast(MapStateDescriptor::new("rules"));
+
+        env.register_source(MyInputFormat::new(), 2)
+            .connect_broadcast(broadcast_stream, MyBroadcastProcessFunction {})
+            .add_sink(MyOutputFormat::new(Properties::new()));
+
+        let dag_manager = DagManager::new(&env.stream_manager.stream_graph.borrow());
+        let job_dag = &dag_manager.job_graph().dag;
+        println!(
+            "{}",
+            serde_json::to_string(&JsonDag::dag_json(job_dag)).unwrap()
+        );
+
+        // source, source->broadcast, broadcast_process->sink
+        assert_eq!(job_dag.node_count(), 3);
+        let mut num_re_balance = 0;
+        for job_edge in job_dag.raw_edges() {
+            let parent = &job_dag[job_edge.source()];
+            let child = &job_dag[job_edge.target()];
+            // the parallelism is inherited from the data stream
+            assert_eq!(child.parallelism, 2);
+
+            match job_edge.weight {
+                JobEdge::ReBalance(distribution) => {
+                    assert_eq!(distribution, Distribution::Broadcast);
+                    assert_eq!(parent.parallelism, 1);
+                    num_re_balance += 1;
+                }
+                JobEdge::Forward => assert_eq!(parent.parallelism, 2),
+            }
+        }
+        assert_eq!(num_re_balance, 1);
+    }
+
+    #[test]
+    pub fn data_stream_partition_test() {
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        env.register_source(MyInputFormat::new(), 2)
+            .rebalance()
+            .flat_map(MyFlatMapFunction::new())
+            .add_sink(MyOutputFormat::new(Properties::new()));
+
+        let dag_manager = DagManager::new(&env.stream_manager.stream_graph.borrow());
+        let job_dag = &dag_manager.job_graph().dag;
+        println!(
+            "{}",
+            serde_json::to_string(&JsonDag::dag_json(job_dag)).unwrap()
+        );
+
+        // source->rebalance, flat_map->sink
+        assert_eq!(job_dag.node_count(), 2);
+        assert_eq!(job_dag.edge_count(), 1);
+        for job_edge in job_dag.raw_edges() {
+            match job_edge.weight {
+                JobEdge::ReBalance(distribution) => {
+                    assert_eq!(distribution, Distribution::ReBalance)
+                }
+                JobEdge::Forward => panic!("the rebalance edge is expected"),
+            }
+        }
+    }
+
+    #[test]
+    pub fn data_stream_parallelism_test() {
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        env.register_source(MyInputFormat::new(), 1)
+            .flat_map(MyFlatMapFunction::new())
+            .set_parallelism(3)
+            .add_sink(MyOutputFormat::new(Properties::new()));
+
+        let dag_manager = DagManager::new(&env.stream_manager.stream_graph.borrow());
+        let job_dag = &dag_manager.job_graph().dag;
+        println!(
+            "{}",
+            serde_json::to_string(&JsonDag::dag_json(job_dag)).unwrap()
+        );
+
+        // source->rebalance, flat_map->sink
+        assert_eq!(job_dag.node_count(), 2);
+        for job_edge in job_dag.raw_edges() {
+            assert_eq!(job_dag[job_edge.source()].parallelism, 1);
+            assert_eq!(job_dag[job_edge.target()].parallelism, 3);
+            match job_edge.weight {
+                JobEdge::ReBalance(distribution) => {
+                    assert_eq!(distribution, Distribution::ReBalance)
+                }
+                JobEdge::Forward => panic!("the records must be rebalanced"),
+            }
+        }
+    }
+
+    #[test]
+    pub fn data_stream_reduce_parallelism_test() {
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        env.register_source(MyInputFormat::new(), 1)
+            .assign_timestamps_and_watermarks(BoundedOutOfOrdernessTimestampExtractor::new(
+                Duration::from_secs(1),
+                MyTimestampAssigner::new(),
+            ))
+            .key_by(MyKeySelectorFunction::new())
+            .window(SlidingEventTimeWindows::new(
+                Duration::from_secs(60),
+                Duration::from_secs(20),
+                None,
+            ))
+            .reduce(MyReduceFunction::new(), 2)
+            .flat_map(MyFlatMapFunction::new())
+            .set_parallelism(3)
+            .add_sink(MyOutputFormat::new(Properties::new()));
+
+        let dag_manager = DagManager::new(&env.stream_manager.stream_graph.borrow());
+        let job_dag = &dag_manager.job_graph().dag;
+        println!(
+            "{}",
+            serde_json::to_string(&JsonDag::dag_json(job_dag)).unwrap()
+        );
+
+        // source->watermark->key_by, window->reduce, keyed_state_flat_map->rebalance,
+        // flat_map->sink
+        assert_eq!(job_dag.node_count(), 4);
+        for job_edge in job_dag.raw_edges() {
+            let parent = &job_dag[job_edge.source()];
+            let child = &job_dag[job_edge.target()];
+            let is_reduce = parent
+                .stream_nodes
+                .iter()
+                .any(|x| x.operator_type == OperatorType::Reduce);
+            if is_reduce {
+                // the window state is consumed by the same task number
+                assert_eq!(child.parallelism, 2);
+                match job_edge.weight {
+                    JobEdge::Forward => {}
+                    JobEdge::ReBalance(_) => panic!("the reduce must be forwarded"),
+                }
+            } else if child.parallelism == 3 {
+                assert_eq!(parent.parallelism, 2);
+                match job_edge.weight {
+                    JobEdge::ReBalance(distribution) => {
+                        assert_eq!(distribution, Distribution::ReBalance)
+                    }
+                    JobEdge::Forward => panic!("the records must be rebalanced"),
+                }
+            }
+        }
+        let flat_map_jobs = job_dag
+            .raw_nodes()
+            .iter()
+            .filter(|job_node| job_node.weight.parallelism == 3)
+            .count();
+        assert_eq!(flat_map_jobs, 1);
+    }
+
+    #[test]
+    pub fn data_stream_chaining_test() {
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        env.register_source(MyInputFormat::new(), 2)
+            .flat_map(MyFlatMapFunction::new())
+            .start_new_chain()
+            .add_sink(MyOutputFormat::new(Properties::new()));
+
+        let dag_manager = DagManager::new(&env.stream_manager.stream_graph.borrow());
+        let job_dag = &dag_manager.job_graph().dag;
+
+        // source, flat_map->sink
+        assert_eq!(job_dag.node_count(), 2);
+        for job_edge in job_dag.raw_edges() {
+            match job_edge.weight {
+                JobEdge::Forward => {}
+                JobEdge::ReBalance(_) => panic!("the records must be forwarded"),
+            }
+        }
+        // the forward tasks are deployed together
+        let num_chains: usize = dag_manager
+            .physic_graph()
+            .task_groups
+            .values()
+            .map(|chains| chains.len())
+            .sum();
+        assert_eq!(num_chains, 2);
+
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        env.register_source(MyInputFormat::new(), 2)
+            .flat_map(MyFlatMapFunction::new())
+            .disable_chaining()
+            .flat_map(MyFlatMapFunction::new())
+            .add_sink(MyOutputFormat::new(Properties::new()));
+
+        let dag_manager = DagManager::new(&env.stream_manager.stream_graph.borrow());
+        let job_dag = &dag_manager.job_graph().dag;
+        println!(
+            "{}",
+            serde_json::to_string(&JsonDag::dag_json(job_dag)).unwrap()
+        );
+
+        // source->rescale, flat_map->rescale, flat_map->sink
+        assert_eq!(job_dag.node_count(), 3);
+        for job_node in job_dag.raw_nodes() {
+            assert_eq!(job_node.weight.parallelism, 2);
+        }
+        for job_edge in job_dag.raw_edges() {
+            match job_edge.weight {
+                JobEdge::ReBalance(distribution) => assert_eq!(distribution, Distribution::Rescale),
+                JobEdge::Forward => panic!("the records must be rescaled"),
+            }
+        }
+        // every task is deployed separately
+        let num_chains: usize = dag_manager
+            .physic_graph()
+            .task_groups
+            .values()
+            .map(|chains| chains.len())
+            .sum();
+        assert_eq!(num_chains, 6);
+    }
+
+    #[test]
+    pub fn data_stream_rescale_test() {
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        env.register_source(MyInputFormat::new(), 4)
+            .rescale()
+            .flat_map(MyFlatMapFunction::new())
+            .set_parallelism(2)
+            .add_sink(MyOutputFormat::new(Properties::new()));
+
+        let dag_manager = DagManager::new(&env.stream_manager.stream_graph.borrow());
+        let job_dag = &dag_manager.job_graph().dag;
+
+        // source->rescale, flat_map->sink
+        assert_eq!(job_dag.node_count(), 2);
+        for job_edge in job_dag.raw_edges() {
+            match job_edge.weight {
+                JobEdge::ReBalance(distribution) => assert_eq!(distribution, Distribution::Rescale),
+                JobEdge::Forward => panic!("the records must be rescaled"),
+            }
+        }
+
+        // each source task is only connected to the flat_map task of its partitions
+        let execution_dag = &dag_manager.execution_graph().dag;
+        assert_eq!(execution_dag.edge_count(), 4);
+        for execution_edge in execution_dag.raw_edges() {
+            let parent = &execution_dag[execution_edge.source()];
+            let child = &execution_dag[execution_edge.target()];
+            assert_eq!(parent.task_id.task_number / 2, child.task_id.task_number);
+        }
+    }
+
+    #[test]
+    pub fn data_stream_forward_test() {
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        env.register_source(MyInputFormat::new(), 2)
+            .forward()
+            .flat_map(MyFlatMapFunction::new())
+            .add_sink(MyOutputFormat::new(Properties::new()));
+
+        let dag_manager = DagManager::new(&env.stream_manager.stream_graph.borrow());
+        let job_dag = &dag_manager.job_graph().dag;
+
+        // source->forward, flat_map->sink
+        assert_eq!(job_dag.node_count(), 2);
+        for job_edge in job_dag.raw_edges() {
+            match job_edge.weight {
+                JobEdge::Forward => {}
+                JobEdge::ReBalance(_) => panic!("the records must be forwarded"),
+            }
+        }
+
+        let execution_dag = &dag_manager.execution_graph().dag;
+        assert_eq!(execution_dag.edge_count(), 2);
+        for execution_edge in execution_dag.raw_edges() {
+            let parent = &execution_dag[execution_edge.source()];
+            let child = &execution_dag[execution_edge.target()];
+            assert_eq!(parent.task_id.task_number, child.task_id.task_number);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "ParallelismMismatch")]
+    pub fn data_stream_forward_parallelism_test() {
+        let mut env = StreamExecutionEnvironment::new("job_name".to_string());
+
+        env.register_source(MyInputFormat::new(), 2)
+            .forward()
+            .flat_map(MyFlatMapFunction::new())
+            .set_parallelism(3)
+            .add_sink(MyOutputFormat::new(Properties::new()));
+
+        DagManager::new(&env.stream_manager.stream_graph.borrow());
+    }
+
     #[derive(Serialize, Deserialize, Debug)]
     pub struct MyInputFormat {}
 
@@ -427,6 +1215,10 @@ mod tests {
             record.clone()
         }
 
+        fn merge(&self, _value: &mut Record, other: &mut Record) -> Record {
+            other.clone()
+        }
+
         fn close(&mut self) -> api::Result<()> {
             Ok(())
         }
@@ -496,4 +1288,160 @@ mod tests {
             "MyCoProcessFunction"
         }
     }
+
+    pub struct MyProcessWindowFunction {}
+
+    impl ProcessWindowFunction for MyProcessWindowFunction {
+        fn open(&mut self, _context: &Context) -> api::Result<()> {
+            Ok(())
+        }
+
+        fn process(
+            &mut self,
+            _key: &Record,
+            _window: &TimeWindow,
+            elements: Box<dyn Iterator<Item = Record>>,
+        ) -> Box<dyn Iterator<Item = Record>> {
+            Box::new(elements.take(1))
+        }
+
+        fn close(&mut self) -> api::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Function for MyProcessWindowFunction {
+        fn get_name(&self) -> &str {
+            "MyProcessWindowFunction"
+        }
+    }
+
+    pub struct MyKeyedProcessFunction {}
+
+    impl KeyedProcessFunction for MyKeyedProcessFunction {
+        fn open(&mut self, _context: &Context) -> api::Result<()> {
+            Ok(())
+        }
+
+        fn process_element(
+            &mut self,
+            record: Record,
+            context: &mut KeyedProcessContext,
+        ) -> Box<dyn Iterator<Item = Record>> {
+            let timestamp = context.timestamp();
+            context.get_value_state("last").update(record);
+            context
+                .timer_service()
+                .register_event_time_timer(timestamp + 60000);
+            Box::new(vec![].into_iter())
+        }
+
+        fn on_timer(
+            &mut self,
+            _timestamp: u64,
+            _time_domain: TimeDomain,
+            context: &mut KeyedProcessContext,
+        ) -> Box<dyn Iterator<Item = Record>> {
+            let mut state = context.get_value_state("last");
+            let records: Vec<Record> = state.value().cloned().into_iter().collect();
+            state.clear();
+            Box::new(records.into_iter())
+        }
+
+        fn close(&mut self) -> api::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Function for MyKeyedProcessFunction {
+        fn get_name(&self) -> &str {
+            "MyKeyedProcessFunction"
+        }
+    }
+
+    pub struct MyJoinFunction {}
+
+    impl JoinFunction for MyJoinFunction {
+        fn open(&mut self, _context: &Context) -> api::Result<()> {
+            Ok(())
+        }
+
+        fn join(&self, left: &mut Record, _right: &mut Record) -> Record {
+            left.clone()
+        }
+
+        fn close(&mut self) -> api::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Function for MyJoinFunction {
+        fn get_name(&self) -> &str {
+            "MyJoinFunction"
+        }
+    }
+
+    pub struct MyAsyncFunction {}
+
+    impl AsyncFunction for MyAsyncFunction {
+        fn open(&mut self, _context: &Context) -> api::Result<()> {
+            Ok(())
+        }
+
+        fn async_invoke(&self, record: Record) -> BoxFuture<'static, Vec<Record>> {
+            Box::pin(async move { vec![record] })
+        }
+
+        fn close(&mut self) -> api::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Function for MyAsyncFunction {
+        fn get_name(&self) -> &str {
+            "MyAsyncFunction"
+        }
+    }
+
+    pub struct MyBroadcastProcessFunction {}
+
+    impl BroadcastProcessFunction for MyBroadcastProcessFunction {
+        fn open(&mut self, _context: &Context) -> api::Result<()> {
+            Ok(())
+        }
+
+        fn process_element(
+            &mut self,
+            record: Record,
+            context: &ReadOnlyBroadcastContext,
+        ) -> Box<dyn Iterator<Item = Record>> {
+            let rules = context.get_broadcast_state(&MapStateDescriptor::new("rules"));
+            if rules.is_empty() {
+                Box::new(vec![].into_iter())
+            } else {
+                Box::new(vec![record].into_iter())
+            }
+        }
+
+        fn process_broadcast_element(
+            &mut self,
+            record: Record,
+            context: &mut BroadcastContext,
+        ) -> Box<dyn Iterator<Item = Record>> {
+            context
+                .get_broadcast_state(&MapStateDescriptor::new("rules"))
+                .put(record.clone(), record);
+            Box::new(vec![].into_iter())
+        }
+
+        fn close(&mut self) -> api::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Function for MyBroadcastProcessFunction {
+        fn get_name(&self) -> &str {
+            "MyBroadcastProcessFunction"
+        }
+    }
 }
@@ -1,15 +1,20 @@
 use std::cmp::max;
 use std::collections::HashMap;
-use std::ops::Index;
+use std::ops::{Index, IndexMut};
 
-use daggy::{Dag, EdgeIndex, NodeIndex};
+use daggy::{Dag, EdgeIndex, NodeIndex, Walker};
 
+use crate::api::function::{Distribution, Partitioner};
 use crate::api::operator::{
     DefaultStreamOperator, FunctionCreator, StreamOperator, TStreamOperator, DEFAULT_PARALLELISM,
 };
+use crate::api::output_tag::OutputTag;
 use crate::api::runtime::OperatorId;
+use crate::api::schema::Schema;
+use crate::api::window::WindowAssigner;
 use crate::dag::utils::JsonDag;
-use crate::dag::{DagError, Label, OperatorType};
+use crate::dag::{ChainingStrategy, DagError, Label, OperatorType};
+use crate::functions::partitioner::{RebalancePartitioner, RescalePartitioner};
 use crate::functions::system::keyed_state_flat_map::KeyedStateFlatMapFunction;
 use crate::functions::system::system_input_format::SystemInputFormat;
 use crate::functions::system::system_output_format::SystemOutputFormat;
@@ -23,6 +28,13 @@ pub struct StreamNode {
     pub(crate) operator_name: String,
     pub(crate) operator_type: OperatorType,
     pub(crate) fn_creator: FunctionCreator,
+    /// the side output consumed by the virtual source, `None` is the main output
+    pub(crate) output_tag: Option<OutputTag>,
+    pub(crate) chaining_strategy: ChainingStrategy,
+    /// the schema of the records emitted by the operator, empty if it is undeclared
+    pub(crate) schema: Schema,
+    /// the distribution of the partition operator, `None` for the other operators
+    pub(crate) distribution: Option<Distribution>,
 }
 
 impl Label for StreamNode {
@@ -68,6 +80,25 @@ impl StreamGraph {
             .map(|x| &x.weight)
     }
 
+    pub fn get_stream_children(&self, operator_id: OperatorId) -> Vec<&StreamNode> {
+        self.dag
+            .raw_nodes()
+            .iter()
+            .filter(|x| x.weight.parent_ids.contains(&operator_id))
+            .map(|x| &x.weight)
+            .collect()
+    }
+
+    /// the side outputs of the operator that are consumed by `DataStream::get_side_output`
+    pub fn get_side_outputs(&self, operator_id: OperatorId) -> Vec<OutputTag> {
+        self.get_stream_children(operator_id)
+            .into_iter()
+            .filter(|stream_node| stream_node.operator_type == OperatorType::Sink)
+            .flat_map(|stream_node| self.get_stream_children(stream_node.id))
+            .filter_map(|stream_node| stream_node.output_tag.clone())
+            .collect()
+    }
+
     pub(crate) fn to_string(&self) -> String {
         JsonDag::dag_json(&self.dag).to_string()
     }
@@ -118,6 +149,15 @@ impl RawStreamGraph {
         operators
     }
 
+    pub fn get_window_assigner(&self, operator_id: OperatorId) -> Option<&dyn WindowAssigner> {
+        match self.operators.get(&operator_id) {
+            Some((_, StreamOperator::StreamWindowAssigner(stream_operator))) => {
+                Some(stream_operator.operator_fn.as_ref())
+            }
+            _ => None,
+        }
+    }
+
     pub fn get_operators(&self) -> HashMap<OperatorId, &StreamOperator> {
         let operator_ids: Vec<OperatorId> = self.operators.iter().map(|(x, _)| *x).collect();
 
@@ -163,6 +203,21 @@ impl RawStreamGraph {
         parent_operator_ids: Vec<OperatorId>,
         parallelism: u16,
     ) -> Result<OperatorId, DagError> {
+        let input_schema = self.get_input_schema(&parent_operator_ids)?;
+        let expected_schema = operator.get_input_schema();
+        if !input_schema.is_empty()
+            && !expected_schema.is_empty()
+            && !input_schema.is_compatible(&expected_schema)
+        {
+            return Err(DagError::SchemaMismatch(format!(
+                "{} expects {}, but the upstream emits {}",
+                operator.get_operator_name(),
+                expected_schema,
+                input_schema
+            )));
+        }
+        let schema = self.get_output_schema(&operator, input_schema, &parent_operator_ids);
+
         let operator_id = self.id_gen;
         self.id_gen.0 = self.id_gen.0 + 1;
 
@@ -173,6 +228,10 @@ impl RawStreamGraph {
             operator_name: operator.get_operator_name().to_string(),
             operator_type: OperatorType::from(&operator),
             fn_creator: operator.get_fn_creator(),
+            output_tag: None,
+            chaining_strategy: ChainingStrategy::default(),
+            schema,
+            distribution: operator.get_distribution(),
         };
 
         let node_index = self.dag.add_node(stream_node.clone());
@@ -225,25 +284,52 @@ impl RawStreamGraph {
                 self.add_operator0(operator, parent_operator_ids, parallelism)
             }
         } else if parent_operator_ids.len() == 1 {
-            let p_operator_id = parent_operator_ids[0];
+            // the window state of reduce can only be consumed once,
+            // so the children of a reduce share the `KeyedStateFlatMapFunction`
+            let p_operator_id = self
+                .get_keyed_state_flat_map(parent_operator_ids[0])
+                .unwrap_or(parent_operator_ids[0]);
+            // the children of the operator with chaining disabled are never pipelined,
+            // they consume the records rescaled by the partition
+            let p_operator_id = if self.is_chaining_disabled(p_operator_id) {
+                self.get_chaining_disabled_partition(p_operator_id)?
+            } else {
+                p_operator_id
+            };
+            self.split_pipelined_children(p_operator_id)?;
+
             let (p_node_index, _) = self.operators.get(&p_operator_id).unwrap();
             let p_stream_node = self.dag.index(*p_node_index);
 
             let p_parallelism = p_stream_node.parallelism;
             let p_operator_type = p_stream_node.operator_type;
 
+            // the virtual sink is shared by the main output and the side outputs,
+            // so the operator can't be pipelined once a side output is created
+            let p_virtual_sink_id = self.get_virtual_sink(p_operator_id);
             let pipeline =
                 self.is_pipeline(operator_type, parallelism, p_operator_type, p_parallelism)?;
-            if pipeline {
+            if pipeline && p_virtual_sink_id.is_none() {
                 // tow types of parallelism inherit
                 // 1. Forward:  source->map
                 // 2. Backward: window->reduce
                 let parallelism = max(parallelism, p_parallelism);
-                self.add_operator0(operator, parent_operator_ids, parallelism)
+                self.add_operator0(operator, vec![p_operator_id], parallelism)
             } else {
-                let vir_sink = self.create_virtual_sink(p_parallelism);
-                let vir_operator_id =
-                    self.add_operator0(vir_sink, vec![p_operator_id], p_parallelism)?;
+                // the pipeline is broken by the shared virtual sink, inherit as the pipeline
+                let parallelism = if pipeline {
+                    max(parallelism, p_parallelism)
+                } else {
+                    parallelism
+                };
+
+                let vir_operator_id = match p_virtual_sink_id {
+                    Some(vir_operator_id) => vir_operator_id,
+                    None => {
+                        let vir_sink = self.create_virtual_sink(p_parallelism);
+                        self.add_operator0(vir_sink, vec![p_operator_id], p_parallelism)?
+                    }
+                };
 
                 let vir_source = self.create_virtual_source(parallelism);
                 let vir_operator_id =
@@ -259,19 +345,29 @@ impl RawStreamGraph {
                 self.add_operator0(operator, vec![vir_operator_id], parallelism)
             }
         } else {
-            if operator_type != OperatorType::CoProcess {
+            // the `KeyedProcess` combines the keyed streams, eg: join
+            if operator_type != OperatorType::CoProcess
+                && operator_type != OperatorType::KeyedProcess
+                && operator_type != OperatorType::BroadcastProcess
+            {
                 return Err(DagError::NotCombineOperator);
             }
 
             let mut new_p_operator_ids = Vec::new();
             for p_operator_id in parent_operator_ids {
+                self.split_pipelined_children(p_operator_id)?;
+
                 let (p_node_index, _) = self.operators.get(&p_operator_id).unwrap();
                 let p_stream_node = self.dag.index(*p_node_index);
 
                 let p_parallelism = p_stream_node.parallelism;
-                let vir_sink = self.create_virtual_sink(0);
-                let vir_operator_id =
-                    self.add_operator0(vir_sink, vec![p_operator_id], p_parallelism)?;
+                let vir_operator_id = match self.get_virtual_sink(p_operator_id) {
+                    Some(vir_operator_id) => vir_operator_id,
+                    None => {
+                        let vir_sink = self.create_virtual_sink(0);
+                        self.add_operator0(vir_sink, vec![p_operator_id], p_parallelism)?
+                    }
+                };
 
                 new_p_operator_ids.push(vir_operator_id);
             }
@@ -284,6 +380,494 @@ impl RawStreamGraph {
         };
     }
 
+    /// set the parallelism of the operator before its children are added,
+    /// the chain with the upstream operator is broken if the parallelism is different,
+    /// and the records of the upstream are rebalanced to the tasks of the operator
+    pub fn set_parallelism(
+        &mut self,
+        operator_id: OperatorId,
+        parallelism: u16,
+    ) -> Result<(), DagError> {
+        let node_index = self.get_childless_node(operator_id)?;
+        self.dag.index_mut(node_index).parallelism = parallelism;
+
+        // the window assigner is pipelined with the window operator,
+        // inherit the parallelism backward
+        let node_index = match self.get_window_assigner_parent(node_index) {
+            Some(window_index) => {
+                self.dag.index_mut(window_index).parallelism = parallelism;
+                window_index
+            }
+            None => node_index,
+        };
+
+        let head_index = match self.get_chained_parent(node_index) {
+            Some(p_node_index) => {
+                let p_stream_node = self.dag.index(p_node_index);
+                if p_stream_node.parallelism != parallelism {
+                    let p_operator_id = p_stream_node.id;
+                    self.split_pipelined_children(p_operator_id)?;
+                    self.add_partition(
+                        p_operator_id,
+                        Box::new(RebalancePartitioner::new()),
+                        Distribution::ReBalance,
+                    )?;
+                }
+                return Ok(());
+            }
+            None => self.get_virtual_source_head(node_index),
+        };
+
+        if let Some(head_index) = head_index {
+            self.dag.index_mut(head_index).parallelism = parallelism;
+            for tail_id in self.get_unpartitioned_upstream(head_index) {
+                let (tail_index, _) = self.operators.get(&tail_id).unwrap();
+                if self.dag.index(*tail_index).parallelism != parallelism {
+                    self.add_partition(
+                        tail_id,
+                        Box::new(RebalancePartitioner::new()),
+                        Distribution::ReBalance,
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// set the chaining strategy of the operator before its children are added,
+    /// the operator is moved to a new job unless it is already the head of a job.
+    /// the records are rescaled from the upstream if the chaining is `Never`
+    pub fn set_chaining_strategy(
+        &mut self,
+        operator_id: OperatorId,
+        chaining_strategy: ChainingStrategy,
+    ) -> Result<(), DagError> {
+        let node_index = self.get_childless_node(operator_id)?;
+        self.dag.index_mut(node_index).chaining_strategy = chaining_strategy;
+
+        let node_index = self
+            .get_window_assigner_parent(node_index)
+            .unwrap_or(node_index);
+
+        let upstream_tail_ids = match self.get_chained_parent(node_index) {
+            Some(p_node_index) => {
+                let p_operator_id = self.dag.index(p_node_index).id;
+                self.split_pipelined_children(p_operator_id)?;
+                vec![p_operator_id]
+            }
+            None => match self.get_virtual_source_head(node_index) {
+                Some(head_index) => self.get_unpartitioned_upstream(head_index),
+                None => vec![],
+            },
+        };
+
+        if chaining_strategy == ChainingStrategy::Never {
+            for tail_id in upstream_tail_ids {
+                self.add_partition(
+                    tail_id,
+                    Box::new(RescalePartitioner::new()),
+                    Distribution::Rescale,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// declare the output schema of the operator before its children are added,
+    /// it overrides the schema of the function, eg: the function derived by `Function`
+    pub fn set_schema(&mut self, operator_id: OperatorId, schema: Schema) -> Result<(), DagError> {
+        let node_index = self.get_childless_node(operator_id)?;
+        self.dag.index_mut(node_index).schema = schema;
+        Ok(())
+    }
+
+    pub fn get_schema(&self, operator_id: OperatorId) -> Result<Schema, DagError> {
+        let (node_index, _) = self
+            .operators
+            .get(&operator_id)
+            .ok_or(DagError::OperatorNotFound)?;
+        Ok(self.dag.index(*node_index).schema.clone())
+    }
+
+    /// add a virtual source that consumes the records tagged with `output_tag` by the parent
+    pub fn add_side_output(
+        &mut self,
+        parent_operator_id: OperatorId,
+        output_tag: OutputTag,
+    ) -> Result<OperatorId, DagError> {
+        let (p_node_index, _) = self
+            .operators
+            .get(&parent_operator_id)
+            .ok_or(DagError::ParentOperatorNotFound)?;
+        let p_stream_node = self.dag.index(*p_node_index);
+        if p_stream_node.operator_type == OperatorType::Sink {
+            return Err(DagError::SinkNotAtEnding);
+        }
+
+        let p_parallelism = p_stream_node.parallelism;
+        self.split_pipelined_children(parent_operator_id)?;
+
+        let vir_operator_id = match self.get_virtual_sink(parent_operator_id) {
+            Some(vir_operator_id) => vir_operator_id,
+            None => {
+                let vir_sink = self.create_virtual_sink(p_parallelism);
+                self.add_operator0(vir_sink, vec![parent_operator_id], p_parallelism)?
+            }
+        };
+
+        let vir_source = self.create_virtual_source(p_parallelism);
+        let vir_operator_id =
+            self.add_operator0(vir_source, vec![vir_operator_id], p_parallelism)?;
+
+        // the side output is not declared by the schema of the parent
+        let (node_index, _) = self.operators.get(&vir_operator_id).unwrap();
+        let vir_stream_node = self.dag.index_mut(*node_index);
+        vir_stream_node.output_tag = Some(output_tag);
+        vir_stream_node.schema = Schema::empty();
+
+        Ok(vir_operator_id)
+    }
+
+    /// split the pipelined children from the operator by the virtual sink,
+    /// then the operator can be shared by multiple children(fan-out)
+    fn split_pipelined_children(&mut self, operator_id: OperatorId) -> Result<(), DagError> {
+        let (node_index, _) = self
+            .operators
+            .get(&operator_id)
+            .ok_or(DagError::ParentOperatorNotFound)?;
+        let node_index = *node_index;
+        let parallelism = self.dag.index(node_index).parallelism;
+
+        let pipelined_children: Vec<(EdgeIndex, NodeIndex)> = self
+            .dag
+            .children(node_index)
+            .iter(&self.dag)
+            .filter(|(_edge_index, child_index)| !self.is_virtual_sink(*child_index))
+            .collect();
+        if pipelined_children.is_empty() {
+            return Ok(());
+        }
+
+        let vir_operator_id = match self.get_virtual_sink(operator_id) {
+            Some(vir_operator_id) => vir_operator_id,
+            None => {
+                let vir_sink = self.create_virtual_sink(parallelism);
+                self.add_operator0(vir_sink, vec![operator_id], parallelism)?
+            }
+        };
+
+        // the edge indices are changed by `remove_edge`, remove from the last one
+        let mut edge_indies: Vec<EdgeIndex> = pipelined_children.iter().map(|x| x.0).collect();
+        edge_indies.sort();
+        for edge_index in edge_indies.into_iter().rev() {
+            self.dag.remove_edge(edge_index);
+        }
+
+        for (_edge_index, child_index) in pipelined_children {
+            let child_parallelism = self.dag.index(child_index).parallelism;
+            let vir_source = self.create_virtual_source(child_parallelism);
+            let vir_source_id =
+                self.add_operator0(vir_source, vec![vir_operator_id], child_parallelism)?;
+            let (vir_source_index, _) = self.operators.get(&vir_source_id).unwrap();
+            let vir_source_index = *vir_source_index;
+
+            let child_stream_node = self.dag.index_mut(child_index);
+            child_stream_node.parent_ids = vec![vir_source_id];
+            let stream_edge = StreamEdge {
+                edge_id: format!("{:?}->{:?}", vir_source_id, child_stream_node.id),
+                source_id: vir_source_id,
+                target_id: child_stream_node.id,
+            };
+
+            self.dag
+                .add_edge(vir_source_index, child_index, stream_edge)
+                .unwrap();
+        }
+
+        self.stream_edges = self.dag.graph().edge_indices().collect();
+
+        Ok(())
+    }
+
+    /// insert a system `Partition` between the operator and its virtual sink,
+    /// then the job of the operator routes the records to the child jobs by the `partitioner`
+    fn add_partition(
+        &mut self,
+        operator_id: OperatorId,
+        partitioner: Box<dyn Partitioner>,
+        distribution: Distribution,
+    ) -> Result<OperatorId, DagError> {
+        let (node_index, _) = self
+            .operators
+            .get(&operator_id)
+            .ok_or(DagError::OperatorNotFound)?;
+        let node_index = *node_index;
+        let parallelism = self.dag.index(node_index).parallelism;
+
+        let vir_sink_index = self
+            .get_virtual_sink(operator_id)
+            .map(|vir_sink_id| self.operators.get(&vir_sink_id).unwrap().0);
+        if let Some(vir_sink_index) = vir_sink_index {
+            let edge_index = self
+                .dag
+                .graph()
+                .find_edge(node_index, vir_sink_index)
+                .unwrap();
+            self.dag.remove_edge(edge_index);
+        }
+
+        let partition =
+            StreamOperator::new_partition(FunctionCreator::System, partitioner, distribution);
+        let partition_id = self.add_operator0(partition, vec![operator_id], parallelism)?;
+
+        if let Some(vir_sink_index) = vir_sink_index {
+            let (partition_index, _) = self.operators.get(&partition_id).unwrap();
+            let partition_index = *partition_index;
+
+            let vir_sink_node = self.dag.index_mut(vir_sink_index);
+            vir_sink_node.parent_ids = vec![partition_id];
+            let stream_edge = StreamEdge {
+                edge_id: format!("{:?}->{:?}", partition_id, vir_sink_node.id),
+                source_id: partition_id,
+                target_id: vir_sink_node.id,
+            };
+
+            self.dag
+                .add_edge(partition_index, vir_sink_index, stream_edge)
+                .unwrap();
+        }
+
+        self.stream_edges = self.dag.graph().edge_indices().collect();
+
+        Ok(partition_id)
+    }
+
+    fn is_chaining_disabled(&self, operator_id: OperatorId) -> bool {
+        let (node_index, _) = self.operators.get(&operator_id).unwrap();
+        let stream_node = self.dag.index(*node_index);
+        stream_node.chaining_strategy == ChainingStrategy::Never
+            && stream_node.operator_type != OperatorType::Sink
+    }
+
+    /// the rescale `Partition` shared by the children of the operator with chaining disabled
+    fn get_chaining_disabled_partition(
+        &mut self,
+        operator_id: OperatorId,
+    ) -> Result<OperatorId, DagError> {
+        let (node_index, _) = self.operators.get(&operator_id).unwrap();
+        let partition_id = self
+            .dag
+            .children(*node_index)
+            .iter(&self.dag)
+            .map(|(_edge_index, child_index)| self.dag.index(child_index))
+            .find(|stream_node| stream_node.operator_type == OperatorType::Partition)
+            .map(|stream_node| stream_node.id);
+
+        match partition_id {
+            Some(partition_id) => Ok(partition_id),
+            None => self.add_partition(
+                operator_id,
+                Box::new(RescalePartitioner::new()),
+                Distribution::Rescale,
+            ),
+        }
+    }
+
+    /// the schema emitted by the parents, the combined streams have no common schema
+    fn get_input_schema(&self, parent_operator_ids: &[OperatorId]) -> Result<Schema, DagError> {
+        if parent_operator_ids.len() != 1 {
+            return Ok(Schema::empty());
+        }
+
+        let (p_node_index, _) = self
+            .operators
+            .get(&parent_operator_ids[0])
+            .ok_or(DagError::ParentOperatorNotFound)?;
+        Ok(self.dag.index(*p_node_index).schema.clone())
+    }
+
+    /// The records are forwarded by the filter, key_by, partition, sink and the assigners.
+    /// The window aggregation emits the key followed by the reduced values.
+    fn get_output_schema(
+        &self,
+        operator: &StreamOperator,
+        input_schema: Schema,
+        parent_operator_ids: &[OperatorId],
+    ) -> Schema {
+        match OperatorType::from(operator) {
+            OperatorType::Filter
+            | OperatorType::KeyBy
+            | OperatorType::Partition
+            | OperatorType::WatermarkAssigner
+            | OperatorType::WindowAssigner
+            | OperatorType::Sink => input_schema,
+            OperatorType::Reduce => {
+                let key_schema = self.get_key_schema(parent_operator_ids[0]);
+                let value_schema = operator.get_output_schema(&input_schema);
+                if key_schema.is_empty() || value_schema.is_empty() {
+                    Schema::empty()
+                } else {
+                    key_schema.join(&value_schema)
+                }
+            }
+            _ => operator.get_output_schema(&input_schema),
+        }
+    }
+
+    /// the key schema of the nearest upstream `KeyBy`
+    pub fn get_key_schema(&self, operator_id: OperatorId) -> Schema {
+        let mut operator_id = operator_id;
+        loop {
+            let (node_index, operator) = match self.operators.get(&operator_id) {
+                Some(node) => node,
+                None => return Schema::empty(),
+            };
+            let stream_node = self.dag.index(*node_index);
+            if operator.is_key_by() {
+                return operator.get_output_schema(&stream_node.schema);
+            }
+
+            match stream_node.parent_ids.as_slice() {
+                [parent_id] => operator_id = *parent_id,
+                _ => return Schema::empty(),
+            }
+        }
+    }
+
+    fn get_childless_node(&self, operator_id: OperatorId) -> Result<NodeIndex, DagError> {
+        let (node_index, _) = self
+            .operators
+            .get(&operator_id)
+            .ok_or(DagError::OperatorNotFound)?;
+        if self
+            .dag
+            .children(*node_index)
+            .iter(&self.dag)
+            .next()
+            .is_some()
+        {
+            return Err(DagError::ChildrenAlreadyAdded);
+        }
+
+        Ok(*node_index)
+    }
+
+    /// the window assigner of the window operator, eg: the reduce or the window process
+    fn get_window_assigner_parent(&self, node_index: NodeIndex) -> Option<NodeIndex> {
+        match self.dag.index(node_index).operator_type {
+            OperatorType::Reduce | OperatorType::KeyedProcess => {}
+            _ => return None,
+        }
+
+        self.dag
+            .parents(node_index)
+            .iter(&self.dag)
+            .map(|(_edge_index, p_node_index)| p_node_index)
+            .find(|p_node_index| {
+                self.dag.index(*p_node_index).operator_type == OperatorType::WindowAssigner
+            })
+    }
+
+    /// the parent pipelined with the operator in the same job, `None` if the operator is
+    /// the head of the job, eg: a source or the child of a virtual source
+    fn get_chained_parent(&self, node_index: NodeIndex) -> Option<NodeIndex> {
+        if self.dag.index(node_index).operator_type == OperatorType::Source {
+            return None;
+        }
+
+        self.dag
+            .parents(node_index)
+            .iter(&self.dag)
+            .map(|(_edge_index, p_node_index)| p_node_index)
+            .find(|p_node_index| !self.is_virtual_source(*p_node_index))
+    }
+
+    /// the virtual source that leads the job of the operator
+    fn get_virtual_source_head(&self, node_index: NodeIndex) -> Option<NodeIndex> {
+        if self.is_virtual_source(node_index) {
+            return Some(node_index);
+        }
+
+        self.dag
+            .parents(node_index)
+            .iter(&self.dag)
+            .map(|(_edge_index, p_node_index)| p_node_index)
+            .find(|p_node_index| self.is_virtual_source(*p_node_index))
+    }
+
+    /// the tail operators of the upstream jobs that do not partition the records,
+    /// the keyed and partitioned upstream are skipped
+    fn get_unpartitioned_upstream(&self, vir_source_index: NodeIndex) -> Vec<OperatorId> {
+        self.dag
+            .parents(vir_source_index)
+            .iter(&self.dag)
+            .flat_map(|(_edge_index, vir_sink_index)| {
+                self.dag.parents(vir_sink_index).iter(&self.dag)
+            })
+            .map(|(_edge_index, tail_index)| self.dag.index(tail_index))
+            .filter(|stream_node| match stream_node.operator_type {
+                OperatorType::KeyBy | OperatorType::Partition | OperatorType::Reduce => false,
+                _ => true,
+            })
+            .map(|stream_node| stream_node.id)
+            .collect()
+    }
+
+    /// the `KeyedStateFlatMapFunction` that emits the main output of the reduce
+    fn get_keyed_state_flat_map(&self, operator_id: OperatorId) -> Option<OperatorId> {
+        if !self.is_reduce_parent(operator_id) {
+            return None;
+        }
+
+        let vir_sink_id = self.get_virtual_sink(operator_id)?;
+        let (vir_sink_index, _) = self.operators.get(&vir_sink_id)?;
+        self.dag
+            .children(*vir_sink_index)
+            .iter(&self.dag)
+            .map(|(_edge_index, child_index)| child_index)
+            .filter(|child_index| self.dag.index(*child_index).output_tag.is_none())
+            .flat_map(|child_index| self.dag.children(child_index).iter(&self.dag))
+            .map(|(_edge_index, child_index)| self.dag.index(child_index))
+            .find(|stream_node| {
+                stream_node.operator_type == OperatorType::FlatMap
+                    && match stream_node.fn_creator {
+                        FunctionCreator::System => true,
+                        FunctionCreator::User => false,
+                    }
+            })
+            .map(|stream_node| stream_node.id)
+    }
+
+    fn is_virtual_source(&self, node_index: NodeIndex) -> bool {
+        let stream_node = self.dag.index(node_index);
+        stream_node.operator_type == OperatorType::Source
+            && match stream_node.fn_creator {
+                FunctionCreator::System => true,
+                FunctionCreator::User => false,
+            }
+    }
+
+    fn is_virtual_sink(&self, node_index: NodeIndex) -> bool {
+        let stream_node = self.dag.index(node_index);
+        stream_node.operator_type == OperatorType::Sink
+            && match stream_node.fn_creator {
+                FunctionCreator::System => true,
+                FunctionCreator::User => false,
+            }
+    }
+
+    /// find the virtual sink child of the operator
+    fn get_virtual_sink(&self, operator_id: OperatorId) -> Option<OperatorId> {
+        let (node_index, _) = self.operators.get(&operator_id)?;
+        self.dag
+            .children(*node_index)
+            .iter(&self.dag)
+            .find(|(_edge_index, child_index)| self.is_virtual_sink(*child_index))
+            .map(|(_edge_index, child_index)| self.dag.index(child_index).id)
+    }
+
     fn is_pipeline(
         &self,
         operator_type: OperatorType,
@@ -291,7 +875,9 @@ impl RawStreamGraph {
         p_operator_type: OperatorType,
         p_parallelism: u16,
     ) -> Result<bool, DagError> {
-        if p_operator_type == OperatorType::WindowAssigner && operator_type == OperatorType::Reduce
+        if p_operator_type == OperatorType::WindowAssigner
+            && (operator_type == OperatorType::Reduce
+                || operator_type == OperatorType::KeyedProcess)
         {
             Ok(true)
         } else {
@@ -313,34 +899,42 @@ impl RawStreamGraph {
             OperatorType::Source => match operator_type {
                 OperatorType::FlatMap
                 | OperatorType::Filter
+                | OperatorType::AsyncMap
                 | OperatorType::WatermarkAssigner
                 | OperatorType::KeyBy
+                | OperatorType::Partition
+                | OperatorType::Sink => Ok(true),
+                OperatorType::Source => Err(DagError::SourceNotAtStarting),
+                _ => Ok(false),
+            },
+            OperatorType::FlatMap
+            | OperatorType::Filter
+            | OperatorType::AsyncMap
+            | OperatorType::WatermarkAssigner
+            | OperatorType::KeyedProcess
+            | OperatorType::BroadcastProcess => match operator_type {
+                OperatorType::FlatMap
+                | OperatorType::Filter
+                | OperatorType::AsyncMap
+                | OperatorType::WatermarkAssigner
+                | OperatorType::KeyBy
+                | OperatorType::Partition
                 | OperatorType::Sink => Ok(true),
                 OperatorType::Source => Err(DagError::SourceNotAtStarting),
                 _ => Ok(false),
             },
-            OperatorType::FlatMap | OperatorType::Filter | OperatorType::WatermarkAssigner => {
-                match operator_type {
-                    OperatorType::FlatMap
-                    | OperatorType::Filter
-                    | OperatorType::WatermarkAssigner
-                    | OperatorType::KeyBy
-                    | OperatorType::Sink => Ok(true),
-                    OperatorType::Source => Err(DagError::SourceNotAtStarting),
-                    _ => Ok(false),
-                }
-            }
             OperatorType::CoProcess => match operator_type {
-                OperatorType::KeyBy => Ok(true),
+                OperatorType::KeyBy | OperatorType::Partition => Ok(true),
                 OperatorType::Source => Err(DagError::SourceNotAtStarting),
                 _ => Ok(false),
             },
-            OperatorType::KeyBy => match operator_type {
+            // the records are partitioned to the tasks of the child job
+            OperatorType::KeyBy | OperatorType::Partition => match operator_type {
                 OperatorType::Source => Err(DagError::SourceNotAtStarting),
                 _ => Ok(false),
             },
             OperatorType::WindowAssigner => match operator_type {
-                OperatorType::Reduce => Ok(true),
+                OperatorType::Reduce | OperatorType::KeyedProcess => Ok(true),
                 OperatorType::Source => Err(DagError::SourceNotAtStarting),
                 _ => Ok(false),
             },
@@ -1,5 +1,6 @@
 use crate::api::element::Record;
 use crate::api::function::{Context, FlatMapFunction, Function};
+use crate::api::schema::Schema;
 
 pub struct BroadcastFlagMapFunction {
     child_job_parallelism: u16,
@@ -44,4 +45,8 @@ impl Function for BroadcastFlagMapFunction {
     fn get_name(&self) -> &str {
         "BroadcastFlagMapFunction"
     }
+
+    fn get_output_schema(&self, input_schema: &Schema) -> Schema {
+        input_schema.clone()
+    }
 }
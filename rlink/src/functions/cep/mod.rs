@@ -0,0 +1,72 @@
+//! Complex event processing, detect the sequences of records by key, eg:
+//! "three login failures followed by a success within 10 minutes".
+//!
+//! The `Pattern` is compiled into an `Nfa` run by a keyed operator, see `TKeyedStream::pattern`.
+//! The records are buffered in the keyed state and matched in the event time order
+//! when the watermark passes, the partial matches are kept in the keyed state
+//! and checkpointed with it.
+
+use crate::api::element::Record;
+use crate::api::function::{Context, Function};
+
+pub(crate) mod nfa;
+pub mod pattern;
+
+pub use pattern::Pattern;
+
+/// The records of a match with the names of their stages, in the event time order
+#[derive(Clone, Debug)]
+pub struct PatternMatch {
+    events: Vec<(String, Record)>,
+}
+
+impl PatternMatch {
+    pub(crate) fn new(events: Vec<(String, Record)>) -> Self {
+        PatternMatch { events }
+    }
+
+    /// the records matched by the stage `name`
+    pub fn get(&self, name: &str) -> Vec<Record> {
+        self.events
+            .iter()
+            .filter(|(stage_name, _record)| stage_name.eq(name))
+            .map(|(_stage_name, record)| record.clone())
+            .collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(String, Record)> {
+        self.events.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+/// Process the matches of the `Pattern` by key
+pub trait PatternProcessFunction
+where
+    Self: Function,
+{
+    fn open(&mut self, context: &Context) -> crate::api::Result<()>;
+    /// Called for each complete match of the `key`.
+    fn process_match(
+        &mut self,
+        key: &Record,
+        pattern_match: PatternMatch,
+    ) -> Box<dyn Iterator<Item = Record>>;
+    /// Called for each partial match timed out by `Pattern::within`,
+    /// the records are emitted to the side output of `Pattern::side_output_timeout`.
+    fn process_timed_out_match(
+        &mut self,
+        _key: &Record,
+        _pattern_match: PatternMatch,
+    ) -> Box<dyn Iterator<Item = Record>> {
+        Box::new(std::iter::empty())
+    }
+    fn close(&mut self) -> crate::api::Result<()>;
+}
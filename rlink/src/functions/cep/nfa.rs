@@ -0,0 +1,200 @@
+use bytes::{Buf, BufMut, BytesMut};
+
+use crate::api::element::{types, Buffer, Record};
+use crate::functions::cep::pattern::{Contiguity, Pattern};
+use crate::functions::cep::PatternMatch;
+
+const PARTIAL_MATCH_TYPES: [u8; 4] = [types::U32, types::U32, types::U64, types::BYTES];
+
+/// A running match of the pattern, it waits for the `count`th record of the stage `stage`
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct PartialMatch {
+    stage: usize,
+    count: usize,
+    pub(crate) start_timestamp: u64,
+    /// the matched records with the index of their stages
+    events: Vec<(usize, Record)>,
+}
+
+impl PartialMatch {
+    /// pack into a record, so the partial matches are kept in the keyed state
+    pub(crate) fn to_record(&self) -> Record {
+        let mut events = BytesMut::new();
+        for (stage, record) in &self.events {
+            let values = record.values.as_slice();
+            events.put_u32(*stage as u32);
+            events.put_u64(record.timestamp);
+            events.put_u32(values.len() as u32);
+            events.put_slice(values);
+        }
+
+        let mut record = Record::with_capacity(events.len() + 16);
+        let mut writer = record.get_writer(&PARTIAL_MATCH_TYPES);
+        writer.set_u32(self.stage as u32).unwrap();
+        writer.set_u32(self.count as u32).unwrap();
+        writer.set_u64(self.start_timestamp).unwrap();
+        writer.set_bytes(events.as_ref()).unwrap();
+        record
+    }
+
+    pub(crate) fn from_record(record: &mut Record) -> Self {
+        let mut reader = record.get_reader(&PARTIAL_MATCH_TYPES);
+        let stage = reader.get_u32(0).unwrap() as usize;
+        let count = reader.get_u32(1).unwrap() as usize;
+        let start_timestamp = reader.get_u64(2).unwrap();
+
+        let mut packed = BytesMut::from(reader.get_bytes(3).unwrap());
+        let mut events = Vec::new();
+        while packed.has_remaining() {
+            let stage = packed.get_u32() as usize;
+            let timestamp = packed.get_u64();
+            let len = packed.get_u32() as usize;
+
+            let mut record = Record::new();
+            record.values = Buffer::from(packed.split_to(len));
+            record.timestamp = timestamp;
+            events.push((stage, record));
+        }
+
+        PartialMatch {
+            stage,
+            count,
+            start_timestamp,
+            events,
+        }
+    }
+
+    pub(crate) fn to_pattern_match(self, pattern: &Pattern) -> PatternMatch {
+        let events = self
+            .events
+            .into_iter()
+            .map(|(stage, record)| (pattern.stages[stage].name.clone(), record))
+            .collect();
+        PatternMatch::new(events)
+    }
+}
+
+/// The transition of a partial match by a record
+#[derive(Debug, PartialEq)]
+pub(crate) enum Transition {
+    /// the record is taken by the partial match
+    Take(PartialMatch),
+    /// the record is skipped by the partial match
+    Ignore(PartialMatch),
+    /// the partial match is broken by the record
+    Discard,
+}
+
+/// The non-deterministic automaton of the `Pattern`,
+/// each partial match is a running instance that moves forward stage by stage.
+pub(crate) struct Nfa<'a> {
+    pattern: &'a Pattern,
+}
+
+impl<'a> Nfa<'a> {
+    pub fn new(pattern: &'a Pattern) -> Self {
+        Nfa { pattern }
+    }
+
+    /// start a new partial match if the record matches the first stage
+    pub fn start(&self, record: &mut Record) -> Option<PartialMatch> {
+        let partial_match = PartialMatch {
+            stage: 0,
+            count: 0,
+            start_timestamp: record.timestamp,
+            events: Vec::new(),
+        };
+        match self.advance(partial_match, record) {
+            Transition::Take(partial_match) => Some(partial_match),
+            _ => None,
+        }
+    }
+
+    pub fn advance(&self, mut partial_match: PartialMatch, record: &mut Record) -> Transition {
+        let stage = &self.pattern.stages[partial_match.stage];
+        if stage.matches(record) {
+            partial_match
+                .events
+                .push((partial_match.stage, record.clone()));
+            partial_match.count += 1;
+            if partial_match.count == stage.times {
+                partial_match.stage += 1;
+                partial_match.count = 0;
+            }
+            return Transition::Take(partial_match);
+        }
+
+        match stage.contiguity(partial_match.count) {
+            Contiguity::Strict => Transition::Discard,
+            Contiguity::Relaxed => Transition::Ignore(partial_match),
+        }
+    }
+
+    pub fn is_complete(&self, partial_match: &PartialMatch) -> bool {
+        partial_match.stage == self.pattern.stages.len()
+    }
+
+    /// the partial match is timed out if it is not completed in `within` of the `timestamp`
+    pub fn is_timed_out(&self, partial_match: &PartialMatch, timestamp: u64) -> bool {
+        match self.pattern.within {
+            Some(within) => partial_match.start_timestamp + within < timestamp,
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::element::{types, Record};
+    use crate::functions::cep::nfa::{Nfa, PartialMatch, Transition};
+    use crate::functions::cep::pattern::Pattern;
+
+    fn record(value: i64, timestamp: u64) -> Record {
+        let mut record = Record::new();
+        record.get_writer(&[types::I64]).set_i64(value).unwrap();
+        record.timestamp = timestamp;
+        record
+    }
+
+    fn value(record: &mut Record) -> i64 {
+        record.get_reader(&[types::I64]).get_i64(0).unwrap()
+    }
+
+    #[test]
+    pub fn nfa_transition_test() {
+        let pattern = Pattern::begin("a")
+            .where_fn(|record| value(record) == 1)
+            .times(2)
+            .next("b")
+            .where_fn(|record| value(record) == 2);
+        let nfa = Nfa::new(&pattern);
+
+        assert!(nfa.start(&mut record(2, 1)).is_none());
+        let partial_match = nfa.start(&mut record(1, 1)).unwrap();
+
+        // the records of `a` are relaxed
+        let partial_match = match nfa.advance(partial_match, &mut record(3, 2)) {
+            Transition::Ignore(partial_match) => partial_match,
+            transition => panic!("unexpected transition {:?}", transition),
+        };
+        let partial_match = match nfa.advance(partial_match, &mut record(1, 3)) {
+            Transition::Take(partial_match) => partial_match,
+            transition => panic!("unexpected transition {:?}", transition),
+        };
+
+        // the partial match survives the checkpoint
+        let partial_match = PartialMatch::from_record(&mut partial_match.to_record());
+        assert_eq!(partial_match.events.len(), 2);
+        assert_eq!(partial_match.events[1].1.timestamp, 3);
+
+        // `b` must follow `a` strictly
+        assert_eq!(
+            nfa.advance(partial_match.clone(), &mut record(3, 4)),
+            Transition::Discard
+        );
+        match nfa.advance(partial_match, &mut record(2, 4)) {
+            Transition::Take(partial_match) => assert!(nfa.is_complete(&partial_match)),
+            transition => panic!("unexpected transition {:?}", transition),
+        }
+    }
+}
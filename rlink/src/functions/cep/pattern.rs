@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use crate::api::element::Record;
+use crate::api::output_tag::OutputTag;
+
+/// How the records of a stage follow the previous record of the match
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Contiguity {
+    /// the next record must match, otherwise the partial match is discarded
+    Strict,
+    /// the records not matched are skipped
+    Relaxed,
+}
+
+pub(crate) struct Stage {
+    pub(crate) name: String,
+    contiguity: Contiguity,
+    conditions: Vec<Box<dyn Fn(&mut Record) -> bool>>,
+    pub(crate) times: usize,
+    consecutive: bool,
+}
+
+impl Stage {
+    fn new(name: &str, contiguity: Contiguity) -> Self {
+        Stage {
+            name: name.to_string(),
+            contiguity,
+            conditions: Vec::new(),
+            times: 1,
+            consecutive: false,
+        }
+    }
+
+    pub(crate) fn matches(&self, record: &mut Record) -> bool {
+        self.conditions.iter().all(|condition| condition(record))
+    }
+
+    /// the contiguity before the `count`th record of the stage
+    pub(crate) fn contiguity(&self, count: usize) -> Contiguity {
+        if count == 0 {
+            self.contiguity
+        } else if self.consecutive {
+            Contiguity::Strict
+        } else {
+            Contiguity::Relaxed
+        }
+    }
+}
+
+/// A sequence of the named stages that the records of a key are matched against, eg:
+/// ```ignore
+/// Pattern::begin("failure")
+///     .where_fn(is_failure)
+///     .times(3)
+///     .followed_by("success")
+///     .where_fn(is_success)
+///     .within(Duration::from_secs(600))
+/// ```
+///
+/// The match starts from any record matching the first stage, the records after a match
+/// are matched by the skip-till-next-match strategy, see `next` and `followed_by`.
+pub struct Pattern {
+    pub(crate) stages: Vec<Stage>,
+    /// milliseconds
+    pub(crate) within: Option<u64>,
+    pub(crate) timeout_output_tag: Option<OutputTag>,
+}
+
+impl Pattern {
+    /// Start the pattern with the stage `name`
+    pub fn begin(name: &str) -> Self {
+        Pattern {
+            stages: vec![Stage::new(name, Contiguity::Relaxed)],
+            within: None,
+            timeout_output_tag: None,
+        }
+    }
+
+    /// Append the stage `name` which must match the record right after the previous stage
+    pub fn next(self, name: &str) -> Self {
+        self.append(name, Contiguity::Strict)
+    }
+
+    /// Append the stage `name` which matches a later record of the previous stage,
+    /// the records in between are skipped
+    pub fn followed_by(self, name: &str) -> Self {
+        self.append(name, Contiguity::Relaxed)
+    }
+
+    /// Add the condition of the current stage, the record matches the stage
+    /// only if all the conditions are satisfied
+    pub fn where_fn<F>(mut self, condition: F) -> Self
+    where
+        F: Fn(&mut Record) -> bool + 'static,
+    {
+        self.current_stage().conditions.push(Box::new(condition));
+        self
+    }
+
+    /// The current stage matches exactly `times` records,
+    /// the unmatched records between them are skipped unless it is `consecutive`
+    pub fn times(mut self, times: usize) -> Self {
+        if times == 0 {
+            panic!("the `times` of the stage must be positive");
+        }
+        self.current_stage().times = times;
+        self
+    }
+
+    /// The records of the current stage must be contiguous, see `times`
+    pub fn consecutive(mut self) -> Self {
+        self.current_stage().consecutive = true;
+        self
+    }
+
+    /// The whole match must complete in `window` of the event time from the first record
+    pub fn within(mut self, window: Duration) -> Self {
+        self.within = Some(window.as_millis() as u64);
+        self
+    }
+
+    /// Route the partial matches timed out by `within` to the side output `output_tag`,
+    /// see `PatternProcessFunction::process_timed_out_match`. By default they are dropped.
+    pub fn side_output_timeout(mut self, output_tag: OutputTag) -> Self {
+        self.timeout_output_tag = Some(output_tag);
+        self
+    }
+
+    fn append(mut self, name: &str, contiguity: Contiguity) -> Self {
+        if self.stages.iter().any(|stage| stage.name.eq(name)) {
+            panic!("duplicate stage name {} in the pattern", name);
+        }
+        self.stages.push(Stage::new(name, contiguity));
+        self
+    }
+
+    fn current_stage(&mut self) -> &mut Stage {
+        self.stages.last_mut().unwrap()
+    }
+}
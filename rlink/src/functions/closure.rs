@@ -0,0 +1,232 @@
+//! The adapters of the closures accepted by `DataStream::map_fn`, `flat_map_fn`,
+//! `filter_fn` and `key_by_fn`, the name of the adapter is the operator name in the metrics,
+//! so it is given by the user to tell the closures apart.
+
+use crate::api::element::Record;
+use crate::api::function::{
+    Context, FilterFunction, FlatMapFunction, Function, KeySelectorFunction,
+};
+
+pub(crate) struct MapFnFunction<F>
+where
+    F: Fn(Record) -> Record + Send + Sync + Clone + 'static,
+{
+    name: String,
+    map_fn: F,
+}
+
+impl<F> MapFnFunction<F>
+where
+    F: Fn(Record) -> Record + Send + Sync + Clone + 'static,
+{
+    pub fn new(name: &str, map_fn: F) -> Self {
+        MapFnFunction {
+            name: name.to_string(),
+            map_fn,
+        }
+    }
+}
+
+impl<F> FlatMapFunction for MapFnFunction<F>
+where
+    F: Fn(Record) -> Record + Send + Sync + Clone + 'static,
+{
+    fn open(&mut self, _context: &Context) -> crate::api::Result<()> {
+        Ok(())
+    }
+
+    fn flat_map(&mut self, record: Record) -> Box<dyn Iterator<Item = Record>> {
+        Box::new(std::iter::once((self.map_fn)(record)))
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        Ok(())
+    }
+}
+
+impl<F> Function for MapFnFunction<F>
+where
+    F: Fn(Record) -> Record + Send + Sync + Clone + 'static,
+{
+    fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+pub(crate) struct FlatMapFnFunction<F>
+where
+    F: Fn(Record) -> Vec<Record> + Send + Sync + Clone + 'static,
+{
+    name: String,
+    flat_map_fn: F,
+}
+
+impl<F> FlatMapFnFunction<F>
+where
+    F: Fn(Record) -> Vec<Record> + Send + Sync + Clone + 'static,
+{
+    pub fn new(name: &str, flat_map_fn: F) -> Self {
+        FlatMapFnFunction {
+            name: name.to_string(),
+            flat_map_fn,
+        }
+    }
+}
+
+impl<F> FlatMapFunction for FlatMapFnFunction<F>
+where
+    F: Fn(Record) -> Vec<Record> + Send + Sync + Clone + 'static,
+{
+    fn open(&mut self, _context: &Context) -> crate::api::Result<()> {
+        Ok(())
+    }
+
+    fn flat_map(&mut self, record: Record) -> Box<dyn Iterator<Item = Record>> {
+        Box::new((self.flat_map_fn)(record).into_iter())
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        Ok(())
+    }
+}
+
+impl<F> Function for FlatMapFnFunction<F>
+where
+    F: Fn(Record) -> Vec<Record> + Send + Sync + Clone + 'static,
+{
+    fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+pub(crate) struct FilterFnFunction<F>
+where
+    F: Fn(&mut Record) -> bool + Send + Sync + Clone + 'static,
+{
+    name: String,
+    filter_fn: F,
+}
+
+impl<F> FilterFnFunction<F>
+where
+    F: Fn(&mut Record) -> bool + Send + Sync + Clone + 'static,
+{
+    pub fn new(name: &str, filter_fn: F) -> Self {
+        FilterFnFunction {
+            name: name.to_string(),
+            filter_fn,
+        }
+    }
+}
+
+impl<F> FilterFunction for FilterFnFunction<F>
+where
+    F: Fn(&mut Record) -> bool + Send + Sync + Clone + 'static,
+{
+    fn open(&mut self, _context: &Context) -> crate::api::Result<()> {
+        Ok(())
+    }
+
+    fn filter(&self, record: &mut Record) -> bool {
+        (self.filter_fn)(record)
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        Ok(())
+    }
+}
+
+impl<F> Function for FilterFnFunction<F>
+where
+    F: Fn(&mut Record) -> bool + Send + Sync + Clone + 'static,
+{
+    fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+pub(crate) struct KeyByFnFunction<F>
+where
+    F: Fn(&mut Record) -> Record + Send + Sync + Clone + 'static,
+{
+    name: String,
+    key_fn: F,
+}
+
+impl<F> KeyByFnFunction<F>
+where
+    F: Fn(&mut Record) -> Record + Send + Sync + Clone + 'static,
+{
+    pub fn new(name: &str, key_fn: F) -> Self {
+        KeyByFnFunction {
+            name: name.to_string(),
+            key_fn,
+        }
+    }
+}
+
+impl<F> KeySelectorFunction for KeyByFnFunction<F>
+where
+    F: Fn(&mut Record) -> Record + Send + Sync + Clone + 'static,
+{
+    fn open(&mut self, _context: &Context) -> crate::api::Result<()> {
+        Ok(())
+    }
+
+    fn get_key(&self, record: &mut Record) -> Record {
+        (self.key_fn)(record)
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        Ok(())
+    }
+}
+
+impl<F> Function for KeyByFnFunction<F>
+where
+    F: Fn(&mut Record) -> Record + Send + Sync + Clone + 'static,
+{
+    fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::element::{types, Record};
+    use crate::api::function::{FilterFunction, FlatMapFunction, Function, KeySelectorFunction};
+    use crate::functions::closure::{
+        FilterFnFunction, FlatMapFnFunction, KeyByFnFunction, MapFnFunction,
+    };
+
+    fn record(value: i64) -> Record {
+        let mut record = Record::new();
+        record.get_writer(&[types::I64]).set_i64(value).unwrap();
+        record
+    }
+
+    fn value(record: &mut Record) -> i64 {
+        record.get_reader(&[types::I64]).get_i64(0).unwrap()
+    }
+
+    #[test]
+    pub fn closure_function_test() {
+        let mut map_fn = MapFnFunction::new("double", |mut r: Record| record(value(&mut r) * 2));
+        let mapped: Vec<i64> = map_fn
+            .flat_map(record(2))
+            .map(|mut r| value(&mut r))
+            .collect();
+        assert_eq!(mapped, vec![4]);
+        assert_eq!(map_fn.get_name(), "double");
+
+        let mut flat_map_fn = FlatMapFnFunction::new("repeat", |r: Record| vec![r.clone(), r]);
+        assert_eq!(flat_map_fn.flat_map(record(1)).count(), 2);
+
+        let filter_fn = FilterFnFunction::new("greater", |r: &mut Record| value(r) > 1);
+        assert!(!filter_fn.filter(&mut record(1)));
+        assert!(filter_fn.filter(&mut record(2)));
+
+        let key_fn = KeyByFnFunction::new("parity", |r: &mut Record| record(value(r) % 2));
+        assert_eq!(value(&mut key_fn.get_key(&mut record(3))), 1);
+    }
+}
@@ -0,0 +1,208 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::element::Record;
+use crate::api::function::{Context, Function, KeyedProcessFunction};
+use crate::api::schema::Schema;
+use crate::api::state::{KeyedProcessContext, TimeDomain};
+use crate::functions::state_record::{u64_record, u64_value};
+use crate::metrics::{register_counter, Tag};
+use crate::utils::date_time::current_timestamp_millis;
+
+const RECORD_STATE: &str = "deduplicate_record";
+const EXPIRE_STATE: &str = "deduplicate_expire";
+
+/// Which record of the duplicates is emitted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeduplicateKeep {
+    /// emit the first record of the key immediately, drop the others until the key expires
+    First,
+    /// buffer the latest record of the key, emit it when the key expires
+    Last,
+}
+
+/// Remove the records with the same key in `ttl`, the key is seen from its first record
+/// and expires `ttl` later in the `time_domain`.
+///
+/// The seen keys are kept in the keyed state, so they survive the checkpoints.
+/// The number of the dropped duplicates is reported by the counter
+/// `Deduplicate_{operator_id}_duplicates`.
+pub struct DeduplicateFunction {
+    ttl: u64,
+    keep: DeduplicateKeep,
+    time_domain: TimeDomain,
+
+    counter: Arc<AtomicU64>,
+}
+
+impl DeduplicateFunction {
+    pub fn new(ttl: Duration, keep: DeduplicateKeep, time_domain: TimeDomain) -> Self {
+        DeduplicateFunction {
+            ttl: ttl.as_millis() as u64,
+            keep,
+            time_domain,
+            counter: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    fn current_time(&self, record: &Record) -> u64 {
+        match self.time_domain {
+            TimeDomain::EventTime => record.timestamp,
+            TimeDomain::ProcessingTime => current_timestamp_millis(),
+        }
+    }
+
+    fn register_timer(&self, context: &mut KeyedProcessContext, time: u64) {
+        match self.time_domain {
+            TimeDomain::EventTime => context.timer_service().register_event_time_timer(time),
+            TimeDomain::ProcessingTime => {
+                context.timer_service().register_processing_time_timer(time)
+            }
+        }
+    }
+}
+
+impl KeyedProcessFunction for DeduplicateFunction {
+    fn open(&mut self, context: &Context) -> crate::api::Result<()> {
+        let tags = vec![
+            Tag("job_id".to_string(), context.task_id.job_id.0.to_string()),
+            Tag(
+                "task_number".to_string(),
+                context.task_id.task_number.to_string(),
+            ),
+        ];
+        let metric_name = format!("Deduplicate_{}_duplicates", context.operator_id.0);
+        register_counter(metric_name.as_str(), tags, self.counter.clone());
+
+        Ok(())
+    }
+
+    fn process_element(
+        &mut self,
+        record: Record,
+        context: &mut KeyedProcessContext,
+    ) -> Box<dyn Iterator<Item = Record>> {
+        let seen = context.get_value_state(EXPIRE_STATE).value().is_some();
+        if seen {
+            self.counter.fetch_add(1, Ordering::Relaxed);
+            if self.keep == DeduplicateKeep::Last {
+                context.get_value_state(RECORD_STATE).update(record);
+            }
+            return Box::new(std::iter::empty());
+        }
+
+        let expire_time = self.current_time(&record) + self.ttl;
+        self.register_timer(context, expire_time);
+        context
+            .get_value_state(EXPIRE_STATE)
+            .update(u64_record(expire_time));
+
+        match self.keep {
+            DeduplicateKeep::First => Box::new(vec![record].into_iter()),
+            DeduplicateKeep::Last => {
+                context.get_value_state(RECORD_STATE).update(record);
+                Box::new(std::iter::empty())
+            }
+        }
+    }
+
+    fn on_timer(
+        &mut self,
+        timestamp: u64,
+        _time_domain: TimeDomain,
+        context: &mut KeyedProcessContext,
+    ) -> Box<dyn Iterator<Item = Record>> {
+        let expire_time = context.get_value_state(EXPIRE_STATE).value().map(u64_value);
+        if expire_time != Some(timestamp) {
+            return Box::new(std::iter::empty());
+        }
+
+        context.get_value_state(EXPIRE_STATE).clear();
+        let record = context.get_value_state(RECORD_STATE).value().cloned();
+        context.get_value_state(RECORD_STATE).clear();
+
+        match record {
+            Some(record) => Box::new(vec![record].into_iter()),
+            None => Box::new(std::iter::empty()),
+        }
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        Ok(())
+    }
+}
+
+impl Function for DeduplicateFunction {
+    fn get_name(&self) -> &str {
+        "DeduplicateFunction"
+    }
+
+    fn get_output_schema(&self, input_schema: &Schema) -> Schema {
+        input_schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    use crate::api::element::{types, Record};
+    use crate::api::function::KeyedProcessFunction;
+    use crate::api::state::{KeyedProcessContext, KeyedStateStore, TimeDomain, TimerService};
+    use crate::functions::deduplicate::{DeduplicateFunction, DeduplicateKeep};
+
+    fn record(key: i64, value: i64, timestamp: u64) -> Record {
+        let mut record = Record::new();
+        let mut writer = record.get_writer(&[types::I64, types::I64]);
+        writer.set_i64(key).unwrap();
+        writer.set_i64(value).unwrap();
+        record.timestamp = timestamp;
+        record
+    }
+
+    fn value(record: &mut Record) -> i64 {
+        record
+            .get_reader(&[types::I64, types::I64])
+            .get_i64(1)
+            .unwrap()
+    }
+
+    /// process the records of key 1 at the timestamps, then fire the timer at `expire_time`
+    fn deduplicate(keep: DeduplicateKeep, timestamps: &[u64], expire_time: u64) -> Vec<i64> {
+        let mut dedup_fn =
+            DeduplicateFunction::new(Duration::from_millis(10), keep, TimeDomain::EventTime);
+        let mut state_store = KeyedStateStore::new();
+        let mut timer_service = TimerService::new();
+
+        let mut key = Record::new();
+        key.get_writer(&[types::I64]).set_i64(1).unwrap();
+        timer_service.set_current_key(key);
+
+        let mut ctx = KeyedProcessContext::new(0, 0, &mut state_store, &mut timer_service);
+        let mut records = Vec::new();
+        for (i, timestamp) in timestamps.iter().enumerate() {
+            let r = record(1, i as i64, *timestamp);
+            records.extend(dedup_fn.process_element(r, &mut ctx));
+        }
+        records.extend(dedup_fn.on_timer(expire_time, TimeDomain::EventTime, &mut ctx));
+
+        let duplicates = dedup_fn.counter.load(Ordering::Relaxed);
+        assert_eq!(duplicates as usize, timestamps.len() - 1);
+
+        records.iter_mut().map(value).collect()
+    }
+
+    #[test]
+    pub fn deduplicate_test() {
+        assert_eq!(deduplicate(DeduplicateKeep::First, &[1, 3, 5], 11), vec![0]);
+        assert_eq!(deduplicate(DeduplicateKeep::Last, &[1, 3, 5], 11), vec![2]);
+
+        // the outdated timer does not expire the key
+        assert_eq!(
+            deduplicate(DeduplicateKeep::Last, &[1, 3, 5], 13),
+            Vec::<i64>::new()
+        );
+    }
+}
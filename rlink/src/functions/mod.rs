@@ -1,6 +1,11 @@
 pub mod broadcast_flat_map;
+pub mod cep;
+pub mod closure;
+pub mod deduplicate;
 pub mod iterator;
+pub mod partitioner;
 pub mod percentile;
 pub mod round_robin_flat_map;
 pub mod schema_base;
+pub(crate) mod state_record;
 pub mod system;
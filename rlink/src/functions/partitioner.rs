@@ -0,0 +1,339 @@
+use std::cmp::max;
+use std::ops::Range;
+
+use rand::Rng;
+
+use crate::api::element::Record;
+use crate::api::function::{Context, Function, Partitioner};
+use crate::utils;
+
+/// Partition by the hash code of the record, the records with the same values are
+/// routed to the same partition. the `KeyByRunnable` partitions the keys by it.
+#[derive(Debug)]
+pub struct HashPartitioner {}
+
+impl HashPartitioner {
+    pub fn new() -> Self {
+        HashPartitioner {}
+    }
+}
+
+impl Default for HashPartitioner {
+    fn default() -> Self {
+        HashPartitioner::new()
+    }
+}
+
+impl Partitioner for HashPartitioner {
+    fn open(&mut self, _context: &Context) -> crate::api::Result<()> {
+        Ok(())
+    }
+
+    fn partition(&mut self, record: &mut Record, num_partitions: u16) -> u16 {
+        let hash_code = utils::hash::hash_code(record.values.as_slice()).unwrap_or(0);
+        (hash_code % num_partitions as u32) as u16
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        Ok(())
+    }
+}
+
+impl Function for HashPartitioner {
+    fn get_name(&self) -> &str {
+        "HashPartitioner"
+    }
+}
+
+/// Distribute the records to all the partitions by round-robin, see `DataStream::rebalance`
+#[derive(Debug)]
+pub struct RebalancePartitioner {
+    next_partition: u16,
+}
+
+impl RebalancePartitioner {
+    pub fn new() -> Self {
+        RebalancePartitioner { next_partition: 0 }
+    }
+}
+
+impl Default for RebalancePartitioner {
+    fn default() -> Self {
+        RebalancePartitioner::new()
+    }
+}
+
+impl Partitioner for RebalancePartitioner {
+    fn open(&mut self, context: &Context) -> crate::api::Result<()> {
+        // the tasks start at different partitions to spread the load
+        self.next_partition = context.task_id.task_number;
+        Ok(())
+    }
+
+    fn partition(&mut self, _record: &mut Record, num_partitions: u16) -> u16 {
+        let partition = self.next_partition % num_partitions;
+        self.next_partition = (partition + 1) % num_partitions;
+        partition
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        Ok(())
+    }
+}
+
+impl Function for RebalancePartitioner {
+    fn get_name(&self) -> &str {
+        "RebalancePartitioner"
+    }
+}
+
+/// Distribute the records by round-robin to the subset of the partitions,
+/// each task of the job owns the partitions `[task_number * n / m, (task_number + 1) * n / m)`,
+/// `n` is the number of partitions and `m` is the parallelism of the job.
+/// see `DataStream::rescale`
+#[derive(Debug)]
+pub struct RescalePartitioner {
+    task_number: u16,
+    num_tasks: u16,
+    next_partition: u16,
+}
+
+impl RescalePartitioner {
+    pub fn new() -> Self {
+        RescalePartitioner {
+            task_number: 0,
+            num_tasks: 0,
+            next_partition: 0,
+        }
+    }
+}
+
+impl Default for RescalePartitioner {
+    fn default() -> Self {
+        RescalePartitioner::new()
+    }
+}
+
+impl Partitioner for RescalePartitioner {
+    fn open(&mut self, context: &Context) -> crate::api::Result<()> {
+        self.task_number = context.task_id.task_number;
+        self.num_tasks = context.task_id.num_tasks;
+        Ok(())
+    }
+
+    fn partition(&mut self, _record: &mut Record, num_partitions: u16) -> u16 {
+        let range = rescale_partitions(self.task_number, self.num_tasks, num_partitions);
+        let size = range.end - range.start;
+
+        let partition = range.start + self.next_partition % size;
+        self.next_partition = (partition + 1 - range.start) % size;
+        partition
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        Ok(())
+    }
+}
+
+impl Function for RescalePartitioner {
+    fn get_name(&self) -> &str {
+        "RescalePartitioner"
+    }
+}
+
+/// the partitions owned by the task of the rescaled job,
+/// the `ExecutionGraph` only connects the task to them
+pub(crate) fn rescale_partitions(
+    task_number: u16,
+    num_tasks: u16,
+    num_partitions: u16,
+) -> Range<u16> {
+    let task_number = task_number as u32;
+    let num_tasks = num_tasks as u32;
+    let n = num_partitions as u32;
+
+    let start = task_number * n / num_tasks;
+    let end = max((task_number + 1) * n / num_tasks, start + 1);
+    start as u16..end as u16
+}
+
+/// Distribute the records to the partitions randomly, see `DataStream::shuffle`
+#[derive(Debug)]
+pub struct ShufflePartitioner {}
+
+impl ShufflePartitioner {
+    pub fn new() -> Self {
+        ShufflePartitioner {}
+    }
+}
+
+impl Default for ShufflePartitioner {
+    fn default() -> Self {
+        ShufflePartitioner::new()
+    }
+}
+
+impl Partitioner for ShufflePartitioner {
+    fn open(&mut self, _context: &Context) -> crate::api::Result<()> {
+        Ok(())
+    }
+
+    fn partition(&mut self, _record: &mut Record, num_partitions: u16) -> u16 {
+        rand::thread_rng().gen_range(0, num_partitions)
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        Ok(())
+    }
+}
+
+impl Function for ShufflePartitioner {
+    fn get_name(&self) -> &str {
+        "ShufflePartitioner"
+    }
+}
+
+/// Route the records to the partition of the same task number, see `DataStream::forward`.
+/// the same parallelism of the child job is checked when building the `JobGraph`
+#[derive(Debug)]
+pub struct ForwardPartitioner {
+    task_number: u16,
+}
+
+impl ForwardPartitioner {
+    pub fn new() -> Self {
+        ForwardPartitioner { task_number: 0 }
+    }
+}
+
+impl Default for ForwardPartitioner {
+    fn default() -> Self {
+        ForwardPartitioner::new()
+    }
+}
+
+impl Partitioner for ForwardPartitioner {
+    fn open(&mut self, context: &Context) -> crate::api::Result<()> {
+        self.task_number = context.task_id.task_number;
+        Ok(())
+    }
+
+    fn partition(&mut self, _record: &mut Record, _num_partitions: u16) -> u16 {
+        self.task_number
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        Ok(())
+    }
+}
+
+impl Function for ForwardPartitioner {
+    fn get_name(&self) -> &str {
+        "ForwardPartitioner"
+    }
+}
+
+/// Replicate the records to all the partitions, see `DataStream::broadcast`.
+/// the `PartitionRunnable` asks a partition for each replica of the record,
+/// the partitions are returned in turn so that each replica goes to another partition
+#[derive(Debug)]
+pub struct BroadcastPartitioner {
+    next_partition: u16,
+}
+
+impl BroadcastPartitioner {
+    pub fn new() -> Self {
+        BroadcastPartitioner { next_partition: 0 }
+    }
+}
+
+impl Default for BroadcastPartitioner {
+    fn default() -> Self {
+        BroadcastPartitioner::new()
+    }
+}
+
+impl Partitioner for BroadcastPartitioner {
+    fn open(&mut self, _context: &Context) -> crate::api::Result<()> {
+        Ok(())
+    }
+
+    fn partition(&mut self, _record: &mut Record, num_partitions: u16) -> u16 {
+        let partition = self.next_partition % num_partitions;
+        self.next_partition = (partition + 1) % num_partitions;
+        partition
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        Ok(())
+    }
+}
+
+impl Function for BroadcastPartitioner {
+    fn get_name(&self) -> &str {
+        "BroadcastPartitioner"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::element::Record;
+    use crate::api::function::Partitioner;
+    use crate::functions::partitioner::{
+        rescale_partitions, BroadcastPartitioner, RebalancePartitioner, RescalePartitioner,
+    };
+
+    #[test]
+    pub fn rebalance_partitioner_test() {
+        let mut partitioner = RebalancePartitioner::new();
+        let mut record = Record::new();
+        let partitions: Vec<u16> = (0..5)
+            .map(|_| partitioner.partition(&mut record, 3))
+            .collect();
+        assert_eq!(partitions, vec![0, 1, 2, 0, 1]);
+    }
+
+    #[test]
+    pub fn rescale_partitioner_test() {
+        let mut record = Record::new();
+
+        // 2 tasks to 4 partitions, the task 1 owns the partitions [2, 4)
+        let mut partitioner = RescalePartitioner::new();
+        partitioner.task_number = 1;
+        partitioner.num_tasks = 2;
+        let partitions: Vec<u16> = (0..3)
+            .map(|_| partitioner.partition(&mut record, 4))
+            .collect();
+        assert_eq!(partitions, vec![2, 3, 2]);
+
+        // 4 tasks to 2 partitions, the task 3 owns the partition 1
+        let mut partitioner = RescalePartitioner::new();
+        partitioner.task_number = 3;
+        partitioner.num_tasks = 4;
+        let partitions: Vec<u16> = (0..2)
+            .map(|_| partitioner.partition(&mut record, 2))
+            .collect();
+        assert_eq!(partitions, vec![1, 1]);
+    }
+
+    #[test]
+    pub fn rescale_partitions_test() {
+        assert_eq!(rescale_partitions(0, 2, 5), 0..2);
+        assert_eq!(rescale_partitions(1, 2, 5), 2..5);
+        assert_eq!(rescale_partitions(2, 3, 2), 1..2);
+    }
+
+    #[test]
+    pub fn broadcast_partitioner_test() {
+        let mut partitioner = BroadcastPartitioner::new();
+        let mut record = Record::new();
+        // the replicas of each record are sent to all the partitions
+        for _ in 0..2 {
+            let mut partitions: Vec<u16> = (0..3)
+                .map(|_| partitioner.partition(&mut record, 3))
+                .collect();
+            partitions.sort();
+            assert_eq!(partitions, vec![0, 1, 2]);
+        }
+    }
+}
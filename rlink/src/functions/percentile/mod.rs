@@ -181,8 +181,13 @@ impl<'a> Percentile<'a> {
     }
 
     pub fn merge(&mut self, percentile: &Percentile) {
-        for i in 0..self.count_container.len() {
-            self.count_container[i] += percentile.count_container[i];
+        // merge by u64 counter, a byte-wise add would lose the carry
+        let mut index = 0;
+        while index < self.count_container.len() {
+            let n = self.read(index) + percentile.read(index);
+            self.write(index, n);
+
+            index += 8;
         }
     }
 }
@@ -1,5 +1,6 @@
 use crate::api::element::Record;
 use crate::api::function::{Context, FlatMapFunction, Function};
+use crate::api::schema::Schema;
 
 pub struct RoundRobinFlagMapFunction {
     child_job_parallelism: u16,
@@ -41,4 +42,8 @@ impl Function for RoundRobinFlagMapFunction {
     fn get_name(&self) -> &str {
         "RoundRobinFlagMapFunction"
     }
+
+    fn get_output_schema(&self, input_schema: &Schema) -> Schema {
+        input_schema.clone()
+    }
 }
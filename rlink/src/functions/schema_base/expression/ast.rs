@@ -0,0 +1,439 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+
+use crate::api::element::{types, BufferReader};
+use crate::api::schema::{type_name, Schema};
+use crate::functions::schema_base::expression::{ExpressionError, Value};
+
+/// The kind of the `types::*`, the values are evaluated by the kind
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Kind {
+    Bool,
+    Int,
+    Float,
+    Str,
+}
+
+impl Kind {
+    pub fn of(data_type: u8) -> Kind {
+        match data_type {
+            types::BOOL => Kind::Bool,
+            types::F32 | types::F64 => Kind::Float,
+            types::BYTES => Kind::Str,
+            _ => Kind::Int,
+        }
+    }
+
+    fn is_numeric(&self) -> bool {
+        *self == Kind::Int || *self == Kind::Float
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    And,
+    Or,
+}
+
+impl BinaryOp {
+    fn is_arithmetic(&self) -> bool {
+        matches!(
+            self,
+            BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod
+        )
+    }
+
+    fn is_logic(&self) -> bool {
+        *self == BinaryOp::And || *self == BinaryOp::Or
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScalarFunction {
+    Upper,
+    Lower,
+    Trim,
+    Length,
+    Substr,
+    Concat,
+    Replace,
+    StartsWith,
+    EndsWith,
+    Contains,
+    Abs,
+}
+
+impl ScalarFunction {
+    pub fn from_name(name: &str) -> Option<Self> {
+        let function = match name.to_lowercase().as_str() {
+            "upper" => ScalarFunction::Upper,
+            "lower" => ScalarFunction::Lower,
+            "trim" => ScalarFunction::Trim,
+            "length" => ScalarFunction::Length,
+            "substr" => ScalarFunction::Substr,
+            "concat" => ScalarFunction::Concat,
+            "replace" => ScalarFunction::Replace,
+            "starts_with" => ScalarFunction::StartsWith,
+            "ends_with" => ScalarFunction::EndsWith,
+            "contains" => ScalarFunction::Contains,
+            "abs" => ScalarFunction::Abs,
+            _ => return None,
+        };
+        Some(function)
+    }
+
+    /// the result type of the function with the `arg_types`
+    fn return_type(&self, arg_types: &[u8]) -> Result<u8, ExpressionError> {
+        let kinds: Vec<Kind> = arg_types.iter().map(|x| Kind::of(*x)).collect();
+        let return_type = match (self, kinds.as_slice()) {
+            (ScalarFunction::Upper, [Kind::Str])
+            | (ScalarFunction::Lower, [Kind::Str])
+            | (ScalarFunction::Trim, [Kind::Str])
+            | (ScalarFunction::Substr, [Kind::Str, Kind::Int])
+            | (ScalarFunction::Substr, [Kind::Str, Kind::Int, Kind::Int])
+            | (ScalarFunction::Replace, [Kind::Str, Kind::Str, Kind::Str]) => Some(types::BYTES),
+            (ScalarFunction::Concat, kinds) if !kinds.is_empty() => Some(types::BYTES),
+            (ScalarFunction::Length, [Kind::Str]) => Some(types::I64),
+            (ScalarFunction::StartsWith, [Kind::Str, Kind::Str])
+            | (ScalarFunction::EndsWith, [Kind::Str, Kind::Str])
+            | (ScalarFunction::Contains, [Kind::Str, Kind::Str]) => Some(types::BOOL),
+            (ScalarFunction::Abs, [Kind::Int]) => Some(types::I64),
+            (ScalarFunction::Abs, [Kind::Float]) => Some(types::F64),
+            _ => None,
+        };
+
+        return_type.ok_or_else(|| {
+            let arg_names: Vec<&str> = arg_types.iter().map(|x| type_name(*x)).collect();
+            ExpressionError::TypeMismatch(format!(
+                "{:?} can't be applied to ({})",
+                self,
+                arg_names.join(", ")
+            ))
+        })
+    }
+
+    fn call(&self, args: Vec<Value>) -> Result<Value, ExpressionError> {
+        let value = match (self, args.as_slice()) {
+            (ScalarFunction::Upper, [Value::Str(s)]) => Value::Str(s.to_uppercase()),
+            (ScalarFunction::Lower, [Value::Str(s)]) => Value::Str(s.to_lowercase()),
+            (ScalarFunction::Trim, [Value::Str(s)]) => Value::Str(s.trim().to_string()),
+            (ScalarFunction::Length, [Value::Str(s)]) => Value::Int(s.chars().count() as i64),
+            (ScalarFunction::Substr, [Value::Str(s), Value::Int(start)]) => {
+                Value::Str(substr(s, *start, None))
+            }
+            (ScalarFunction::Substr, [Value::Str(s), Value::Int(start), Value::Int(len)]) => {
+                Value::Str(substr(s, *start, Some(*len)))
+            }
+            (ScalarFunction::Concat, args) => {
+                let values: Vec<String> = args.iter().map(|x| x.to_string()).collect();
+                Value::Str(values.concat())
+            }
+            (ScalarFunction::Replace, [Value::Str(s), Value::Str(from), Value::Str(to)]) => {
+                Value::Str(s.replace(from.as_str(), to.as_str()))
+            }
+            (ScalarFunction::StartsWith, [Value::Str(s), Value::Str(p)]) => {
+                Value::Bool(s.starts_with(p.as_str()))
+            }
+            (ScalarFunction::EndsWith, [Value::Str(s), Value::Str(p)]) => {
+                Value::Bool(s.ends_with(p.as_str()))
+            }
+            (ScalarFunction::Contains, [Value::Str(s), Value::Str(p)]) => {
+                Value::Bool(s.contains(p.as_str()))
+            }
+            (ScalarFunction::Abs, [Value::Int(i)]) => Value::Int(i.wrapping_abs()),
+            (ScalarFunction::Abs, [Value::Float(f)]) => Value::Float(f.abs()),
+            _ => {
+                return Err(ExpressionError::Eval(format!(
+                    "illegal arguments of {:?}",
+                    self
+                )))
+            }
+        };
+        Ok(value)
+    }
+}
+
+/// the chars from the 1-based `start`, the negative `start` counts from the end
+fn substr(s: &str, start: i64, len: Option<i64>) -> String {
+    let char_count = s.chars().count() as i64;
+    let start = if start > 0 {
+        start - 1
+    } else if start < 0 {
+        (char_count + start).max(0)
+    } else {
+        0
+    };
+    let len = len.unwrap_or(char_count).max(0);
+    s.chars().skip(start as usize).take(len as usize).collect()
+}
+
+/// The typed expression bound to the columns of the input schema,
+/// it is built by the constructors checking the types of the operands, eg: `Expr::binary`
+#[derive(Clone, Debug)]
+pub enum Expr {
+    Column {
+        index: usize,
+        data_type: u8,
+    },
+    Literal(Value),
+    Unary {
+        op: UnaryOp,
+        expr: Box<Expr>,
+        data_type: u8,
+    },
+    Binary {
+        op: BinaryOp,
+        left: Box<Expr>,
+        right: Box<Expr>,
+        data_type: u8,
+    },
+    Function {
+        function: ScalarFunction,
+        args: Vec<Expr>,
+        data_type: u8,
+    },
+    Cast {
+        expr: Box<Expr>,
+        data_type: u8,
+    },
+}
+
+impl Expr {
+    /// the reference to the column `index` of the `schema`
+    pub fn column(schema: &Schema, index: usize) -> Result<Expr, ExpressionError> {
+        let field = schema
+            .get_field(index)
+            .ok_or_else(|| ExpressionError::ColumnNotFound(format!("${}", index)))?;
+        Ok(Expr::Column {
+            index,
+            data_type: field.get_data_type(),
+        })
+    }
+
+    pub fn unary(op: UnaryOp, expr: Expr) -> Result<Expr, ExpressionError> {
+        let kind = Kind::of(expr.data_type());
+        let data_type = match (op, kind) {
+            (UnaryOp::Neg, Kind::Int) => types::I64,
+            (UnaryOp::Neg, Kind::Float) => types::F64,
+            (UnaryOp::Not, Kind::Bool) => types::BOOL,
+            _ => {
+                return Err(ExpressionError::TypeMismatch(format!(
+                    "{:?} can't be applied to {}",
+                    op,
+                    type_name(expr.data_type())
+                )))
+            }
+        };
+
+        Ok(Expr::Unary {
+            op,
+            expr: Box::new(expr),
+            data_type,
+        })
+    }
+
+    pub fn binary(op: BinaryOp, left: Expr, right: Expr) -> Result<Expr, ExpressionError> {
+        let left_kind = Kind::of(left.data_type());
+        let right_kind = Kind::of(right.data_type());
+        let comparable =
+            left_kind == right_kind || (left_kind.is_numeric() && right_kind.is_numeric());
+
+        let data_type = if op.is_arithmetic() {
+            match (left_kind, right_kind) {
+                (Kind::Int, Kind::Int) => Some(types::I64),
+                _ if left_kind.is_numeric() && right_kind.is_numeric() => Some(types::F64),
+                _ => None,
+            }
+        } else if op.is_logic() {
+            match (left_kind, right_kind) {
+                (Kind::Bool, Kind::Bool) => Some(types::BOOL),
+                _ => None,
+            }
+        } else if comparable {
+            Some(types::BOOL)
+        } else {
+            None
+        };
+
+        let data_type = data_type.ok_or_else(|| {
+            ExpressionError::TypeMismatch(format!(
+                "{:?} can't be applied to {} and {}",
+                op,
+                type_name(left.data_type()),
+                type_name(right.data_type())
+            ))
+        })?;
+
+        Ok(Expr::Binary {
+            op,
+            left: Box::new(left),
+            right: Box::new(right),
+            data_type,
+        })
+    }
+
+    pub fn function(function: ScalarFunction, args: Vec<Expr>) -> Result<Expr, ExpressionError> {
+        let arg_types: Vec<u8> = args.iter().map(|x| x.data_type()).collect();
+        let data_type = function.return_type(arg_types.as_slice())?;
+        Ok(Expr::Function {
+            function,
+            args,
+            data_type,
+        })
+    }
+
+    pub fn data_type(&self) -> u8 {
+        match self {
+            Expr::Column { data_type, .. } => *data_type,
+            Expr::Literal(value) => value.data_type(),
+            Expr::Unary { data_type, .. } => *data_type,
+            Expr::Binary { data_type, .. } => *data_type,
+            Expr::Function { data_type, .. } => *data_type,
+            Expr::Cast { data_type, .. } => *data_type,
+        }
+    }
+
+    pub fn eval(&self, reader: &mut BufferReader) -> Result<Value, ExpressionError> {
+        match self {
+            Expr::Column { index, data_type } => read_column(reader, *index, *data_type),
+            Expr::Literal(value) => Ok(value.clone()),
+            Expr::Unary { op, expr, .. } => match (op, expr.eval(reader)?) {
+                (UnaryOp::Neg, Value::Int(i)) => Ok(Value::Int(i.wrapping_neg())),
+                (UnaryOp::Neg, Value::Float(f)) => Ok(Value::Float(-f)),
+                (UnaryOp::Not, Value::Bool(b)) => Ok(Value::Bool(!b)),
+                (op, value) => Err(ExpressionError::Eval(format!(
+                    "{:?} can't be applied to {}",
+                    op, value
+                ))),
+            },
+            Expr::Binary {
+                op, left, right, ..
+            } => {
+                // short-circuit the boolean logic
+                if op.is_logic() {
+                    let left = left.eval(reader)?.as_bool()?;
+                    return match op {
+                        BinaryOp::And if !left => Ok(Value::Bool(false)),
+                        BinaryOp::Or if left => Ok(Value::Bool(true)),
+                        _ => Ok(Value::Bool(right.eval(reader)?.as_bool()?)),
+                    };
+                }
+
+                let left = left.eval(reader)?;
+                let right = right.eval(reader)?;
+                if op.is_arithmetic() {
+                    arithmetic(*op, left, right)
+                } else {
+                    let ordering = compare(&left, &right);
+                    let result = match op {
+                        BinaryOp::Eq => ordering == Some(Ordering::Equal),
+                        BinaryOp::NotEq => ordering != Some(Ordering::Equal),
+                        BinaryOp::Lt => ordering == Some(Ordering::Less),
+                        BinaryOp::LtEq => {
+                            ordering == Some(Ordering::Less) || ordering == Some(Ordering::Equal)
+                        }
+                        BinaryOp::Gt => ordering == Some(Ordering::Greater),
+                        _ => {
+                            ordering == Some(Ordering::Greater) || ordering == Some(Ordering::Equal)
+                        }
+                    };
+                    Ok(Value::Bool(result))
+                }
+            }
+            Expr::Function { function, args, .. } => {
+                let mut values = Vec::with_capacity(args.len());
+                for arg in args {
+                    values.push(arg.eval(reader)?);
+                }
+                function.call(values)
+            }
+            Expr::Cast { expr, data_type } => expr.eval(reader)?.cast(*data_type),
+        }
+    }
+}
+
+fn read_column(
+    reader: &mut BufferReader,
+    index: usize,
+    data_type: u8,
+) -> Result<Value, ExpressionError> {
+    let value = match data_type {
+        types::BOOL => Value::Bool(reader.get_bool(index)?),
+        types::I8 => Value::Int(reader.get_i8(index)? as i64),
+        types::U8 => Value::Int(reader.get_u8(index)? as i64),
+        types::I16 => Value::Int(reader.get_i16(index)? as i64),
+        types::U16 => Value::Int(reader.get_u16(index)? as i64),
+        types::I32 => Value::Int(reader.get_i32(index)? as i64),
+        types::U32 => Value::Int(reader.get_u32(index)? as i64),
+        types::I64 => Value::Int(reader.get_i64(index)?),
+        types::U64 => {
+            let value = reader.get_u64(index)?;
+            let value = i64::try_from(value).map_err(|_e| {
+                ExpressionError::Eval(format!("the U64 column {} overflows INT: {}", index, value))
+            })?;
+            Value::Int(value)
+        }
+        types::F32 => Value::Float(reader.get_f32(index)? as f64),
+        types::F64 => Value::Float(reader.get_f64(index)?),
+        _ => Value::Str(reader.get_str(index)?),
+    };
+    Ok(value)
+}
+
+fn arithmetic(op: BinaryOp, left: Value, right: Value) -> Result<Value, ExpressionError> {
+    if let (Value::Int(a), Value::Int(b)) = (&left, &right) {
+        let (a, b) = (*a, *b);
+        if b == 0 && (op == BinaryOp::Div || op == BinaryOp::Mod) {
+            return Err(ExpressionError::Eval("divided by zero".to_string()));
+        }
+
+        let value = match op {
+            BinaryOp::Add => a.wrapping_add(b),
+            BinaryOp::Sub => a.wrapping_sub(b),
+            BinaryOp::Mul => a.wrapping_mul(b),
+            BinaryOp::Div => a.wrapping_div(b),
+            _ => a.wrapping_rem(b),
+        };
+        return Ok(Value::Int(value));
+    }
+
+    let (a, b) = (left.as_f64()?, right.as_f64()?);
+    let value = match op {
+        BinaryOp::Add => a + b,
+        BinaryOp::Sub => a - b,
+        BinaryOp::Mul => a * b,
+        BinaryOp::Div => a / b,
+        _ => a % b,
+    };
+    Ok(Value::Float(value))
+}
+
+/// `None` if the values are not comparable, eg: the `NaN`
+fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        (Value::Int(a), Value::Int(b)) => Some(a.cmp(b)),
+        (Value::Str(a), Value::Str(b)) => Some(a.cmp(b)),
+        (a, b) => match (a.as_f64(), b.as_f64()) {
+            (Ok(a), Ok(b)) => a.partial_cmp(&b),
+            _ => None,
+        },
+    }
+}
@@ -0,0 +1,130 @@
+use crate::functions::schema_base::expression::ExpressionError;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Token {
+    /// a column name, a function name or a keyword, the quoted name is never a keyword
+    Ident(String, bool),
+    /// the column reference by index, eg: `$0`
+    ColumnIndex(usize),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Symbol(&'static str),
+}
+
+impl Token {
+    pub fn is_keyword(&self, keyword: &str) -> bool {
+        match self {
+            Token::Ident(name, false) => name.eq_ignore_ascii_case(keyword),
+            _ => false,
+        }
+    }
+}
+
+/// the symbols ordered by the length, so the longest symbol is matched first
+const SYMBOLS: [&str; 16] = [
+    "==", "!=", "<>", "<=", ">=", "+", "-", "*", "/", "%", "(", ")", ",", "=", "<", ">",
+];
+
+/// split the text into the tokens with their positions
+pub(crate) fn tokenize(text: &str) -> Result<Vec<(usize, Token)>, ExpressionError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+
+    let mut pos = 0;
+    while pos < chars.len() {
+        let c = chars[pos];
+        if c.is_whitespace() {
+            pos += 1;
+            continue;
+        }
+
+        let start = pos;
+        let token = if c.is_ascii_alphabetic() || c == '_' {
+            while pos < chars.len() && (chars[pos].is_ascii_alphanumeric() || chars[pos] == '_') {
+                pos += 1;
+            }
+            Token::Ident(chars[start..pos].iter().collect(), false)
+        } else if c == '`' {
+            let name = read_quoted(&chars, &mut pos, '`')?;
+            Token::Ident(name, true)
+        } else if c == '\'' {
+            Token::Str(read_quoted(&chars, &mut pos, '\'')?)
+        } else if c == '$' {
+            pos += 1;
+            while pos < chars.len() && chars[pos].is_ascii_digit() {
+                pos += 1;
+            }
+            let index: String = chars[start + 1..pos].iter().collect();
+            let index = index.parse::<usize>().map_err(|_e| {
+                ExpressionError::Syntax(start, "expect a column index".to_string())
+            })?;
+            Token::ColumnIndex(index)
+        } else if c.is_ascii_digit() {
+            while pos < chars.len() && chars[pos].is_ascii_digit() {
+                pos += 1;
+            }
+            let is_float =
+                pos + 1 < chars.len() && chars[pos] == '.' && chars[pos + 1].is_ascii_digit();
+            if is_float {
+                pos += 1;
+                while pos < chars.len() && chars[pos].is_ascii_digit() {
+                    pos += 1;
+                }
+            }
+
+            let number: String = chars[start..pos].iter().collect();
+            if is_float {
+                Token::Float(number.parse().unwrap())
+            } else {
+                let value = number.parse().map_err(|_e| {
+                    ExpressionError::Syntax(start, format!("integer overflow {}", number))
+                })?;
+                Token::Int(value)
+            }
+        } else {
+            let rest: String = chars[pos..chars.len().min(pos + 2)].iter().collect();
+            let symbol = SYMBOLS
+                .iter()
+                .find(|symbol| rest.starts_with(*symbol))
+                .ok_or(ExpressionError::Syntax(pos, format!("unexpected '{}'", c)))?;
+            pos += symbol.len();
+            Token::Symbol(symbol)
+        };
+
+        tokens.push((start, token));
+    }
+
+    Ok(tokens)
+}
+
+/// read the text quoted by `quote`, the quote is escaped by doubling it, eg: `'it''s'`
+fn read_quoted(chars: &[char], pos: &mut usize, quote: char) -> Result<String, ExpressionError> {
+    let start = *pos;
+    let mut text = String::new();
+
+    *pos += 1;
+    loop {
+        match chars.get(*pos) {
+            Some(c) if *c == quote => {
+                if chars.get(*pos + 1) == Some(&quote) {
+                    text.push(quote);
+                    *pos += 2;
+                } else {
+                    *pos += 1;
+                    return Ok(text);
+                }
+            }
+            Some(c) => {
+                text.push(*c);
+                *pos += 1;
+            }
+            None => {
+                return Err(ExpressionError::Syntax(
+                    start,
+                    format!("unterminated {}", quote),
+                ))
+            }
+        }
+    }
+}
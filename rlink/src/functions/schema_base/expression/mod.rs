@@ -0,0 +1,358 @@
+//! A small expression language over the columns of the `Record`, eg:
+//! `upper(name) = 'A' AND (value + 1) * 2 > 10`.
+//!
+//! The columns are referenced by the names of the `Schema` or by the indexes, eg: `$1`,
+//! the names conflicting with the keywords are quoted by the backtick, eg: `` `and` ``.
+//! The expression supports the arithmetic `+ - * / %`, the comparisons `= != <> < <= > >=`,
+//! the boolean logic `AND OR NOT`, the `CAST(expr AS type)` and the string functions
+//! `upper`, `lower`, `trim`, `length`, `substr`, `concat`, `replace`, `starts_with`,
+//! `ends_with`, `contains` and the `abs`.
+//!
+//! The integers are evaluated as `I64` and the floats as `F64`, the type of the expression
+//! is checked at parsing.
+//!
+//! The expressions of other front-ends, eg: the SQL, are translated to the `ast::Expr` and
+//! wrapped by `Expression::from_expr`.
+
+use std::fmt::{Display, Formatter};
+
+use thiserror::Error;
+
+use crate::api::element::{types, BufferReader, BufferWriter, Record};
+use crate::api::schema::Schema;
+use crate::functions::schema_base::expression::ast::{Expr, Kind};
+use crate::functions::schema_base::expression::parser::Parser;
+
+pub mod ast;
+pub(crate) mod lexer;
+pub(crate) mod parser;
+
+#[derive(Error, Debug)]
+pub enum ExpressionError {
+    #[error("syntax error at {0}. {1}")]
+    Syntax(usize, String),
+    #[error("column not found. {0}")]
+    ColumnNotFound(String),
+    #[error("unknown function. {0}")]
+    UnknownFunction(String),
+    #[error("type mismatch. {0}")]
+    TypeMismatch(String),
+    #[error("evaluation error. {0}")]
+    Eval(String),
+    #[error(transparent)]
+    IOError(#[from] std::io::Error),
+}
+
+/// The value of the expression
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+}
+
+impl Value {
+    /// the `types::*` of the value
+    pub fn data_type(&self) -> u8 {
+        match self {
+            Value::Bool(_) => types::BOOL,
+            Value::Int(_) => types::I64,
+            Value::Float(_) => types::F64,
+            Value::Str(_) => types::BYTES,
+        }
+    }
+
+    pub fn as_bool(&self) -> Result<bool, ExpressionError> {
+        match self {
+            Value::Bool(b) => Ok(*b),
+            _ => Err(ExpressionError::Eval(format!("{} is not a BOOL", self))),
+        }
+    }
+
+    pub fn as_i64(&self) -> Result<i64, ExpressionError> {
+        match self {
+            Value::Int(i) => Ok(*i),
+            _ => Err(ExpressionError::Eval(format!("{} is not an integer", self))),
+        }
+    }
+
+    /// the integer is widened to the float
+    pub fn as_f64(&self) -> Result<f64, ExpressionError> {
+        match self {
+            Value::Int(i) => Ok(*i as f64),
+            Value::Float(f) => Ok(*f),
+            _ => Err(ExpressionError::Eval(format!("{} is not a number", self))),
+        }
+    }
+
+    /// cast to the `types::*`, the integer is truncated to the width of the `data_type`
+    pub fn cast(self, data_type: u8) -> Result<Value, ExpressionError> {
+        let value = match Kind::of(data_type) {
+            Kind::Bool => Value::Bool(match self {
+                Value::Bool(b) => b,
+                Value::Int(i) => i != 0,
+                Value::Float(f) => f != 0.0,
+                Value::Str(s) => match s.trim().to_lowercase().as_str() {
+                    "true" => true,
+                    "false" => false,
+                    _ => return Err(cast_error(s.as_str(), data_type)),
+                },
+            }),
+            Kind::Int => {
+                let i = match self {
+                    Value::Bool(b) => b as i64,
+                    Value::Int(i) => i,
+                    Value::Float(f) => f as i64,
+                    Value::Str(s) => s
+                        .trim()
+                        .parse::<i64>()
+                        .map_err(|_e| cast_error(s.as_str(), data_type))?,
+                };
+                Value::Int(truncate(i, data_type))
+            }
+            Kind::Float => {
+                let f = match self {
+                    Value::Bool(b) => (b as i64) as f64,
+                    Value::Int(i) => i as f64,
+                    Value::Float(f) => f,
+                    Value::Str(s) => s
+                        .trim()
+                        .parse::<f64>()
+                        .map_err(|_e| cast_error(s.as_str(), data_type))?,
+                };
+                if data_type == types::F32 {
+                    Value::Float(f as f32 as f64)
+                } else {
+                    Value::Float(f)
+                }
+            }
+            Kind::Str => Value::Str(self.to_string()),
+        };
+        Ok(value)
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Int(i) => write!(f, "{}", i),
+            Value::Float(v) => write!(f, "{}", v),
+            Value::Str(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+fn cast_error(s: &str, data_type: u8) -> ExpressionError {
+    ExpressionError::Eval(format!(
+        "'{}' can't be cast to {}",
+        s,
+        crate::api::schema::type_name(data_type)
+    ))
+}
+
+fn truncate(i: i64, data_type: u8) -> i64 {
+    match data_type {
+        types::I8 => i as i8 as i64,
+        types::U8 => i as u8 as i64,
+        types::I16 => i as i16 as i64,
+        types::U16 => i as u16 as i64,
+        types::I32 => i as i32 as i64,
+        types::U32 => i as u32 as i64,
+        _ => i,
+    }
+}
+
+/// The parsed and type checked expression over the records of a `Schema`
+#[derive(Clone, Debug)]
+pub struct Expression {
+    expr: Expr,
+    field_types: Vec<u8>,
+}
+
+impl Expression {
+    pub fn parse(text: &str, schema: &Schema) -> Result<Self, ExpressionError> {
+        let mut parser = Parser::new(text, schema)?;
+        let expr = parser.parse_expr()?;
+        parser.expect_end()?;
+
+        Ok(Expression::new(expr, schema))
+    }
+
+    /// parse the expression with an optional alias, eg: `value * 2 AS double_value`
+    pub fn parse_with_alias(
+        text: &str,
+        schema: &Schema,
+    ) -> Result<(Self, Option<String>), ExpressionError> {
+        let mut parser = Parser::new(text, schema)?;
+        let expr = parser.parse_expr()?;
+        let alias = parser.parse_alias()?;
+        parser.expect_end()?;
+
+        Ok((Expression::new(expr, schema), alias))
+    }
+
+    /// wrap the `expr` built by the `ast::Expr` constructors over the columns of the `schema`
+    pub fn from_expr(expr: Expr, schema: &Schema) -> Self {
+        Expression::new(expr, schema)
+    }
+
+    fn new(expr: Expr, schema: &Schema) -> Self {
+        Expression {
+            expr,
+            field_types: schema.get_field_types(),
+        }
+    }
+
+    /// the `types::*` of the evaluated value
+    pub fn get_data_type(&self) -> u8 {
+        self.expr.data_type()
+    }
+
+    /// the column index if the expression is a column reference, eg: `name` or `$0`
+    pub fn get_column_index(&self) -> Option<usize> {
+        match self.expr {
+            Expr::Column { index, .. } => Some(index),
+            _ => None,
+        }
+    }
+
+    pub fn eval(&self, record: &mut Record) -> Result<Value, ExpressionError> {
+        let mut reader = record.get_reader(self.field_types.as_slice());
+        self.eval_reader(&mut reader)
+    }
+
+    /// evaluate with a shared reader, so the expressions of a projection read the same record
+    pub(crate) fn eval_reader(&self, reader: &mut BufferReader) -> Result<Value, ExpressionError> {
+        self.expr.eval(reader)
+    }
+}
+
+/// write the `value` as the `data_type`, eg: the evaluated `I64` to an `U32` column
+pub(crate) fn write_value(
+    writer: &mut BufferWriter,
+    data_type: u8,
+    value: Value,
+) -> Result<(), ExpressionError> {
+    match (data_type, value) {
+        (types::BOOL, Value::Bool(b)) => writer.set_bool(b)?,
+        (types::I8, Value::Int(i)) => writer.set_i8(i as i8)?,
+        (types::U8, Value::Int(i)) => writer.set_u8(i as u8)?,
+        (types::I16, Value::Int(i)) => writer.set_i16(i as i16)?,
+        (types::U16, Value::Int(i)) => writer.set_u16(i as u16)?,
+        (types::I32, Value::Int(i)) => writer.set_i32(i as i32)?,
+        (types::U32, Value::Int(i)) => writer.set_u32(i as u32)?,
+        (types::I64, Value::Int(i)) => writer.set_i64(i)?,
+        (types::U64, Value::Int(i)) => writer.set_u64(i as u64)?,
+        (types::F32, Value::Float(f)) => writer.set_f32(f as f32)?,
+        (types::F64, Value::Float(f)) => writer.set_f64(f)?,
+        (types::BYTES, Value::Str(s)) => writer.set_str(s.as_str())?,
+        (data_type, value) => {
+            return Err(ExpressionError::TypeMismatch(format!(
+                "{} can't be written as {}",
+                value,
+                crate::api::schema::type_name(data_type)
+            )))
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::element::{types, Record};
+    use crate::api::schema::Schema;
+    use crate::functions::schema_base::expression::{Expression, ExpressionError, Value};
+
+    fn schema() -> Schema {
+        Schema::from_names(
+            &["name", "value", "price", "valid"],
+            &[types::BYTES, types::U32, types::F64, types::BOOL],
+        )
+    }
+
+    fn record(name: &str, value: u32, price: f64, valid: bool) -> Record {
+        let mut record = Record::new();
+        let field_types = schema().get_field_types();
+        let mut writer = record.get_writer(field_types.as_slice());
+        writer.set_str(name).unwrap();
+        writer.set_u32(value).unwrap();
+        writer.set_f64(price).unwrap();
+        writer.set_bool(valid).unwrap();
+        record
+    }
+
+    fn eval(text: &str) -> Value {
+        let expression = Expression::parse(text, &schema()).unwrap();
+        let value = expression
+            .eval(&mut record(" Apple ", 3, 1.5, true))
+            .unwrap();
+        assert_eq!(value.data_type(), expression.get_data_type());
+        value
+    }
+
+    #[test]
+    pub fn expression_eval_test() {
+        assert_eq!(eval("value + 2 * 3"), Value::Int(9));
+        assert_eq!(eval("($1 + 2) * 3 % 5"), Value::Int(0));
+        assert_eq!(eval("-value / 2"), Value::Int(-1));
+        assert_eq!(eval("value * price"), Value::Float(4.5));
+        assert_eq!(
+            eval("value > 2 AND NOT price >= 2 OR false"),
+            Value::Bool(true)
+        );
+        assert_eq!(eval("valid = true AND value <> 3"), Value::Bool(false));
+        assert_eq!(eval("upper(trim(name))"), Value::Str("APPLE".to_string()));
+        assert_eq!(
+            eval("substr(trim(name), 2, 3)"),
+            Value::Str("ppl".to_string())
+        );
+        assert_eq!(eval("length(name)"), Value::Int(7));
+        assert_eq!(
+            eval("concat(trim(name), '''s ', value)"),
+            Value::Str("Apple's 3".to_string())
+        );
+        assert_eq!(eval("contains(lower(name), 'pp')"), Value::Bool(true));
+        assert_eq!(eval("CAST(price AS I64) + CAST('2' AS I32)"), Value::Int(3));
+        assert_eq!(eval("CAST(value AS STRING)"), Value::Str("3".to_string()));
+        assert_eq!(eval("abs(1 - value)"), Value::Int(2));
+    }
+
+    #[test]
+    pub fn expression_error_test() {
+        let schema = schema();
+        match Expression::parse("value +", &schema) {
+            Err(ExpressionError::Syntax(7, _)) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        match Expression::parse("unknown > 1", &schema) {
+            Err(ExpressionError::ColumnNotFound(name)) => assert_eq!(name, "unknown"),
+            other => panic!("unexpected {:?}", other),
+        }
+        match Expression::parse("name + 1", &schema) {
+            Err(ExpressionError::TypeMismatch(_)) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        match Expression::parse("value AND valid", &schema) {
+            Err(ExpressionError::TypeMismatch(_)) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let expression = Expression::parse("value / (value - 3)", &schema).unwrap();
+        match expression.eval(&mut record("a", 3, 0.0, false)) {
+            Err(ExpressionError::Eval(_)) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+
+        // the U64 values out of the INT range are not truncated
+        let schema = Schema::from_names(&["id"], &[types::U64]);
+        let expression = Expression::parse("id + 1", &schema).unwrap();
+        let mut record = Record::new();
+        record.get_writer(&[types::U64]).set_u64(u64::MAX).unwrap();
+        match expression.eval(&mut record) {
+            Err(ExpressionError::Eval(_)) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use crate::api::schema::{data_type_of, Schema};
+use crate::functions::schema_base::expression::ast::{BinaryOp, Expr, ScalarFunction, UnaryOp};
+use crate::functions::schema_base::expression::lexer::{tokenize, Token};
+use crate::functions::schema_base::expression::{ExpressionError, Value};
+
+/// the keywords can't be used as the column names unless quoted, eg: `` `and` ``
+const KEYWORDS: [&str; 7] = ["AND", "OR", "NOT", "TRUE", "FALSE", "CAST", "AS"];
+
+/// A recursive descent parser binding the column names to the `schema`.
+///
+/// The precedence from low to high:
+/// `OR`, `AND`, `NOT`, the comparisons, `+ -`, `* / %`, the unary `-`
+pub(crate) struct Parser<'a> {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    text_len: usize,
+    schema: &'a Schema,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(text: &str, schema: &'a Schema) -> Result<Self, ExpressionError> {
+        Ok(Parser {
+            tokens: tokenize(text)?,
+            pos: 0,
+            text_len: text.chars().count(),
+            schema,
+        })
+    }
+
+    pub fn parse_expr(&mut self) -> Result<Expr, ExpressionError> {
+        self.parse_or()
+    }
+
+    /// the optional `AS alias` following the expression
+    pub fn parse_alias(&mut self) -> Result<Option<String>, ExpressionError> {
+        if !self.consume_keyword("AS") {
+            return Ok(None);
+        }
+
+        match self.next() {
+            Some(Token::Ident(name, _)) => Ok(Some(name)),
+            _ => Err(self.syntax_error("expect an alias after AS")),
+        }
+    }
+
+    pub fn expect_end(&self) -> Result<(), ExpressionError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.syntax_error("unexpected token")),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, ExpressionError> {
+        let mut left = self.parse_and()?;
+        while self.consume_keyword("OR") {
+            let right = self.parse_and()?;
+            left = Expr::binary(BinaryOp::Or, left, right)?;
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, ExpressionError> {
+        let mut left = self.parse_not()?;
+        while self.consume_keyword("AND") {
+            let right = self.parse_not()?;
+            left = Expr::binary(BinaryOp::And, left, right)?;
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, ExpressionError> {
+        if self.consume_keyword("NOT") {
+            let expr = self.parse_not()?;
+            Expr::unary(UnaryOp::Not, expr)
+        } else {
+            self.parse_comparison()
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, ExpressionError> {
+        let left = self.parse_additive()?;
+        let op = match self.peek() {
+            Some(Token::Symbol("=")) | Some(Token::Symbol("==")) => BinaryOp::Eq,
+            Some(Token::Symbol("!=")) | Some(Token::Symbol("<>")) => BinaryOp::NotEq,
+            Some(Token::Symbol("<")) => BinaryOp::Lt,
+            Some(Token::Symbol("<=")) => BinaryOp::LtEq,
+            Some(Token::Symbol(">")) => BinaryOp::Gt,
+            Some(Token::Symbol(">=")) => BinaryOp::GtEq,
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+
+        let right = self.parse_additive()?;
+        Expr::binary(op, left, right)
+    }
+
+    fn parse_additive(&mut self) -> Result<Expr, ExpressionError> {
+        let mut left = self.parse_multiplicative()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Symbol("+")) => BinaryOp::Add,
+                Some(Token::Symbol("-")) => BinaryOp::Sub,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+
+            let right = self.parse_multiplicative()?;
+            left = Expr::binary(op, left, right)?;
+        }
+    }
+
+    fn parse_multiplicative(&mut self) -> Result<Expr, ExpressionError> {
+        let mut left = self.parse_unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Symbol("*")) => BinaryOp::Mul,
+                Some(Token::Symbol("/")) => BinaryOp::Div,
+                Some(Token::Symbol("%")) => BinaryOp::Mod,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+
+            let right = self.parse_unary()?;
+            left = Expr::binary(op, left, right)?;
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, ExpressionError> {
+        if self.consume_symbol("-") {
+            // fold the negative literals, eg: `-1`
+            return match self.parse_unary()? {
+                Expr::Literal(Value::Int(i)) => Ok(Expr::Literal(Value::Int(i.wrapping_neg()))),
+                Expr::Literal(Value::Float(f)) => Ok(Expr::Literal(Value::Float(-f))),
+                expr => Expr::unary(UnaryOp::Neg, expr),
+            };
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, ExpressionError> {
+        let start = self.pos;
+        let token = self
+            .next()
+            .ok_or_else(|| self.syntax_error("unexpected end"))?;
+
+        match token {
+            Token::Int(i) => Ok(Expr::Literal(Value::Int(i))),
+            Token::Float(f) => Ok(Expr::Literal(Value::Float(f))),
+            Token::Str(s) => Ok(Expr::Literal(Value::Str(s))),
+            Token::Symbol("(") => {
+                let expr = self.parse_expr()?;
+                self.expect_symbol(")")?;
+                Ok(expr)
+            }
+            Token::ColumnIndex(index) => self.column(index),
+            ref t if t.is_keyword("TRUE") => Ok(Expr::Literal(Value::Bool(true))),
+            ref t if t.is_keyword("FALSE") => Ok(Expr::Literal(Value::Bool(false))),
+            ref t if t.is_keyword("CAST") => {
+                self.expect_symbol("(")?;
+                let expr = self.parse_expr()?;
+                if !self.consume_keyword("AS") {
+                    return Err(self.syntax_error("expect AS in CAST"));
+                }
+
+                let data_type = match self.next() {
+                    Some(Token::Ident(name, false)) => data_type_of(name.as_str()),
+                    _ => None,
+                }
+                .ok_or_else(|| self.syntax_error("expect a type name, eg: I64"))?;
+                self.expect_symbol(")")?;
+
+                Ok(Expr::Cast {
+                    expr: Box::new(expr),
+                    data_type,
+                })
+            }
+            Token::Ident(name, quoted) => {
+                if self.consume_symbol("(") {
+                    let function = ScalarFunction::from_name(name.as_str())
+                        .ok_or(ExpressionError::UnknownFunction(name))?;
+                    let args = self.parse_args()?;
+                    Expr::function(function, args)
+                } else if !quoted && KEYWORDS.iter().any(|k| name.eq_ignore_ascii_case(k)) {
+                    self.pos = start;
+                    Err(self.syntax_error("unexpected keyword"))
+                } else {
+                    let index = self
+                        .schema
+                        .index_of(name.as_str())
+                        .ok_or(ExpressionError::ColumnNotFound(name))?;
+                    self.column(index)
+                }
+            }
+            _ => {
+                self.pos = start;
+                Err(self.syntax_error("unexpected token"))
+            }
+        }
+    }
+
+    /// the comma separated arguments till `)`
+    fn parse_args(&mut self) -> Result<Vec<Expr>, ExpressionError> {
+        let mut args = Vec::new();
+        if self.consume_symbol(")") {
+            return Ok(args);
+        }
+
+        loop {
+            args.push(self.parse_expr()?);
+            if self.consume_symbol(")") {
+                return Ok(args);
+            }
+            self.expect_symbol(",")?;
+        }
+    }
+
+    fn column(&self, index: usize) -> Result<Expr, ExpressionError> {
+        Expr::column(self.schema, index)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, token)| token)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(_, token)| token.clone());
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn consume_keyword(&mut self, keyword: &str) -> bool {
+        let matched = self.peek().map(|t| t.is_keyword(keyword)).unwrap_or(false);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn consume_symbol(&mut self, symbol: &str) -> bool {
+        let matched = match self.peek() {
+            Some(Token::Symbol(s)) => *s == symbol,
+            _ => false,
+        };
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), ExpressionError> {
+        if self.consume_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.syntax_error(format!("expect '{}'", symbol).as_str()))
+        }
+    }
+
+    /// the error at the position of the current token
+    fn syntax_error(&self, message: &str) -> ExpressionError {
+        let position = self
+            .tokens
+            .get(self.pos)
+            .map(|(position, _)| *position)
+            .unwrap_or(self.text_len);
+        ExpressionError::Syntax(position, message.to_string())
+    }
+}
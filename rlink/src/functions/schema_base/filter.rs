@@ -0,0 +1,119 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::api::element::{types, Record};
+use crate::api::function::{Context, FilterFunction, Function};
+use crate::api::schema::{type_name, Schema};
+use crate::functions::schema_base::expression::{Expression, ExpressionError, Value};
+use crate::metrics::{register_counter, Tag};
+
+/// Keep the records matching the `BOOL` expression, eg: `value > 10 AND name != ''`.
+///
+/// The records failed to evaluate are dropped and reported by the counter
+/// `SchemaBaseFilterFunction_{operator_id}_errors`, eg: divided by zero.
+#[derive(Debug)]
+pub struct SchemaBaseFilterFunction {
+    condition: Expression,
+    schema: Schema,
+
+    errors: Arc<AtomicU64>,
+}
+
+impl SchemaBaseFilterFunction {
+    pub fn new(condition: &str, schema: &Schema) -> Result<Self, ExpressionError> {
+        let condition = Expression::parse(condition, schema)?;
+        SchemaBaseFilterFunction::from_expression(condition, schema)
+    }
+
+    /// the `condition` built over the columns of the `schema`, eg: translated from the SQL
+    pub fn from_expression(
+        condition: Expression,
+        schema: &Schema,
+    ) -> Result<Self, ExpressionError> {
+        if condition.get_data_type() != types::BOOL {
+            return Err(ExpressionError::TypeMismatch(format!(
+                "the filter condition must be BOOL, but {}",
+                type_name(condition.get_data_type())
+            )));
+        }
+
+        Ok(SchemaBaseFilterFunction {
+            condition,
+            schema: schema.clone(),
+            errors: Arc::new(AtomicU64::new(0)),
+        })
+    }
+}
+
+impl FilterFunction for SchemaBaseFilterFunction {
+    fn open(&mut self, context: &Context) -> crate::api::Result<()> {
+        let tags = vec![
+            Tag("job_id".to_string(), context.task_id.job_id.0.to_string()),
+            Tag(
+                "task_number".to_string(),
+                context.task_id.task_number.to_string(),
+            ),
+        ];
+        // the operator id tells apart the functions of the same type in a job
+        let metric_name = format!("SchemaBaseFilterFunction_{}_errors", context.operator_id.0);
+        register_counter(metric_name.as_str(), tags, self.errors.clone());
+
+        Ok(())
+    }
+
+    fn filter(&self, record: &mut Record) -> bool {
+        match self.condition.eval(record) {
+            Ok(Value::Bool(matched)) => matched,
+            _ => {
+                self.errors.fetch_add(1, Ordering::Relaxed);
+                false
+            }
+        }
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        Ok(())
+    }
+}
+
+impl Function for SchemaBaseFilterFunction {
+    fn get_name(&self) -> &str {
+        "SchemaBaseFilterFunction"
+    }
+
+    fn get_input_schema(&self) -> Schema {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::element::{types, Record};
+    use crate::api::function::FilterFunction;
+    use crate::api::schema::Schema;
+    use crate::functions::schema_base::filter::SchemaBaseFilterFunction;
+
+    fn record(name: &str, value: i64) -> Record {
+        let mut record = Record::new();
+        let mut writer = record.get_writer(&[types::BYTES, types::I64]);
+        writer.set_str(name).unwrap();
+        writer.set_i64(value).unwrap();
+        record
+    }
+
+    #[test]
+    pub fn schema_base_filter_test() {
+        let schema = Schema::from_names(&["name", "value"], &[types::BYTES, types::I64]);
+        assert!(SchemaBaseFilterFunction::new("value + 1", &schema).is_err());
+
+        let filter =
+            SchemaBaseFilterFunction::new("starts_with(name, 'a') AND 10 / value > 2", &schema)
+                .unwrap();
+        assert!(filter.filter(&mut record("ab", 2)));
+        assert!(!filter.filter(&mut record("ab", 5)));
+        assert!(!filter.filter(&mut record("b", 2)));
+        // divided by zero
+        assert!(!filter.filter(&mut record("ab", 0)));
+        assert_eq!(filter.errors.load(std::sync::atomic::Ordering::Relaxed), 1);
+    }
+}
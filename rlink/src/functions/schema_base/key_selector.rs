@@ -1,5 +1,6 @@
 use crate::api::element::Record;
 use crate::api::function::{Context, Function, KeySelectorFunction};
+use crate::api::schema::Schema;
 use crate::functions::schema_base::FunctionSchema;
 
 #[derive(Debug)]
@@ -18,11 +19,19 @@ impl SchemaBaseKeySelector {
             key_field_types,
         }
     }
+
+    /// the key fields of the `schema`, the undeclared schema if the columns are out of it
+    fn project(&self, schema: &Schema) -> Schema {
+        schema.project(self.columns.as_slice()).unwrap_or_else(|e| {
+            error!("{} key schema error. {}", self.get_name(), e);
+            Schema::empty()
+        })
+    }
 }
 
 impl FunctionSchema for SchemaBaseKeySelector {
-    fn get_schema_types(&self) -> Vec<u8> {
-        self.key_field_types.clone()
+    fn get_schema(&self) -> Schema {
+        self.project(&Schema::from_types(self.field_types.as_slice()))
     }
 }
 
@@ -55,4 +64,17 @@ impl Function for SchemaBaseKeySelector {
     fn get_name(&self) -> &str {
         "SchemaBaseKeySelector"
     }
+
+    /// the key fields keep the names of the input
+    fn get_output_schema(&self, input_schema: &Schema) -> Schema {
+        if input_schema.is_empty() {
+            self.get_schema()
+        } else {
+            self.project(input_schema)
+        }
+    }
+
+    fn get_input_schema(&self) -> Schema {
+        Schema::from_types(self.field_types.as_slice())
+    }
 }
@@ -1,8 +1,16 @@
+pub mod expression;
+pub mod filter;
 pub mod key_selector;
 pub mod print_output_format;
+pub mod project;
 pub mod reduce;
 pub mod timestamp_assigner;
+pub mod top_n;
+
+use crate::api::schema::Schema;
 
 pub trait FunctionSchema {
-    fn get_schema_types(&self) -> Vec<u8>;
+    /// the schema of the records emitted by the function,
+    /// the fields are named by the column indexes of the input, eg: `f0`
+    fn get_schema(&self) -> Schema;
 }
@@ -4,6 +4,7 @@ use serbuffer::types;
 
 use crate::api::element::Record;
 use crate::api::function::{Context, Function, OutputFormat};
+use crate::api::schema::Schema;
 use crate::api::window::TWindow;
 use crate::utils::date_time::{fmt_date_time, FMT_DATE_TIME};
 
@@ -80,4 +81,8 @@ impl Function for PrintOutputFormat {
     fn get_name(&self) -> &str {
         "PrintOutputFormat"
     }
+
+    fn get_input_schema(&self) -> Schema {
+        Schema::from_types(self.field_types.as_slice())
+    }
 }
@@ -0,0 +1,165 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::api::element::Record;
+use crate::api::function::{Context, FlatMapFunction, Function};
+use crate::api::schema::{Field, Schema};
+use crate::functions::schema_base::expression::{write_value, Expression, ExpressionError, Value};
+use crate::metrics::{register_counter, Tag};
+
+/// Project the records to the columns computed by the expressions,
+/// eg: `["name", "upper(name) AS upper_name", "value * 2 AS double_value"]`.
+///
+/// The column is named by the alias, or by the referenced column name if the expression
+/// is a column reference, otherwise by the index, eg: `f1`.
+/// The timestamp and the windows of the records are kept.
+/// The records failed to evaluate are dropped and reported by the counter
+/// `SchemaBaseProjectFunction_{operator_id}_errors`.
+#[derive(Debug)]
+pub struct SchemaBaseProjectFunction {
+    expressions: Vec<Expression>,
+    input_schema: Schema,
+    output_schema: Schema,
+
+    errors: Arc<AtomicU64>,
+}
+
+impl SchemaBaseProjectFunction {
+    pub fn new(columns: &[&str], schema: &Schema) -> Result<Self, ExpressionError> {
+        let mut expressions = Vec::with_capacity(columns.len());
+        let mut fields = Vec::with_capacity(columns.len());
+        for (index, column) in columns.iter().enumerate() {
+            let (expression, alias) = Expression::parse_with_alias(column, schema)?;
+
+            let field = match (alias, expression.get_column_index()) {
+                (Some(alias), _) => Field::new(alias.as_str(), expression.get_data_type(), false),
+                (None, Some(column_index)) => schema.get_fields()[column_index].clone(),
+                (None, None) => Field::new(
+                    format!("f{}", index).as_str(),
+                    expression.get_data_type(),
+                    false,
+                ),
+            };
+
+            expressions.push(expression);
+            fields.push(field);
+        }
+
+        Ok(SchemaBaseProjectFunction {
+            expressions,
+            input_schema: schema.clone(),
+            output_schema: Schema::new(fields),
+            errors: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    fn project(&self, record: &mut Record) -> Result<Record, ExpressionError> {
+        let input_field_types = self.input_schema.get_field_types();
+        let mut reader = record.get_reader(input_field_types.as_slice());
+
+        let mut values: Vec<Value> = Vec::with_capacity(self.expressions.len());
+        for expression in &self.expressions {
+            values.push(expression.eval_reader(&mut reader)?);
+        }
+
+        let output_field_types = self.output_schema.get_field_types();
+        let mut projected = Record::with_capacity(record.len());
+        let mut writer = projected.get_writer(output_field_types.as_slice());
+        for (value, data_type) in values.into_iter().zip(output_field_types.iter()) {
+            write_value(&mut writer, *data_type, value)?;
+        }
+
+        Ok(projected)
+    }
+}
+
+impl FlatMapFunction for SchemaBaseProjectFunction {
+    fn open(&mut self, context: &Context) -> crate::api::Result<()> {
+        let tags = vec![
+            Tag("job_id".to_string(), context.task_id.job_id.0.to_string()),
+            Tag(
+                "task_number".to_string(),
+                context.task_id.task_number.to_string(),
+            ),
+        ];
+        // the operator id tells apart the functions of the same type in a job
+        let metric_name = format!("SchemaBaseProjectFunction_{}_errors", context.operator_id.0);
+        register_counter(metric_name.as_str(), tags, self.errors.clone());
+
+        Ok(())
+    }
+
+    fn flat_map(&mut self, mut record: Record) -> Box<dyn Iterator<Item = Record>> {
+        match self.project(&mut record) {
+            Ok(projected) => {
+                // keep the metadata of the record, eg: the timestamp and the windows
+                record.values = projected.values;
+                Box::new(std::iter::once(record))
+            }
+            Err(_e) => {
+                self.errors.fetch_add(1, Ordering::Relaxed);
+                Box::new(std::iter::empty())
+            }
+        }
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        Ok(())
+    }
+}
+
+impl Function for SchemaBaseProjectFunction {
+    fn get_name(&self) -> &str {
+        "SchemaBaseProjectFunction"
+    }
+
+    fn get_output_schema(&self, _input_schema: &Schema) -> Schema {
+        self.output_schema.clone()
+    }
+
+    fn get_input_schema(&self) -> Schema {
+        self.input_schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::element::{types, Record};
+    use crate::api::function::{FlatMapFunction, Function};
+    use crate::api::schema::Schema;
+    use crate::functions::schema_base::project::SchemaBaseProjectFunction;
+
+    #[test]
+    pub fn schema_base_project_test() {
+        let schema = Schema::from_names(&["name", "value"], &[types::BYTES, types::U32]);
+        let mut project_fn = SchemaBaseProjectFunction::new(
+            &["value", "upper(name) AS upper_name", "value * 2.5", "$0"],
+            &schema,
+        )
+        .unwrap();
+
+        let output_schema = project_fn.get_output_schema(&schema);
+        assert_eq!(
+            output_schema.to_string(),
+            "(value U32 NOT NULL, upper_name BYTES NOT NULL, f2 F64 NOT NULL, \
+             name BYTES NOT NULL)"
+        );
+
+        let mut record = Record::new();
+        record.timestamp = 10;
+        let mut writer = record.get_writer(&[types::BYTES, types::U32]);
+        writer.set_str("abc").unwrap();
+        writer.set_u32(3).unwrap();
+
+        let mut projected: Vec<Record> = project_fn.flat_map(record).collect();
+        assert_eq!(projected.len(), 1);
+        assert_eq!(projected[0].timestamp, 10);
+
+        let field_types = output_schema.get_field_types();
+        let mut reader = projected[0].get_reader(field_types.as_slice());
+        assert_eq!(reader.get_u32(0).unwrap(), 3);
+        assert_eq!(reader.get_str(1).unwrap(), "ABC");
+        assert_eq!(reader.get_f64(2).unwrap(), 7.5);
+        assert_eq!(reader.get_str(3).unwrap(), "abc");
+    }
+}
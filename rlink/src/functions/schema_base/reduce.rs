@@ -3,7 +3,8 @@ use std::fmt::Debug;
 
 use crate::api::element::Record;
 use crate::api::element::{types, BufferReader, BufferWriter};
-use crate::api::function::{Context, Function, ReduceFunction};
+use crate::api::function::{AggregateFunction, Context, Function};
+use crate::api::schema::{Field, Schema};
 use crate::functions::percentile::{get_percentile_capacity, Percentile};
 use crate::functions::schema_base::FunctionSchema;
 
@@ -69,6 +70,23 @@ pub fn pct_u64(column_index: usize, scale: &'static [f64]) -> Box<dyn Aggregatio
     agg
 }
 
+/// pick the `i64` or `f64` aggregation by the type of the column
+pub(crate) fn column_aggregation(
+    column_index: usize,
+    field_types: &[u8],
+    agg_i64: fn(usize) -> Box<dyn Aggregation>,
+    agg_f64: fn(usize) -> Box<dyn Aggregation>,
+) -> Box<dyn Aggregation> {
+    match field_types[column_index] {
+        types::I64 => agg_i64(column_index),
+        types::F64 => agg_f64(column_index),
+        field_type => panic!(
+            "unsupported aggregation type {} of column {}",
+            field_type, column_index
+        ),
+    }
+}
+
 pub trait Aggregation: Debug {
     fn agg_type(&self) -> u8;
     fn len(&self) -> usize;
@@ -80,6 +98,14 @@ pub trait Aggregation: Debug {
         value_index: usize,
         record_reader: &mut BufferReader,
     );
+    /// merge two reduced values, used by the merging windows
+    fn merge(
+        &self,
+        writer: &mut BufferWriter,
+        value_reader: &mut BufferReader,
+        other_reader: &mut BufferReader,
+        value_index: usize,
+    );
 }
 
 #[derive(Debug)]
@@ -119,6 +145,18 @@ impl Aggregation for SumI64 {
             }
         }
     }
+
+    fn merge(
+        &self,
+        writer: &mut BufferWriter,
+        value_reader: &mut BufferReader,
+        other_reader: &mut BufferReader,
+        value_index: usize,
+    ) {
+        let agg_value =
+            value_reader.get_i64(value_index).unwrap() + other_reader.get_i64(value_index).unwrap();
+        writer.set_i64(agg_value).unwrap();
+    }
 }
 
 #[derive(Debug)]
@@ -157,6 +195,18 @@ impl Aggregation for SumF64 {
             }
         }
     }
+
+    fn merge(
+        &self,
+        writer: &mut BufferWriter,
+        value_reader: &mut BufferReader,
+        other_reader: &mut BufferReader,
+        value_index: usize,
+    ) {
+        let agg_value =
+            value_reader.get_f64(value_index).unwrap() + other_reader.get_f64(value_index).unwrap();
+        writer.set_f64(agg_value).unwrap();
+    }
 }
 
 #[derive(Debug)]
@@ -197,6 +247,20 @@ impl Aggregation for MaxI64 {
             }
         }
     }
+
+    fn merge(
+        &self,
+        writer: &mut BufferWriter,
+        value_reader: &mut BufferReader,
+        other_reader: &mut BufferReader,
+        value_index: usize,
+    ) {
+        let stat_value = value_reader.get_i64(value_index).unwrap();
+        let other_value = other_reader.get_i64(value_index).unwrap();
+        writer
+            .set_i64(std::cmp::max(stat_value, other_value))
+            .unwrap();
+    }
 }
 
 #[derive(Debug)]
@@ -241,6 +305,23 @@ impl Aggregation for MaxF64 {
             }
         }
     }
+
+    fn merge(
+        &self,
+        writer: &mut BufferWriter,
+        value_reader: &mut BufferReader,
+        other_reader: &mut BufferReader,
+        value_index: usize,
+    ) {
+        let stat_value = value_reader.get_f64(value_index).unwrap();
+        let other_value = other_reader.get_f64(value_index).unwrap();
+        let max_value = if other_value > stat_value {
+            other_value
+        } else {
+            stat_value
+        };
+        writer.set_f64(max_value).unwrap();
+    }
 }
 
 #[derive(Debug)]
@@ -281,6 +362,20 @@ impl Aggregation for MinI64 {
             }
         }
     }
+
+    fn merge(
+        &self,
+        writer: &mut BufferWriter,
+        value_reader: &mut BufferReader,
+        other_reader: &mut BufferReader,
+        value_index: usize,
+    ) {
+        let stat_value = value_reader.get_i64(value_index).unwrap();
+        let other_value = other_reader.get_i64(value_index).unwrap();
+        writer
+            .set_i64(std::cmp::min(stat_value, other_value))
+            .unwrap();
+    }
 }
 
 #[derive(Debug)]
@@ -325,6 +420,23 @@ impl Aggregation for MinF64 {
             }
         }
     }
+
+    fn merge(
+        &self,
+        writer: &mut BufferWriter,
+        value_reader: &mut BufferReader,
+        other_reader: &mut BufferReader,
+        value_index: usize,
+    ) {
+        let stat_value = value_reader.get_f64(value_index).unwrap();
+        let other_value = other_reader.get_f64(value_index).unwrap();
+        let min_value = if other_value > stat_value {
+            stat_value
+        } else {
+            other_value
+        };
+        writer.set_f64(min_value).unwrap();
+    }
 }
 
 #[derive(Debug)]
@@ -374,6 +486,22 @@ impl Aggregation for PctU64 {
             }
         }
     }
+
+    fn merge(
+        &self,
+        writer: &mut BufferWriter,
+        value_reader: &mut BufferReader,
+        other_reader: &mut BufferReader,
+        value_index: usize,
+    ) {
+        let stat_value = value_reader.get_bytes_mut(value_index).unwrap();
+        let other_value = other_reader.get_bytes_mut(value_index).unwrap();
+
+        let mut percentile = Percentile::new(self.scale, stat_value);
+        percentile.merge(&Percentile::new(self.scale, other_value));
+
+        writer.set_bytes(stat_value).unwrap();
+    }
 }
 
 #[derive(Debug)]
@@ -411,48 +539,102 @@ impl SchemaBaseReduceFunction {
     }
 }
 
+impl SchemaBaseReduceFunction {
+    /// the reduced values are named by the aggregated columns of the `input_schema`
+    fn value_schema(&self, input_schema: &Schema) -> Schema {
+        let fields = self
+            .agg_operators
+            .iter()
+            .zip(self.val_field_types.iter())
+            .map(|(agg, data_type)| {
+                let name = input_schema
+                    .get_field(agg.record_index())
+                    .map(|field| field.get_name().to_string())
+                    .unwrap_or(format!("f{}", agg.record_index()));
+                Field::new(name.as_str(), *data_type, false)
+            })
+            .collect();
+        Schema::new(fields)
+    }
+}
+
 impl FunctionSchema for SchemaBaseReduceFunction {
-    fn get_schema_types(&self) -> Vec<u8> {
-        self.val_field_types.clone()
+    fn get_schema(&self) -> Schema {
+        self.value_schema(&Schema::empty())
     }
 }
 
-impl ReduceFunction for SchemaBaseReduceFunction {
+/// The accumulator holds the aggregated values of the `val_field_types`,
+/// it is empty until the first record is added.
+impl AggregateFunction for SchemaBaseReduceFunction {
     fn open(&mut self, _context: &Context) -> crate::api::Result<()> {
         Ok(())
     }
 
-    fn reduce(&self, value: Option<&mut Record>, record: &mut Record) -> Record {
+    fn create_accumulator(&self) -> Record {
+        Record::with_capacity(0)
+    }
+
+    fn add(&self, accumulator: &mut Record, record: &mut Record) {
         let mut record_rt = Record::with_capacity(self.val_len);
         let mut writer = record_rt.get_writer(self.val_field_types.as_slice());
 
         let mut record_reader = record.get_reader(self.field_types.as_slice());
 
-        match value {
-            Some(state_value) => {
-                let mut stat_reader = state_value.get_reader(self.val_field_types.as_slice());
-
-                for index in 0..self.agg_operators.len() {
-                    self.agg_operators[index].reduce(
-                        writer.borrow_mut(),
-                        Some(stat_reader.borrow_mut()),
-                        index,
-                        record_reader.borrow_mut(),
-                    )
-                }
+        if accumulator.len() == 0 {
+            for index in 0..self.agg_operators.len() {
+                self.agg_operators[index].reduce(
+                    writer.borrow_mut(),
+                    None,
+                    index,
+                    record_reader.borrow_mut(),
+                )
             }
-            None => {
-                for index in 0..self.agg_operators.len() {
-                    self.agg_operators[index].reduce(
-                        writer.borrow_mut(),
-                        None,
-                        index,
-                        record_reader.borrow_mut(),
-                    )
-                }
+        } else {
+            let mut stat_reader = accumulator.get_reader(self.val_field_types.as_slice());
+
+            for index in 0..self.agg_operators.len() {
+                self.agg_operators[index].reduce(
+                    writer.borrow_mut(),
+                    Some(stat_reader.borrow_mut()),
+                    index,
+                    record_reader.borrow_mut(),
+                )
             }
         }
-        record_rt
+
+        *accumulator = record_rt;
+    }
+
+    fn merge(&self, accumulator: &mut Record, other: &mut Record) {
+        if other.len() == 0 {
+            return;
+        }
+        if accumulator.len() == 0 {
+            *accumulator = other.clone();
+            return;
+        }
+
+        let mut record_rt = Record::with_capacity(self.val_len);
+        let mut writer = record_rt.get_writer(self.val_field_types.as_slice());
+
+        let mut value_reader = accumulator.get_reader(self.val_field_types.as_slice());
+        let mut other_reader = other.get_reader(self.val_field_types.as_slice());
+
+        for index in 0..self.agg_operators.len() {
+            self.agg_operators[index].merge(
+                writer.borrow_mut(),
+                value_reader.borrow_mut(),
+                other_reader.borrow_mut(),
+                index,
+            )
+        }
+
+        *accumulator = record_rt;
+    }
+
+    fn get_result(&self, accumulator: Record) -> Record {
+        accumulator
     }
 
     fn close(&mut self) -> crate::api::Result<()> {
@@ -464,4 +646,12 @@ impl Function for SchemaBaseReduceFunction {
     fn get_name(&self) -> &str {
         "SchemaBaseReduceFunction"
     }
+
+    fn get_output_schema(&self, input_schema: &Schema) -> Schema {
+        self.value_schema(input_schema)
+    }
+
+    fn get_input_schema(&self) -> Schema {
+        Schema::from_types(self.field_types.as_slice())
+    }
 }
@@ -1,5 +1,6 @@
 use crate::api::element::Record;
 use crate::api::function::Function;
+use crate::api::schema::Schema;
 use crate::api::watermark::TimestampAssigner;
 
 #[derive(Debug)]
@@ -28,4 +29,8 @@ impl Function for SchemaBaseTimestampAssigner {
     fn get_name(&self) -> &str {
         "SchemaBaseTimestampAssigner"
     }
+
+    fn get_input_schema(&self) -> Schema {
+        Schema::from_types(self.field_types.as_slice())
+    }
 }
@@ -0,0 +1,457 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use bytes::{Buf, BufMut, BytesMut};
+
+use crate::api::element::{types, Buffer, BufferReader, Record};
+use crate::api::function::{Context, Function, ProcessWindowFunction, ReduceFunction};
+use crate::api::schema::{Field, Schema};
+use crate::api::window::TimeWindow;
+use crate::functions::schema_base::key_selector::SchemaBaseKeySelector;
+use crate::functions::schema_base::FunctionSchema;
+
+/// The sort order of a column of `TopN`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+/// Rank the records of a window by the sort columns, emit the first `n` records of each
+/// partition key with the rank, the rank starts from 1 and is appended as a `u64` column.
+///
+/// The records are ranked in two stages by `WindowedStream::top_n`,
+/// each task of the window keeps a bounded heap per window and key,
+/// then the candidates are merged by the partition key in a global stage.
+#[derive(Clone, Debug)]
+pub struct TopN {
+    n: usize,
+    field_types: Vec<u8>,
+    sort_columns: Vec<(usize, SortOrder)>,
+    partition_columns: Vec<usize>,
+}
+
+impl TopN {
+    pub fn new(
+        n: usize,
+        sort_columns: Vec<(usize, SortOrder)>,
+        partition_columns: Vec<usize>,
+        field_types: &[u8],
+    ) -> Self {
+        if n == 0 {
+            panic!("the `n` of TopN must be positive");
+        }
+        for (column_index, _order) in &sort_columns {
+            match field_types[*column_index] {
+                types::BOOL
+                | types::I8
+                | types::U8
+                | types::I16
+                | types::U16
+                | types::I32
+                | types::U32
+                | types::I64
+                | types::U64
+                | types::F32
+                | types::F64
+                | types::BYTES => {}
+                field_type => panic!(
+                    "unsupported sort type {} of column {}",
+                    field_type, column_index
+                ),
+            }
+        }
+
+        TopN {
+            n,
+            field_types: field_types.to_vec(),
+            sort_columns,
+            partition_columns,
+        }
+    }
+
+    pub(crate) fn partition_key_selector(&self) -> SchemaBaseKeySelector {
+        SchemaBaseKeySelector::new(self.partition_columns.clone(), self.field_types.as_slice())
+    }
+
+    pub(crate) fn new_heap(&self) -> TopNHeap {
+        TopNHeap {
+            n: self.n,
+            rows: BinaryHeap::with_capacity(self.n + 1),
+        }
+    }
+
+    /// restore the heap from the record packed by `TopNHeap::to_record`
+    pub(crate) fn heap_from_record(&self, heap_record: &mut Record) -> TopNHeap {
+        let mut heap = self.new_heap();
+        let mut reader = heap_record.get_reader(&[types::BYTES]);
+        let mut packed = BytesMut::from(reader.get_bytes(0).unwrap());
+        while packed.has_remaining() {
+            let len = packed.get_u32() as usize;
+            let mut row = Record::new();
+            row.values = Buffer::from(packed.split_to(len));
+            self.push(&mut heap, row);
+        }
+        heap
+    }
+
+    /// Returns `false` if the heap packed by `TopNHeap::into_record` is full and the `row`
+    /// does not rank before its last row, only the last row of the heap is read.
+    pub(crate) fn can_enter(&self, heap_record: &mut Record, row: &mut Record) -> bool {
+        let mut reader = heap_record.get_reader(&[types::BYTES]);
+        let mut packed = reader.get_bytes(0).unwrap();
+        let mut len = 0;
+        let mut last_values: &[u8] = &[];
+        while packed.has_remaining() {
+            let row_len = packed.get_u32() as usize;
+            let (values, remaining) = packed.split_at(row_len);
+            last_values = values;
+            packed = remaining;
+            len += 1;
+        }
+
+        if len < self.n {
+            return true;
+        }
+
+        let mut last_row = Record::new();
+        last_row.values = Buffer::from(BytesMut::from(last_values));
+        self.sort_key(row) < self.sort_key(&mut last_row)
+    }
+
+    pub(crate) fn push(&self, heap: &mut TopNHeap, mut row: Record) {
+        let sort_key = self.sort_key(&mut row);
+        heap.push(SortRow { sort_key, row });
+    }
+
+    fn sort_key(&self, row: &mut Record) -> Vec<SortField> {
+        let mut reader = row.get_reader(self.field_types.as_slice());
+        self.sort_columns
+            .iter()
+            .map(|(column_index, order)| SortField {
+                value: sort_value(&mut reader, *column_index, self.field_types[*column_index]),
+                order: *order,
+            })
+            .collect()
+    }
+}
+
+impl TopN {
+    /// the ranked records with the `rank` column appended
+    pub(crate) fn ranked_schema(&self, input_schema: &Schema) -> Schema {
+        let rank_schema = Schema::new(vec![Field::new("rank", types::U64, false)]);
+        if input_schema.is_empty() {
+            Schema::from_types(self.field_types.as_slice()).join(&rank_schema)
+        } else {
+            input_schema.join(&rank_schema)
+        }
+    }
+}
+
+impl FunctionSchema for TopN {
+    fn get_schema(&self) -> Schema {
+        self.ranked_schema(&Schema::empty())
+    }
+}
+
+/// Pre-aggregate the records of a window into the packed heap
+impl ReduceFunction for TopN {
+    fn open(&mut self, _context: &Context) -> crate::api::Result<()> {
+        Ok(())
+    }
+
+    fn reduce(&self, value: Option<&mut Record>, record: &mut Record) -> Record {
+        let mut heap = match value {
+            Some(value) => {
+                // the record out of the full heap is skipped without unpacking the heap
+                if !self.can_enter(value, record) {
+                    return value.clone();
+                }
+                self.heap_from_record(value)
+            }
+            None => self.new_heap(),
+        };
+        self.push(&mut heap, record.clone());
+        heap.into_record()
+    }
+
+    fn merge(&self, value: &mut Record, other: &mut Record) -> Record {
+        let mut heap = self.heap_from_record(value);
+        for row in self.heap_from_record(other).into_sorted_rows() {
+            self.push(&mut heap, row);
+        }
+        heap.into_record()
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        Ok(())
+    }
+}
+
+/// Emit the candidate records of the packed heap to the global stage
+impl ProcessWindowFunction for TopN {
+    fn open(&mut self, _context: &Context) -> crate::api::Result<()> {
+        Ok(())
+    }
+
+    fn process(
+        &mut self,
+        _key: &Record,
+        _window: &TimeWindow,
+        elements: Box<dyn Iterator<Item = Record>>,
+    ) -> Box<dyn Iterator<Item = Record>> {
+        let mut heap = self.new_heap();
+        for mut heap_record in elements {
+            for row in self.heap_from_record(&mut heap_record).into_sorted_rows() {
+                self.push(&mut heap, row);
+            }
+        }
+        Box::new(heap.into_sorted_rows().into_iter())
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        Ok(())
+    }
+}
+
+impl Function for TopN {
+    fn get_name(&self) -> &str {
+        "TopN"
+    }
+
+    /// the candidates of the window are the input records
+    fn get_output_schema(&self, input_schema: &Schema) -> Schema {
+        input_schema.clone()
+    }
+
+    fn get_input_schema(&self) -> Schema {
+        Schema::from_types(self.field_types.as_slice())
+    }
+}
+
+/// A bounded heap of the first `n` rows, the top of the heap is the last ranked row
+#[derive(Debug)]
+pub(crate) struct TopNHeap {
+    n: usize,
+    rows: BinaryHeap<SortRow>,
+}
+
+impl TopNHeap {
+    fn push(&mut self, sort_row: SortRow) {
+        if self.rows.len() < self.n {
+            self.rows.push(sort_row);
+        } else if let Some(last) = self.rows.peek() {
+            if sort_row < *last {
+                self.rows.pop();
+                self.rows.push(sort_row);
+            }
+        }
+    }
+
+    /// the rows in the rank order
+    pub(crate) fn into_sorted_rows(self) -> Vec<Record> {
+        self.rows
+            .into_sorted_vec()
+            .into_iter()
+            .map(|sort_row| sort_row.row)
+            .collect()
+    }
+
+    /// emit the rows in the rank order, extended with the rank
+    pub(crate) fn into_ranked_rows(self, timestamp: u64) -> Vec<Record> {
+        self.into_sorted_rows()
+            .into_iter()
+            .enumerate()
+            .map(|(index, mut row)| {
+                let mut rank = Record::with_capacity(8);
+                rank.get_writer(&[types::U64])
+                    .set_u64(index as u64 + 1)
+                    .unwrap();
+                row.extend(rank).unwrap();
+                row.timestamp = timestamp;
+                row
+            })
+            .collect()
+    }
+
+    /// pack the rows in the rank order into a `bytes` column, each row is prefixed with its
+    /// length, so the last ranked row is found without unpacking the heap, see `TopN::can_enter`
+    pub(crate) fn into_record(self) -> Record {
+        let mut packed = BytesMut::new();
+        for row in self.into_sorted_rows() {
+            let values = row.values.as_slice();
+            packed.put_u32(values.len() as u32);
+            packed.put_slice(values);
+        }
+
+        let mut heap_record = Record::with_capacity(packed.len() + 4);
+        heap_record
+            .get_writer(&[types::BYTES])
+            .set_bytes(packed.as_ref())
+            .unwrap();
+        heap_record
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+enum SortValue {
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Bytes(Vec<u8>),
+}
+
+#[derive(Clone, Debug)]
+struct SortField {
+    value: SortValue,
+    order: SortOrder,
+}
+
+impl PartialEq for SortField {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SortField {}
+
+impl PartialOrd for SortField {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// the first ranked value is the least, `NaN` is equal to any value
+impl Ord for SortField {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let ordering = self
+            .value
+            .partial_cmp(&other.value)
+            .unwrap_or(Ordering::Equal);
+        match self.order {
+            SortOrder::Asc => ordering,
+            SortOrder::Desc => ordering.reverse(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SortRow {
+    sort_key: Vec<SortField>,
+    row: Record,
+}
+
+impl PartialEq for SortRow {
+    fn eq(&self, other: &Self) -> bool {
+        self.sort_key.eq(&other.sort_key)
+    }
+}
+
+impl Eq for SortRow {}
+
+impl PartialOrd for SortRow {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SortRow {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key.cmp(&other.sort_key)
+    }
+}
+
+fn sort_value(reader: &mut BufferReader, column_index: usize, field_type: u8) -> SortValue {
+    match field_type {
+        types::BOOL => SortValue::U64(reader.get_bool(column_index).unwrap() as u64),
+        types::I8 => SortValue::I64(reader.get_i8(column_index).unwrap() as i64),
+        types::U8 => SortValue::U64(reader.get_u8(column_index).unwrap() as u64),
+        types::I16 => SortValue::I64(reader.get_i16(column_index).unwrap() as i64),
+        types::U16 => SortValue::U64(reader.get_u16(column_index).unwrap() as u64),
+        types::I32 => SortValue::I64(reader.get_i32(column_index).unwrap() as i64),
+        types::U32 => SortValue::U64(reader.get_u32(column_index).unwrap() as u64),
+        types::I64 => SortValue::I64(reader.get_i64(column_index).unwrap()),
+        types::U64 => SortValue::U64(reader.get_u64(column_index).unwrap()),
+        types::F32 => SortValue::F64(reader.get_f32(column_index).unwrap() as f64),
+        types::F64 => SortValue::F64(reader.get_f64(column_index).unwrap()),
+        _ => SortValue::Bytes(reader.get_bytes(column_index).unwrap().to_vec()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::element::{types, Record};
+    use crate::api::function::ReduceFunction;
+    use crate::functions::schema_base::top_n::{SortOrder, TopN};
+
+    const FIELD_TYPES: [u8; 2] = [types::I64, types::F64];
+
+    fn record(item: i64, value: f64) -> Record {
+        let mut record = Record::new();
+        let mut writer = record.get_writer(&FIELD_TYPES);
+        writer.set_i64(item).unwrap();
+        writer.set_f64(value).unwrap();
+        record
+    }
+
+    #[test]
+    pub fn top_n_test() {
+        let top_n = TopN::new(
+            3,
+            vec![(1, SortOrder::Desc), (0, SortOrder::Asc)],
+            vec![],
+            &FIELD_TYPES,
+        );
+
+        // two parallel tasks pre-aggregate the heaps
+        let mut left = top_n.reduce(None, &mut record(1, 1.0));
+        for (item, value) in &[(2, 5.0), (3, 3.0), (4, 4.0)] {
+            left = top_n.reduce(Some(&mut left), &mut record(*item, *value));
+        }
+        let mut right = top_n.reduce(None, &mut record(5, 4.0));
+        right = top_n.reduce(Some(&mut right), &mut record(6, 0.5));
+
+        let heap = top_n.heap_from_record(&mut top_n.merge(&mut left, &mut right));
+        let ranked: Vec<(i64, f64, u64)> = heap
+            .into_ranked_rows(10)
+            .into_iter()
+            .map(|mut row| {
+                assert_eq!(row.timestamp, 10);
+                let mut reader = row.get_reader(&[types::I64, types::F64, types::U64]);
+                (
+                    reader.get_i64(0).unwrap(),
+                    reader.get_f64(1).unwrap(),
+                    reader.get_u64(2).unwrap(),
+                )
+            })
+            .collect();
+
+        // the tie of value 4.0 is ranked by the item
+        assert_eq!(ranked, vec![(2, 5.0, 1), (4, 4.0, 2), (5, 4.0, 3)]);
+    }
+
+    #[test]
+    pub fn top_n_full_heap_test() {
+        let top_n = TopN::new(2, vec![(1, SortOrder::Desc)], vec![], &FIELD_TYPES);
+
+        let mut heap_record = top_n.reduce(None, &mut record(1, 3.0));
+        assert!(top_n.can_enter(&mut heap_record, &mut record(2, 0.5)));
+        heap_record = top_n.reduce(Some(&mut heap_record), &mut record(2, 2.0));
+
+        // the rows not ranked before the last row of the full heap are skipped
+        assert!(!top_n.can_enter(&mut heap_record, &mut record(3, 1.0)));
+        assert!(!top_n.can_enter(&mut heap_record, &mut record(3, 2.0)));
+        let skipped = top_n.reduce(Some(&mut heap_record), &mut record(3, 1.0));
+        assert_eq!(skipped.values.as_slice(), heap_record.values.as_slice());
+
+        assert!(top_n.can_enter(&mut heap_record, &mut record(4, 2.5)));
+        heap_record = top_n.reduce(Some(&mut heap_record), &mut record(4, 2.5));
+        let items: Vec<i64> = top_n
+            .heap_from_record(&mut heap_record)
+            .into_sorted_rows()
+            .into_iter()
+            .map(|mut row| row.get_reader(&FIELD_TYPES).get_i64(0).unwrap())
+            .collect();
+        assert_eq!(items, vec![1, 4]);
+    }
+}
@@ -0,0 +1,13 @@
+//! The records of a single `U64` kept in the keyed states, eg: the timestamps and the counts.
+
+use crate::api::element::{types, Record};
+
+pub(crate) fn u64_record(value: u64) -> Record {
+    let mut record = Record::new();
+    record.get_writer(&[types::U64]).set_u64(value).unwrap();
+    record
+}
+
+pub(crate) fn u64_value(record: &Record) -> u64 {
+    record.clone().get_reader(&[types::U64]).get_u64(0).unwrap()
+}
@@ -0,0 +1,336 @@
+use bytes::{Buf, BufMut, BytesMut};
+
+use crate::api::element::{Buffer, Record};
+use crate::api::function::{Context, Function, KeyedProcessFunction};
+use crate::api::schema::Schema;
+use crate::api::state::{KeyedProcessContext, TimeDomain};
+use crate::functions::cep::nfa::{Nfa, PartialMatch, Transition};
+use crate::functions::cep::{Pattern, PatternProcessFunction};
+use crate::functions::state_record::{u64_record, u64_value};
+
+const EVENT_STATE: &str = "cep_events";
+const PARTIAL_MATCH_STATE: &str = "cep_partial_matches";
+
+/// Match the records of the key against the `Pattern` in the event time order.
+///
+/// The records are buffered by the timestamp until the watermark passes,
+/// the records behind the watermark are dropped.
+/// A timer at the end of `within` of each partial match times out the partial match.
+pub(crate) struct CepFunction {
+    pattern: Pattern,
+    process_fn: Box<dyn PatternProcessFunction>,
+}
+
+impl CepFunction {
+    pub fn new(pattern: Pattern, process_fn: Box<dyn PatternProcessFunction>) -> Self {
+        CepFunction {
+            pattern,
+            process_fn,
+        }
+    }
+
+    /// advance all the partial matches of the key by the record
+    fn process_event(
+        &mut self,
+        mut record: Record,
+        context: &mut KeyedProcessContext,
+        output: &mut Vec<Record>,
+    ) {
+        let nfa = Nfa::new(&self.pattern);
+        let mut completed = Vec::new();
+        let mut timed_out = Vec::new();
+        let mut partial_matches = Vec::new();
+
+        let running_matches = load_partial_matches(context);
+        for partial_match in running_matches {
+            if nfa.is_timed_out(&partial_match, record.timestamp) {
+                timed_out.push(partial_match);
+                continue;
+            }
+
+            match nfa.advance(partial_match, &mut record) {
+                Transition::Take(partial_match) if nfa.is_complete(&partial_match) => {
+                    completed.push(partial_match)
+                }
+                Transition::Take(partial_match) | Transition::Ignore(partial_match) => {
+                    partial_matches.push(partial_match)
+                }
+                Transition::Discard => {}
+            }
+        }
+
+        if let Some(partial_match) = nfa.start(&mut record) {
+            if nfa.is_complete(&partial_match) {
+                completed.push(partial_match);
+            } else {
+                if let Some(within) = self.pattern.within {
+                    let timeout = partial_match.start_timestamp + within + 1;
+                    context.timer_service().register_event_time_timer(timeout);
+                }
+                partial_matches.push(partial_match);
+            }
+        }
+
+        store_partial_matches(context, partial_matches);
+        self.emit_timed_out(timed_out, record.timestamp, context, output);
+        for partial_match in completed {
+            let pattern_match = partial_match.to_pattern_match(&self.pattern);
+            output.extend(
+                self.process_fn
+                    .process_match(context.get_current_key(), pattern_match)
+                    .map(|mut matched| {
+                        matched.timestamp = record.timestamp;
+                        matched
+                    }),
+            );
+        }
+    }
+
+    fn emit_timed_out(
+        &mut self,
+        timed_out: Vec<PartialMatch>,
+        timestamp: u64,
+        context: &mut KeyedProcessContext,
+        output: &mut Vec<Record>,
+    ) {
+        let output_tag = match &self.pattern.timeout_output_tag {
+            Some(output_tag) => output_tag.clone(),
+            None => return,
+        };
+
+        for partial_match in timed_out {
+            let pattern_match = partial_match.to_pattern_match(&self.pattern);
+            output.extend(
+                self.process_fn
+                    .process_timed_out_match(context.get_current_key(), pattern_match)
+                    .map(|mut record| {
+                        record.timestamp = timestamp;
+                        record.set_output_tag(output_tag.clone());
+                        record
+                    }),
+            );
+        }
+    }
+}
+
+impl KeyedProcessFunction for CepFunction {
+    fn open(&mut self, context: &Context) -> crate::api::Result<()> {
+        self.process_fn.open(context)
+    }
+
+    fn process_element(
+        &mut self,
+        record: Record,
+        context: &mut KeyedProcessContext,
+    ) -> Box<dyn Iterator<Item = Record>> {
+        if record.timestamp <= context.timer_service().get_current_watermark() {
+            return Box::new(std::iter::empty());
+        }
+
+        let event_key = u64_record(record.timestamp);
+        let mut events = context
+            .get_map_state(EVENT_STATE)
+            .get(&event_key)
+            .map(|events| events.values.as_slice().to_vec())
+            .unwrap_or_default();
+        let values = record.values.as_slice();
+        events.put_u32(values.len() as u32);
+        events.put_slice(values);
+
+        let mut events_record = Record::new();
+        events_record.values = Buffer::from(BytesMut::from(events.as_slice()));
+        context
+            .get_map_state(EVENT_STATE)
+            .put(event_key, events_record);
+        context
+            .timer_service()
+            .register_event_time_timer(record.timestamp);
+
+        Box::new(std::iter::empty())
+    }
+
+    fn on_timer(
+        &mut self,
+        timestamp: u64,
+        _time_domain: TimeDomain,
+        context: &mut KeyedProcessContext,
+    ) -> Box<dyn Iterator<Item = Record>> {
+        let mut output = Vec::new();
+
+        // the buffered records up to the timer, in the event time order
+        let mut event_timestamps: Vec<u64> = match context.get_map_state(EVENT_STATE).iter() {
+            Some(iter) => iter
+                .map(|(event_key, _events)| u64_value(event_key))
+                .filter(|event_timestamp| *event_timestamp <= timestamp)
+                .collect(),
+            None => Vec::new(),
+        };
+        event_timestamps.sort();
+
+        for event_timestamp in event_timestamps {
+            let events = context
+                .get_map_state(EVENT_STATE)
+                .remove(&u64_record(event_timestamp))
+                .unwrap();
+            let mut packed = BytesMut::from(events.values.as_slice());
+            while packed.has_remaining() {
+                let len = packed.get_u32() as usize;
+                let mut record = Record::new();
+                record.values = Buffer::from(packed.split_to(len));
+                record.timestamp = event_timestamp;
+
+                self.process_event(record, context, &mut output);
+            }
+        }
+
+        // time out the partial matches that can not be completed
+        let nfa = Nfa::new(&self.pattern);
+        let (timed_out, partial_matches): (Vec<PartialMatch>, Vec<PartialMatch>) =
+            load_partial_matches(context)
+                .into_iter()
+                .partition(|partial_match| nfa.is_timed_out(partial_match, timestamp));
+        if !timed_out.is_empty() {
+            store_partial_matches(context, partial_matches);
+            self.emit_timed_out(timed_out, timestamp, context, &mut output);
+        }
+
+        Box::new(output.into_iter())
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        self.process_fn.close()
+    }
+}
+
+impl Function for CepFunction {
+    fn get_name(&self) -> &str {
+        self.process_fn.get_name()
+    }
+
+    fn get_output_schema(&self, input_schema: &Schema) -> Schema {
+        self.process_fn.get_output_schema(input_schema)
+    }
+}
+
+fn load_partial_matches(context: &mut KeyedProcessContext) -> Vec<PartialMatch> {
+    context
+        .get_list_state(PARTIAL_MATCH_STATE)
+        .get()
+        .iter()
+        .map(|record| PartialMatch::from_record(&mut record.clone()))
+        .collect()
+}
+
+fn store_partial_matches(context: &mut KeyedProcessContext, partial_matches: Vec<PartialMatch>) {
+    let records = partial_matches
+        .iter()
+        .map(|partial_match| partial_match.to_record())
+        .collect();
+    context.get_list_state(PARTIAL_MATCH_STATE).update(records);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::api::element::{types, Record};
+    use crate::api::function::{Context, Function, KeyedProcessFunction};
+    use crate::api::output_tag::OutputTag;
+    use crate::api::state::{KeyedProcessContext, KeyedStateStore, TimeDomain, TimerService};
+    use crate::functions::cep::{Pattern, PatternMatch, PatternProcessFunction};
+    use crate::functions::system::cep_function::CepFunction;
+
+    const FAILURE: i64 = 0;
+    const SUCCESS: i64 = 1;
+
+    fn login(result: i64, timestamp: u64) -> Record {
+        let mut record = Record::new();
+        record.get_writer(&[types::I64]).set_i64(result).unwrap();
+        record.timestamp = timestamp;
+        record
+    }
+
+    fn result(record: &mut Record) -> i64 {
+        record.get_reader(&[types::I64]).get_i64(0).unwrap()
+    }
+
+    /// emit the number of the matched records
+    struct CountPatternFunction {}
+
+    impl PatternProcessFunction for CountPatternFunction {
+        fn open(&mut self, _context: &Context) -> crate::api::Result<()> {
+            Ok(())
+        }
+
+        fn process_match(
+            &mut self,
+            _key: &Record,
+            pattern_match: PatternMatch,
+        ) -> Box<dyn Iterator<Item = Record>> {
+            let mut record = Record::new();
+            let len = pattern_match.len() as i64;
+            record.get_writer(&[types::I64]).set_i64(len).unwrap();
+            Box::new(vec![record].into_iter())
+        }
+
+        fn process_timed_out_match(
+            &mut self,
+            key: &Record,
+            pattern_match: PatternMatch,
+        ) -> Box<dyn Iterator<Item = Record>> {
+            self.process_match(key, pattern_match)
+        }
+
+        fn close(&mut self) -> crate::api::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Function for CountPatternFunction {
+        fn get_name(&self) -> &str {
+            "CountPatternFunction"
+        }
+    }
+
+    #[test]
+    pub fn cep_function_test() {
+        let pattern = Pattern::begin("failure")
+            .where_fn(|record| result(record) == FAILURE)
+            .times(3)
+            .followed_by("success")
+            .where_fn(|record| result(record) == SUCCESS)
+            .within(Duration::from_millis(100))
+            .side_output_timeout(OutputTag::new("timeout"));
+        let mut cep_fn = CepFunction::new(pattern, Box::new(CountPatternFunction {}));
+
+        let mut state_store = KeyedStateStore::new();
+        let mut timer_service = TimerService::new();
+        let mut ctx = KeyedProcessContext::new(0, 0, &mut state_store, &mut timer_service);
+
+        // out of order in the same watermark
+        for (login_result, timestamp) in &[(FAILURE, 3), (FAILURE, 1), (FAILURE, 2), (SUCCESS, 4)] {
+            let records: Vec<Record> = cep_fn
+                .process_element(login(*login_result, *timestamp), &mut ctx)
+                .collect();
+            assert!(records.is_empty());
+        }
+
+        let mut records: Vec<Record> = cep_fn
+            .on_timer(4, TimeDomain::EventTime, &mut ctx)
+            .collect();
+        assert_eq!(records.len(), 1);
+        assert_eq!(result(&mut records[0]), 4);
+        assert!(records[0].get_output_tag().is_none());
+
+        // the partial matches started by the failures at 2 and 3 time out
+        let mut records: Vec<Record> = cep_fn
+            .on_timer(200, TimeDomain::EventTime, &mut ctx)
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(result(&mut records[0]), 2);
+        assert_eq!(
+            records[0].get_output_tag(),
+            Some(&OutputTag::new("timeout"))
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use std::cmp::max;
+
+use crate::api::element::Record;
+use crate::api::function::{Context, Function, JoinFunction, KeyedProcessFunction};
+use crate::api::state::{KeyedProcessContext, TimeDomain};
+
+const LEFT_STATE: &str = "interval_join_left";
+const RIGHT_STATE: &str = "interval_join_right";
+
+/// Join a left record with the right records of the same key that
+/// `left.timestamp - lower_bound <= right.timestamp <= left.timestamp + upper_bound`.
+///
+/// The records are buffered until the watermark passes the bounds,
+/// the left record is kept until `left.timestamp + upper_bound`,
+/// the right record is kept until `right.timestamp + lower_bound`.
+pub(crate) struct IntervalJoinFunction {
+    /// milliseconds
+    lower_bound: u64,
+    /// milliseconds
+    upper_bound: u64,
+    join_fn: Box<dyn JoinFunction>,
+}
+
+impl IntervalJoinFunction {
+    pub fn new(lower_bound: u64, upper_bound: u64, join_fn: Box<dyn JoinFunction>) -> Self {
+        IntervalJoinFunction {
+            lower_bound,
+            upper_bound,
+            join_fn,
+        }
+    }
+
+    fn is_joined(&self, left: &Record, right: &Record) -> bool {
+        left.timestamp.saturating_sub(self.lower_bound) <= right.timestamp
+            && right.timestamp <= left.timestamp + self.upper_bound
+    }
+
+    fn join(&self, left: &mut Record, right: &mut Record) -> Record {
+        let timestamp = max(left.timestamp, right.timestamp);
+        let mut joined_record = self.join_fn.join(left, right);
+        joined_record.timestamp = timestamp;
+        joined_record
+    }
+}
+
+impl KeyedProcessFunction for IntervalJoinFunction {
+    fn open(&mut self, context: &Context) -> crate::api::Result<()> {
+        self.join_fn.open(context)
+    }
+
+    fn process_element(
+        &mut self,
+        mut record: Record,
+        context: &mut KeyedProcessContext,
+    ) -> Box<dyn Iterator<Item = Record>> {
+        let is_left = context.get_stream_seq() == 0;
+        let (other_state, state, cleanup_time) = if is_left {
+            (RIGHT_STATE, LEFT_STATE, record.timestamp + self.upper_bound)
+        } else {
+            (LEFT_STATE, RIGHT_STATE, record.timestamp + self.lower_bound)
+        };
+
+        let mut joined_records = Vec::new();
+        let mut others = context.get_list_state(other_state).get().to_vec();
+        for other in others.iter_mut() {
+            if is_left && self.is_joined(&record, other) {
+                joined_records.push(self.join(&mut record, other));
+            } else if !is_left && self.is_joined(other, &record) {
+                joined_records.push(self.join(other, &mut record));
+            }
+        }
+
+        // the record can't be joined by the coming records once the watermark passes
+        if cleanup_time > context.timer_service().get_current_watermark() {
+            context
+                .timer_service()
+                .register_event_time_timer(cleanup_time);
+            context.get_list_state(state).add(record);
+        }
+
+        Box::new(joined_records.into_iter())
+    }
+
+    fn on_timer(
+        &mut self,
+        timestamp: u64,
+        _time_domain: TimeDomain,
+        context: &mut KeyedProcessContext,
+    ) -> Box<dyn Iterator<Item = Record>> {
+        let upper_bound = self.upper_bound;
+        let lefts: Vec<Record> = context
+            .get_list_state(LEFT_STATE)
+            .get()
+            .iter()
+            .filter(|record| record.timestamp + upper_bound > timestamp)
+            .cloned()
+            .collect();
+        context.get_list_state(LEFT_STATE).update(lefts);
+
+        let lower_bound = self.lower_bound;
+        let rights: Vec<Record> = context
+            .get_list_state(RIGHT_STATE)
+            .get()
+            .iter()
+            .filter(|record| record.timestamp + lower_bound > timestamp)
+            .cloned()
+            .collect();
+        context.get_list_state(RIGHT_STATE).update(rights);
+
+        Box::new(std::iter::empty())
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        self.join_fn.close()
+    }
+}
+
+impl Function for IntervalJoinFunction {
+    fn get_name(&self) -> &str {
+        "IntervalJoinFunction"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::element::{types, Record};
+    use crate::api::function::KeyedProcessFunction;
+    use crate::api::state::{KeyedProcessContext, KeyedStateStore, TimeDomain, TimerService};
+    use crate::functions::system::interval_join_function::{
+        IntervalJoinFunction, LEFT_STATE, RIGHT_STATE,
+    };
+    use crate::functions::system::window_join_function::tests::MyJoinFunction;
+
+    fn record(value: i64, timestamp: u64) -> Record {
+        let mut record = Record::new();
+        record.get_writer(&[types::I64]).set_i64(value).unwrap();
+        record.timestamp = timestamp;
+        record
+    }
+
+    fn process(
+        join_fn: &mut IntervalJoinFunction,
+        state_store: &mut KeyedStateStore,
+        timer_service: &mut TimerService,
+        stream_seq: usize,
+        record: Record,
+    ) -> Vec<(i64, i64, u64)> {
+        let mut ctx =
+            KeyedProcessContext::new(record.timestamp, stream_seq, state_store, timer_service);
+        join_fn
+            .process_element(record, &mut ctx)
+            .map(|mut record| {
+                let timestamp = record.timestamp;
+                let mut reader = record.get_reader(&[types::I64, types::I64]);
+                (
+                    reader.get_i64(0).unwrap(),
+                    reader.get_i64(1).unwrap(),
+                    timestamp,
+                )
+            })
+            .collect()
+    }
+
+    fn state_len(
+        state_store: &mut KeyedStateStore,
+        timer_service: &mut TimerService,
+        state_name: &str,
+    ) -> usize {
+        let mut ctx = KeyedProcessContext::new(0, 0, state_store, timer_service);
+        ctx.get_list_state(state_name).get().len()
+    }
+
+    #[test]
+    pub fn interval_join_test() {
+        // right.timestamp in [left.timestamp - 5, left.timestamp + 10]
+        let mut join_fn = IntervalJoinFunction::new(5, 10, Box::new(MyJoinFunction {}));
+        let mut state_store = KeyedStateStore::new();
+        let mut timer_service = TimerService::new();
+        timer_service.set_current_key(record(1, 0));
+        let (f, s, t) = (&mut join_fn, &mut state_store, &mut timer_service);
+
+        assert!(process(f, s, t, 0, record(1, 10)).is_empty());
+        assert_eq!(process(f, s, t, 1, record(2, 15)), vec![(1, 2, 15)]);
+        assert!(process(f, s, t, 1, record(3, 3)).is_empty());
+        assert_eq!(process(f, s, t, 1, record(4, 6)), vec![(1, 4, 10)]);
+        // the new left record joins the buffered right records
+        assert_eq!(
+            process(f, s, t, 0, record(5, 8)),
+            vec![(5, 2, 15), (5, 3, 8), (5, 4, 8)]
+        );
+
+        // the left records are kept till `timestamp + 10`, the right till `timestamp + 5`
+        t.current_watermark = 18;
+        for (timer, _key) in t.poll_event_time_timers(18) {
+            let mut ctx = KeyedProcessContext::new(timer, 0, s, t);
+            assert_eq!(
+                f.on_timer(timer, TimeDomain::EventTime, &mut ctx).count(),
+                0
+            );
+        }
+        assert_eq!(state_len(s, t, LEFT_STATE), 1);
+        assert_eq!(state_len(s, t, RIGHT_STATE), 1);
+
+        t.current_watermark = 20;
+        for (timer, _key) in t.poll_event_time_timers(20) {
+            let mut ctx = KeyedProcessContext::new(timer, 0, s, t);
+            assert_eq!(
+                f.on_timer(timer, TimeDomain::EventTime, &mut ctx).count(),
+                0
+            );
+        }
+        assert_eq!(state_len(s, t, LEFT_STATE), 0);
+        assert_eq!(state_len(s, t, RIGHT_STATE), 0);
+
+        // the record that can't be joined by the coming records is not buffered
+        assert!(process(f, s, t, 1, record(6, 12)).is_empty());
+        assert_eq!(state_len(s, t, RIGHT_STATE), 0);
+    }
+}
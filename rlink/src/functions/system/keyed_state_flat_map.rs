@@ -3,6 +3,7 @@ use crate::api::element::{Barrier, Element, Record};
 use crate::api::function::{Context, FlatMapFunction, Function};
 use crate::api::properties::SystemProperties;
 use crate::api::runtime::{CheckpointId, JobId};
+use crate::api::schema::Schema;
 use crate::api::window::{TWindow, Window};
 use crate::storage::keyed_state::{ReducingState, StateKey, TReducingState};
 
@@ -75,6 +76,10 @@ impl Function for KeyedStateFlatMapFunction {
     fn get_name(&self) -> &str {
         "KeyedStateFlatMapFunction"
     }
+
+    fn get_output_schema(&self, input_schema: &Schema) -> Schema {
+        input_schema.clone()
+    }
 }
 
 pub(crate) struct BatchIterator<T>
@@ -1,3 +1,11 @@
+pub mod cep_function;
+pub mod interval_join_function;
 pub mod keyed_state_flat_map;
+pub mod reduce_aggregate_function;
+pub mod rolling_aggregate_function;
 pub mod system_input_format;
 pub mod system_output_format;
+pub mod top_n_function;
+pub mod union_function;
+pub mod window_join_function;
+pub mod window_process_function;
@@ -0,0 +1,68 @@
+use crate::api::element::Record;
+use crate::api::function::{AggregateFunction, Context, Function, ReduceFunction};
+use crate::api::schema::Schema;
+
+/// Run a `ReduceFunction` as an `AggregateFunction`, the reduced value is the accumulator.
+/// The empty accumulator stands for the key without any reduced value yet.
+pub(crate) struct ReduceAggregateFunction {
+    reduce_fn: Box<dyn ReduceFunction>,
+}
+
+impl ReduceAggregateFunction {
+    pub fn new(reduce_fn: Box<dyn ReduceFunction>) -> Self {
+        ReduceAggregateFunction { reduce_fn }
+    }
+}
+
+impl AggregateFunction for ReduceAggregateFunction {
+    fn open(&mut self, context: &Context) -> crate::api::Result<()> {
+        self.reduce_fn.open(context)
+    }
+
+    fn create_accumulator(&self) -> Record {
+        Record::with_capacity(0)
+    }
+
+    fn add(&self, accumulator: &mut Record, record: &mut Record) {
+        let value = if accumulator.len() == 0 {
+            self.reduce_fn.reduce(None, record)
+        } else {
+            self.reduce_fn.reduce(Some(&mut *accumulator), record)
+        };
+        *accumulator = value;
+    }
+
+    fn merge(&self, accumulator: &mut Record, other: &mut Record) {
+        if other.len() == 0 {
+            return;
+        }
+
+        if accumulator.len() == 0 {
+            *accumulator = other.clone();
+        } else {
+            *accumulator = self.reduce_fn.merge(accumulator, other);
+        }
+    }
+
+    fn get_result(&self, accumulator: Record) -> Record {
+        accumulator
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        self.reduce_fn.close()
+    }
+}
+
+impl Function for ReduceAggregateFunction {
+    fn get_name(&self) -> &str {
+        self.reduce_fn.get_name()
+    }
+
+    fn get_output_schema(&self, input_schema: &Schema) -> Schema {
+        self.reduce_fn.get_output_schema(input_schema)
+    }
+
+    fn get_input_schema(&self) -> Schema {
+        self.reduce_fn.get_input_schema()
+    }
+}
@@ -0,0 +1,203 @@
+use crate::api::element::Record;
+use crate::api::function::{AggregateFunction, Context, Function, KeyedProcessFunction};
+use crate::api::schema::Schema;
+use crate::api::state::{KeyedProcessContext, TimeDomain};
+use crate::functions::state_record::{u64_record, u64_value};
+use crate::utils::date_time::current_timestamp_millis;
+
+const ACCUMULATOR_STATE: &str = "rolling_accumulator";
+const EXPIRE_STATE: &str = "rolling_expire";
+
+/// Aggregate the records of the key across the whole stream, the accumulator is kept
+/// in the keyed state and the key with the updated result is emitted for each record.
+///
+/// If `idle_state_retention` is set, the states of the key are cleared once no record
+/// of the key arrives for the retention in the processing time.
+pub(crate) struct RollingAggregateFunction {
+    aggregate_fn: Box<dyn AggregateFunction>,
+    idle_state_retention: Option<u64>,
+    /// the schema of the key that the accumulated values are appended to
+    key_schema: Schema,
+}
+
+impl RollingAggregateFunction {
+    pub fn new(
+        aggregate_fn: Box<dyn AggregateFunction>,
+        idle_state_retention: Option<u64>,
+        key_schema: Schema,
+    ) -> Self {
+        RollingAggregateFunction {
+            aggregate_fn,
+            idle_state_retention,
+            key_schema,
+        }
+    }
+
+    /// move the expire timer of the key to `retention` after now
+    fn refresh_expire_time(&self, retention: u64, context: &mut KeyedProcessContext) {
+        let expire_time = context.get_value_state(EXPIRE_STATE).value().map(u64_value);
+        if let Some(expire_time) = expire_time {
+            context
+                .timer_service()
+                .delete_processing_time_timer(expire_time);
+        }
+
+        let expire_time = current_timestamp_millis() + retention;
+        context
+            .timer_service()
+            .register_processing_time_timer(expire_time);
+        context
+            .get_value_state(EXPIRE_STATE)
+            .update(u64_record(expire_time));
+    }
+}
+
+impl KeyedProcessFunction for RollingAggregateFunction {
+    fn open(&mut self, context: &Context) -> crate::api::Result<()> {
+        self.aggregate_fn.open(context)
+    }
+
+    fn process_element(
+        &mut self,
+        mut record: Record,
+        context: &mut KeyedProcessContext,
+    ) -> Box<dyn Iterator<Item = Record>> {
+        let mut accumulator = match context.get_value_state(ACCUMULATOR_STATE).value() {
+            Some(accumulator) => accumulator.clone(),
+            None => self.aggregate_fn.create_accumulator(),
+        };
+        self.aggregate_fn.add(&mut accumulator, &mut record);
+        context
+            .get_value_state(ACCUMULATOR_STATE)
+            .update(accumulator.clone());
+
+        if let Some(retention) = self.idle_state_retention {
+            self.refresh_expire_time(retention, context);
+        }
+
+        let mut upsert_record = context.get_current_key().clone();
+        upsert_record
+            .extend(self.aggregate_fn.get_result(accumulator))
+            .unwrap();
+        upsert_record.timestamp = record.timestamp;
+
+        Box::new(vec![upsert_record].into_iter())
+    }
+
+    fn on_timer(
+        &mut self,
+        timestamp: u64,
+        _time_domain: TimeDomain,
+        context: &mut KeyedProcessContext,
+    ) -> Box<dyn Iterator<Item = Record>> {
+        // the timer may be outdated if it was not deleted before a restore
+        let expire_time = context.get_value_state(EXPIRE_STATE).value().map(u64_value);
+        if expire_time == Some(timestamp) {
+            context.get_value_state(ACCUMULATOR_STATE).clear();
+            context.get_value_state(EXPIRE_STATE).clear();
+        }
+
+        Box::new(std::iter::empty())
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        self.aggregate_fn.close()
+    }
+}
+
+impl Function for RollingAggregateFunction {
+    fn get_name(&self) -> &str {
+        self.aggregate_fn.get_name()
+    }
+
+    fn get_output_schema(&self, input_schema: &Schema) -> Schema {
+        let value_schema = self.aggregate_fn.get_output_schema(input_schema);
+        if self.key_schema.is_empty() || value_schema.is_empty() {
+            Schema::empty()
+        } else {
+            self.key_schema.join(&value_schema)
+        }
+    }
+
+    fn get_input_schema(&self) -> Schema {
+        self.aggregate_fn.get_input_schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::element::{types, Record};
+    use crate::api::function::KeyedProcessFunction;
+    use crate::api::schema::Schema;
+    use crate::api::state::{KeyedProcessContext, KeyedStateStore, TimeDomain, TimerService};
+    use crate::functions::schema_base::reduce::{sum_i64, SchemaBaseReduceFunction};
+    use crate::functions::state_record::u64_value;
+    use crate::functions::system::rolling_aggregate_function::{
+        RollingAggregateFunction, EXPIRE_STATE,
+    };
+
+    fn record(key: i64, value: i64) -> Record {
+        let mut record = Record::new();
+        let mut writer = record.get_writer(&[types::I64, types::I64]);
+        writer.set_i64(key).unwrap();
+        writer.set_i64(value).unwrap();
+        record
+    }
+
+    fn key(key: i64) -> Record {
+        let mut record = Record::new();
+        record.get_writer(&[types::I64]).set_i64(key).unwrap();
+        record
+    }
+
+    /// process the record and return the rolling sum of the key
+    fn sum(
+        rolling_fn: &mut RollingAggregateFunction,
+        state_store: &mut KeyedStateStore,
+        timer_service: &mut TimerService,
+        key_value: i64,
+        value: i64,
+    ) -> i64 {
+        timer_service.set_current_key(key(key_value));
+        let mut ctx = KeyedProcessContext::new(0, 0, state_store, timer_service);
+        let mut upserts: Vec<Record> = rolling_fn
+            .process_element(record(key_value, value), &mut ctx)
+            .collect();
+        assert_eq!(upserts.len(), 1);
+
+        let mut reader = upserts[0].get_reader(&[types::I64, types::I64]);
+        assert_eq!(reader.get_i64(0).unwrap(), key_value);
+        reader.get_i64(1).unwrap()
+    }
+
+    #[test]
+    pub fn rolling_sum_test() {
+        let field_types = [types::I64, types::I64];
+        let aggregate_fn = SchemaBaseReduceFunction::new(vec![sum_i64(1)], &field_types);
+        let mut rolling_fn =
+            RollingAggregateFunction::new(Box::new(aggregate_fn), Some(1000), Schema::empty());
+
+        let mut state_store = KeyedStateStore::new();
+        let mut timer_service = TimerService::new();
+        let (f, s, t) = (&mut rolling_fn, &mut state_store, &mut timer_service);
+
+        assert_eq!(sum(f, s, t, 1, 2), 2);
+        assert_eq!(sum(f, s, t, 2, 5), 5);
+        assert_eq!(sum(f, s, t, 1, 3), 5);
+
+        // the state of key 1 expires by its last timer
+        t.set_current_key(key(1));
+        {
+            let mut ctx = KeyedProcessContext::new(0, 0, s, t);
+            let expire_time = ctx.get_value_state(EXPIRE_STATE).value().map(u64_value);
+            let records: Vec<Record> = f
+                .on_timer(expire_time.unwrap(), TimeDomain::ProcessingTime, &mut ctx)
+                .collect();
+            assert!(records.is_empty());
+            assert!(ctx.get_value_state(EXPIRE_STATE).value().is_none());
+        }
+
+        assert_eq!(sum(f, s, t, 1, 4), 4);
+        assert_eq!(sum(f, s, t, 2, 1), 6);
+    }
+}
@@ -3,6 +3,7 @@ use crate::api::element::{Element, Record};
 use crate::api::function::{Context, Function, InputFormat, InputSplit, InputSplitSource};
 use crate::api::properties::SystemProperties;
 use crate::api::runtime::TaskId;
+use crate::api::schema::Schema;
 use crate::channel::ElementReceiver;
 use crate::dag::execution_graph::ExecutionEdge;
 use crate::functions::iterator::{ChannelIterator, MultiChannelIterator};
@@ -98,6 +99,10 @@ impl Function for SystemInputFormat {
     fn get_name(&self) -> &str {
         "SystemInputFormat"
     }
+
+    fn get_output_schema(&self, input_schema: &Schema) -> Schema {
+        input_schema.clone()
+    }
 }
 
 struct SubscribeIterator {
@@ -3,27 +3,72 @@ use std::collections::HashMap;
 
 use crate::api::element::{Element, Partition, Record};
 use crate::api::function::{Context, Function, OutputFormat};
+use crate::api::output_tag::OutputTag;
 use crate::api::properties::SystemProperties;
 use crate::api::runtime::{ChannelKey, JobId, TaskId};
 use crate::channel::ElementSender;
 use crate::dag::execution_graph::ExecutionEdge;
 use crate::pub_sub::{memory, network, ChannelType, DEFAULT_CHANNEL_SIZE};
 
-/// support job's Multiplexing, but only one channel mode(memory/network) support
+/// support job's Multiplexing, the jobs can be subscribed by memory or network channel.
+/// the records are routed to the jobs subscribed the `output_tag` of the record,
+/// other elements are broadcast to all jobs.
 pub(crate) struct SystemOutputFormat {
     task_id: TaskId,
-    channel_type: ChannelType,
-    job_senders: Vec<(JobId, Vec<(TaskId, ElementSender)>)>,
+    job_senders: Vec<(
+        JobId,
+        ChannelType,
+        Option<OutputTag>,
+        Vec<(TaskId, ElementSender)>,
+    )>,
 }
 
 impl SystemOutputFormat {
     pub fn new() -> Self {
         SystemOutputFormat {
             task_id: TaskId::default(),
-            channel_type: ChannelType::Memory,
             job_senders: Vec::new(),
         }
     }
+
+    fn send_element(
+        task_id: TaskId,
+        channel_type: ChannelType,
+        task_senders: &Vec<(TaskId, ElementSender)>,
+        mut element: Element,
+    ) {
+        match channel_type {
+            ChannelType::Memory => {
+                let (target_task_id, sender) = &task_senders[0];
+                match element.borrow_mut() {
+                    Element::Record(record) => {
+                        record.channel_key = ChannelKey {
+                            source_task_id: task_id,
+                            target_task_id: *target_task_id,
+                        };
+                    }
+                    _ => {}
+                }
+                sender.send(element).unwrap()
+            }
+            ChannelType::Network => {
+                if element.is_record() {
+                    // the task may connect to a subset of the child tasks, eg: `Rescale`
+                    let partition = element.get_partition();
+                    let index = task_senders
+                        .binary_search_by_key(&partition, |(task_id, _)| task_id.task_number)
+                        .unwrap_or_else(|_| panic!("partition {} is not connected", partition));
+                    let (_task_id, sender) = &task_senders[index];
+                    sender.send(element).unwrap()
+                } else {
+                    // the watermarks and barriers are broadcast to all partitions
+                    for (_task_id, sender) in task_senders {
+                        sender.send(element.clone()).unwrap()
+                    }
+                }
+            }
+        }
+    }
 }
 
 impl OutputFormat for SystemOutputFormat {
@@ -49,26 +94,29 @@ impl OutputFormat for SystemOutputFormat {
 
         let mut memory_jobs = Vec::new();
         let mut network_jobs = Vec::new();
+        let mut job_output_tags = HashMap::new();
 
         context
             .children
             .iter()
-            .for_each(|(execution_node, execution_edge)| match execution_edge {
-                ExecutionEdge::Memory => memory_jobs.push(execution_node.task_id.clone()),
-                ExecutionEdge::Network => network_jobs.push(execution_node.task_id.clone()),
+            .for_each(|(execution_node, execution_edge)| {
+                let output_tag = execution_node
+                    .stream_nodes
+                    .get(0)
+                    .and_then(|stream_node| stream_node.output_tag.clone());
+                job_output_tags.insert(execution_node.task_id.job_id, output_tag);
+
+                match execution_edge {
+                    ExecutionEdge::Memory => memory_jobs.push(execution_node.task_id.clone()),
+                    ExecutionEdge::Network => network_jobs.push(execution_node.task_id.clone()),
+                }
             });
 
         if memory_jobs.len() == 0 && network_jobs.len() == 0 {
             panic!("child job not found");
         }
 
-        if memory_jobs.len() > 0 && network_jobs.len() > 0 {
-            panic!("only one channel mode(memory/network) support");
-        }
-
         if memory_jobs.len() > 0 {
-            self.channel_type = ChannelType::Memory;
-
             let task_senders = memory::publish(&context.task_id, &memory_jobs, channel_size);
 
             let mut job_senders = HashMap::new();
@@ -89,24 +137,19 @@ impl OutputFormat for SystemOutputFormat {
                 if senders.len() != 1 {
                     panic!("only `Forward` support in memory channel");
                 }
-                self.job_senders.push((job_id, senders));
+                let output_tag = job_output_tags.get(&job_id).cloned().unwrap_or_default();
+                self.job_senders
+                    .push((job_id, ChannelType::Memory, output_tag, senders));
             }
         }
 
         if network_jobs.len() > 0 {
-            self.channel_type = ChannelType::Network;
             let task_senders = network::publish(&context.task_id, &network_jobs, channel_size);
 
-            let child_parallelism = task_senders[0].0.target_task_id.num_tasks;
-
             // group by `job_id`
             let mut job_senders = HashMap::new();
             for (channel_key, sender) in task_senders {
                 let target_task_id = channel_key.target_task_id;
-                if child_parallelism != target_task_id.num_tasks {
-                    panic!("the task `num_tasks` conflict in network channel");
-                }
-
                 job_senders
                     .entry(target_task_id.job_id)
                     .or_insert(Vec::new())
@@ -114,19 +157,17 @@ impl OutputFormat for SystemOutputFormat {
             }
 
             for (job_id, mut task_senders) in job_senders {
-                if task_senders.len() != child_parallelism as usize {
+                let child_parallelism = task_senders[0].0.num_tasks;
+                if task_senders.len() > child_parallelism as usize {
                     panic!("the job `num_tasks` conflict in network channel");
                 }
 
-                // sort `task_senders` by `TaskId.task_number`
+                // sort `task_senders` by `TaskId.task_number`, the records are routed by it
                 task_senders.sort_by(|a, b| a.0.task_number.cmp(&b.0.task_number));
-                for i in 0..task_senders.len() {
-                    if task_senders[i].0.task_number as usize != i {
-                        panic!("lost task");
-                    }
-                }
 
-                self.job_senders.push((job_id, task_senders));
+                let output_tag = job_output_tags.get(&job_id).cloned().unwrap_or_default();
+                self.job_senders
+                    .push((job_id, ChannelType::Network, output_tag, task_senders));
             }
         }
         Ok(())
@@ -135,50 +176,35 @@ impl OutputFormat for SystemOutputFormat {
     fn write_record(&mut self, _record: Record) {}
 
     fn write_element(&mut self, mut element: Element) {
-        match self.channel_type {
-            ChannelType::Memory => {
-                // Multiplexing publish
-                if self.job_senders.len() == 1 {
-                    let (_job_id, task_senders) = &self.job_senders[0];
-                    let (task_id, sender) = &task_senders[0];
-                    match element.borrow_mut() {
-                        Element::Record(record) => {
-                            record.channel_key = ChannelKey {
-                                source_task_id: self.task_id,
-                                target_task_id: *task_id,
-                            };
-                        }
-                        _ => {}
-                    }
-                    sender.send(element).unwrap()
-                } else {
-                    for (_job, task_senders) in &self.job_senders {
-                        let (task_id, sender) = &task_senders[0];
-                        match element.borrow_mut() {
-                            Element::Record(record) => {
-                                record.channel_key = ChannelKey {
-                                    source_task_id: self.task_id,
-                                    target_task_id: *task_id,
-                                };
-                            }
-                            _ => {}
-                        }
-                        sender.send(element.clone()).unwrap()
-                    }
-                }
+        // the tag is only used for routing, the downstream receive the record as the main output
+        let (is_record, output_tag) = match element.borrow_mut() {
+            Element::Record(record) => (true, record.output_tag.take()),
+            _ => (false, None),
+        };
+        let subscribed = |job_output_tag: &Option<OutputTag>| -> bool {
+            !is_record || job_output_tag.eq(&output_tag)
+        };
+
+        // Multiplexing publish
+        if self.job_senders.len() == 1 {
+            let (_job_id, channel_type, job_output_tag, task_senders) = &self.job_senders[0];
+            if subscribed(job_output_tag) {
+                SystemOutputFormat::send_element(
+                    self.task_id,
+                    *channel_type,
+                    task_senders,
+                    element,
+                );
             }
-            ChannelType::Network => {
-                if self.job_senders.len() == 1 {
-                    let (_job_id, task_senders) = &self.job_senders[0];
-                    let (_task_id, sender) =
-                        task_senders.get(element.get_partition() as usize).unwrap();
-                    sender.send(element).unwrap();
-                } else {
-                    for (_job, task_senders) in &self.job_senders {
-                        let (_task_id, sender) =
-                            task_senders.get(element.get_partition() as usize).unwrap();
-                        sender.send(element.clone()).unwrap()
-                    }
+        } else {
+            for (_job_id, channel_type, job_output_tag, task_senders) in &self.job_senders {
+                if subscribed(job_output_tag) {
+                    SystemOutputFormat::send_element(
+                        self.task_id,
+                        *channel_type,
+                        task_senders,
+                        element.clone(),
+                    );
                 }
             }
         }
@@ -0,0 +1,98 @@
+use crate::api::element::Record;
+use crate::api::function::{Context, Function, KeyedProcessFunction};
+use crate::api::schema::Schema;
+use crate::api::state::{KeyedProcessContext, TimeDomain};
+use crate::functions::schema_base::top_n::TopN;
+use crate::functions::state_record::u64_record;
+
+const HEAP_STATE: &str = "top_n_heap";
+
+/// The global stage of `TopN`, merge the candidate records of the partition key
+/// from all the window tasks, the ranked records are emitted when the window ends.
+///
+/// The candidate records are stamped with the `max_timestamp` of their window,
+/// so the heaps are kept by the timestamp until the timer of the window fires.
+pub(crate) struct TopNFunction {
+    top_n: TopN,
+    time_domain: TimeDomain,
+}
+
+impl TopNFunction {
+    pub fn new(top_n: TopN, time_domain: TimeDomain) -> Self {
+        TopNFunction { top_n, time_domain }
+    }
+}
+
+impl KeyedProcessFunction for TopNFunction {
+    fn open(&mut self, _context: &Context) -> crate::api::Result<()> {
+        Ok(())
+    }
+
+    fn process_element(
+        &mut self,
+        mut record: Record,
+        context: &mut KeyedProcessContext,
+    ) -> Box<dyn Iterator<Item = Record>> {
+        let window_key = u64_record(record.timestamp);
+        let mut heap = match context.get_map_state(HEAP_STATE).get(&window_key) {
+            Some(heap_record) => {
+                let mut heap_record = heap_record.clone();
+                // the timer of the window is registered by the former candidates
+                if !self.top_n.can_enter(&mut heap_record, &mut record) {
+                    return Box::new(std::iter::empty());
+                }
+                self.top_n.heap_from_record(&mut heap_record)
+            }
+            None => self.top_n.new_heap(),
+        };
+        self.top_n.push(&mut heap, record.clone());
+        context
+            .get_map_state(HEAP_STATE)
+            .put(window_key, heap.into_record());
+
+        match self.time_domain {
+            TimeDomain::EventTime => context
+                .timer_service()
+                .register_event_time_timer(record.timestamp),
+            TimeDomain::ProcessingTime => context
+                .timer_service()
+                .register_processing_time_timer(record.timestamp),
+        }
+
+        Box::new(std::iter::empty())
+    }
+
+    fn on_timer(
+        &mut self,
+        timestamp: u64,
+        _time_domain: TimeDomain,
+        context: &mut KeyedProcessContext,
+    ) -> Box<dyn Iterator<Item = Record>> {
+        let window_key = u64_record(timestamp);
+        match context.get_map_state(HEAP_STATE).remove(&window_key) {
+            Some(mut heap_record) => {
+                let heap = self.top_n.heap_from_record(&mut heap_record);
+                Box::new(heap.into_ranked_rows(timestamp).into_iter())
+            }
+            None => Box::new(std::iter::empty()),
+        }
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        Ok(())
+    }
+}
+
+impl Function for TopNFunction {
+    fn get_name(&self) -> &str {
+        "TopNFunction"
+    }
+
+    fn get_output_schema(&self, input_schema: &Schema) -> Schema {
+        self.top_n.ranked_schema(input_schema)
+    }
+
+    fn get_input_schema(&self) -> Schema {
+        self.top_n.get_input_schema()
+    }
+}
@@ -0,0 +1,47 @@
+use crate::api::element::Record;
+use crate::api::function::{CoProcessFunction, Context, Function};
+use crate::api::schema::Schema;
+
+/// Forward the records of all the union streams as they are
+pub(crate) struct UnionFunction {
+    /// the common schema of the union streams
+    schema: Schema,
+}
+
+impl UnionFunction {
+    pub fn new(schema: Schema) -> Self {
+        UnionFunction { schema }
+    }
+}
+
+impl CoProcessFunction for UnionFunction {
+    fn open(&mut self, _context: &Context) -> crate::api::Result<()> {
+        Ok(())
+    }
+
+    fn process_left(&self, record: Record) -> Box<dyn Iterator<Item = Record>> {
+        Box::new(vec![record].into_iter())
+    }
+
+    fn process_right(
+        &self,
+        _stream_seq: usize,
+        record: Record,
+    ) -> Box<dyn Iterator<Item = Record>> {
+        Box::new(vec![record].into_iter())
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        Ok(())
+    }
+}
+
+impl Function for UnionFunction {
+    fn get_name(&self) -> &str {
+        "UnionFunction"
+    }
+
+    fn get_output_schema(&self, _input_schema: &Schema) -> Schema {
+        self.schema.clone()
+    }
+}
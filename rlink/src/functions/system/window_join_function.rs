@@ -0,0 +1,279 @@
+use std::collections::HashSet;
+
+use crate::api::element::Record;
+use crate::api::function::{Context, Function, JoinFunction, KeyedProcessFunction};
+use crate::api::state::{KeyedProcessContext, TimeDomain};
+use crate::api::window::{TWindow, Window, WindowAssigner, WindowAssignerContext};
+
+const LEFT_STATE: &str = "window_join_left";
+const RIGHT_STATE: &str = "window_join_right";
+
+/// Buffer the records of both sides in the windows of the key,
+/// join them once the watermark passes the end of the window, then evict the window.
+pub(crate) struct WindowJoinFunction {
+    window_assigner: Box<dyn WindowAssigner>,
+    join_fn: Box<dyn JoinFunction>,
+}
+
+impl WindowJoinFunction {
+    pub fn new(window_assigner: Box<dyn WindowAssigner>, join_fn: Box<dyn JoinFunction>) -> Self {
+        WindowJoinFunction {
+            window_assigner,
+            join_fn,
+        }
+    }
+}
+
+impl KeyedProcessFunction for WindowJoinFunction {
+    fn open(&mut self, context: &Context) -> crate::api::Result<()> {
+        self.join_fn.open(context)
+    }
+
+    fn process_element(
+        &mut self,
+        mut record: Record,
+        context: &mut KeyedProcessContext,
+    ) -> Box<dyn Iterator<Item = Record>> {
+        let watermark = context.timer_service().get_current_watermark();
+        let windows: Vec<Window> = self
+            .window_assigner
+            .assign_record_windows(&mut record, WindowAssignerContext {})
+            .into_iter()
+            .filter(|window| window.max_timestamp() > watermark)
+            .collect();
+        if windows.is_empty() {
+            // the late record, all the windows have been joined
+            return Box::new(std::iter::empty());
+        }
+
+        for window in &windows {
+            context
+                .timer_service()
+                .register_event_time_timer(window.max_timestamp());
+        }
+        record.set_location_windows(windows);
+
+        let state_name = if context.get_stream_seq() == 0 {
+            LEFT_STATE
+        } else {
+            RIGHT_STATE
+        };
+        context.get_list_state(state_name).add(record);
+
+        Box::new(std::iter::empty())
+    }
+
+    fn on_timer(
+        &mut self,
+        timestamp: u64,
+        _time_domain: TimeDomain,
+        context: &mut KeyedProcessContext,
+    ) -> Box<dyn Iterator<Item = Record>> {
+        let mut lefts = context.get_list_state(LEFT_STATE).get().to_vec();
+        let mut rights = context.get_list_state(RIGHT_STATE).get().to_vec();
+
+        let fired_windows: HashSet<Window> = lefts
+            .iter()
+            .flat_map(|record| record.get_location_windows().iter())
+            .filter(|window| window.max_timestamp() <= timestamp)
+            .cloned()
+            .collect();
+
+        let mut joined_records = Vec::new();
+        for window in fired_windows {
+            for left in lefts.iter_mut() {
+                if !left.get_location_windows().contains(&window) {
+                    continue;
+                }
+
+                for right in rights.iter_mut() {
+                    if !right.get_location_windows().contains(&window) {
+                        continue;
+                    }
+
+                    let mut joined_record = self.join_fn.join(left, right);
+                    joined_record.timestamp = window.max_timestamp();
+                    joined_records.push(joined_record);
+                }
+            }
+        }
+
+        context
+            .get_list_state(LEFT_STATE)
+            .update(evict_windows(lefts, timestamp));
+        context
+            .get_list_state(RIGHT_STATE)
+            .update(evict_windows(rights, timestamp));
+
+        Box::new(joined_records.into_iter())
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        self.join_fn.close()
+    }
+}
+
+impl Function for WindowJoinFunction {
+    fn get_name(&self) -> &str {
+        "WindowJoinFunction"
+    }
+}
+
+/// remove the windows that `window.max_timestamp() <= timestamp`,
+/// the records without any window left are evicted
+fn evict_windows(records: Vec<Record>, timestamp: u64) -> Vec<Record> {
+    records
+        .into_iter()
+        .filter_map(|mut record| {
+            let windows: Vec<Window> = record
+                .get_location_windows()
+                .iter()
+                .filter(|window| window.max_timestamp() > timestamp)
+                .cloned()
+                .collect();
+            if windows.is_empty() {
+                None
+            } else {
+                record.set_location_windows(windows);
+                Some(record)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::time::Duration;
+
+    use crate::api::element::{types, Record};
+    use crate::api::function::{Context, Function, JoinFunction, KeyedProcessFunction};
+    use crate::api::state::{KeyedProcessContext, KeyedStateStore, TimeDomain, TimerService};
+    use crate::api::window::TumblingEventTimeWindows;
+    use crate::functions::system::window_join_function::{
+        WindowJoinFunction, LEFT_STATE, RIGHT_STATE,
+    };
+
+    fn record(value: i64, timestamp: u64) -> Record {
+        let mut record = Record::new();
+        record.get_writer(&[types::I64]).set_i64(value).unwrap();
+        record.timestamp = timestamp;
+        record
+    }
+
+    fn key() -> Record {
+        record(1, 0)
+    }
+
+    fn values(records: Vec<Record>) -> Vec<(i64, i64, u64)> {
+        records
+            .into_iter()
+            .map(|mut record| {
+                let timestamp = record.timestamp;
+                let mut reader = record.get_reader(&[types::I64, types::I64]);
+                (
+                    reader.get_i64(0).unwrap(),
+                    reader.get_i64(1).unwrap(),
+                    timestamp,
+                )
+            })
+            .collect()
+    }
+
+    fn process(
+        join_fn: &mut WindowJoinFunction,
+        state_store: &mut KeyedStateStore,
+        timer_service: &mut TimerService,
+        stream_seq: usize,
+        record: Record,
+    ) -> Vec<Record> {
+        let mut ctx =
+            KeyedProcessContext::new(record.timestamp, stream_seq, state_store, timer_service);
+        join_fn.process_element(record, &mut ctx).collect()
+    }
+
+    /// advance the watermark and fire the timers of the key
+    fn fire(
+        join_fn: &mut WindowJoinFunction,
+        state_store: &mut KeyedStateStore,
+        timer_service: &mut TimerService,
+        watermark: u64,
+    ) -> Vec<Record> {
+        timer_service.current_watermark = watermark;
+        let mut joined_records = Vec::new();
+        for (timer, _key) in timer_service.poll_event_time_timers(watermark) {
+            let mut ctx = KeyedProcessContext::new(timer, 0, state_store, timer_service);
+            joined_records.extend(join_fn.on_timer(timer, TimeDomain::EventTime, &mut ctx));
+        }
+        joined_records
+    }
+
+    fn state_len(
+        state_store: &mut KeyedStateStore,
+        timer_service: &mut TimerService,
+        state_name: &str,
+    ) -> usize {
+        let mut ctx = KeyedProcessContext::new(0, 0, state_store, timer_service);
+        ctx.get_list_state(state_name).get().len()
+    }
+
+    #[test]
+    pub fn window_join_test() {
+        let window_assigner = TumblingEventTimeWindows::new(Duration::from_millis(10), None);
+        let mut join_fn =
+            WindowJoinFunction::new(Box::new(window_assigner), Box::new(MyJoinFunction {}));
+        let mut state_store = KeyedStateStore::new();
+        let mut timer_service = TimerService::new();
+        timer_service.set_current_key(key());
+        let (f, s, t) = (&mut join_fn, &mut state_store, &mut timer_service);
+
+        // the records are buffered till the end of the window
+        assert!(process(f, s, t, 0, record(1, 1)).is_empty());
+        assert!(process(f, s, t, 1, record(2, 5)).is_empty());
+        assert!(process(f, s, t, 1, record(3, 12)).is_empty());
+        assert!(process(f, s, t, 0, record(4, 8)).is_empty());
+
+        // the window [0, 10) is joined and evicted, the window [10, 20) is kept
+        assert_eq!(values(fire(f, s, t, 9)), vec![(1, 2, 9), (4, 2, 9)]);
+        assert_eq!(state_len(s, t, LEFT_STATE), 0);
+        assert_eq!(state_len(s, t, RIGHT_STATE), 1);
+
+        // the late record of the joined window is dropped
+        assert!(process(f, s, t, 0, record(5, 3)).is_empty());
+        assert_eq!(state_len(s, t, LEFT_STATE), 0);
+
+        assert!(process(f, s, t, 0, record(6, 15)).is_empty());
+        assert_eq!(values(fire(f, s, t, 19)), vec![(6, 3, 19)]);
+        assert_eq!(state_len(s, t, LEFT_STATE), 0);
+        assert_eq!(state_len(s, t, RIGHT_STATE), 0);
+    }
+
+    /// join the values of both sides
+    pub struct MyJoinFunction {}
+
+    impl JoinFunction for MyJoinFunction {
+        fn open(&mut self, _context: &Context) -> crate::api::Result<()> {
+            Ok(())
+        }
+
+        fn join(&self, left: &mut Record, right: &mut Record) -> Record {
+            let left_value = left.get_reader(&[types::I64]).get_i64(0).unwrap();
+            let right_value = right.get_reader(&[types::I64]).get_i64(0).unwrap();
+
+            let mut record = Record::new();
+            let mut writer = record.get_writer(&[types::I64, types::I64]);
+            writer.set_i64(left_value).unwrap();
+            writer.set_i64(right_value).unwrap();
+            record
+        }
+
+        fn close(&mut self) -> crate::api::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Function for MyJoinFunction {
+        fn get_name(&self) -> &str {
+            "MyJoinFunction"
+        }
+    }
+}
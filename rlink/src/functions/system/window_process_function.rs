@@ -0,0 +1,283 @@
+use std::collections::HashSet;
+
+use crate::api::element::Record;
+use crate::api::function::{
+    Context, Function, KeyedProcessFunction, ProcessWindowFunction, ReduceFunction,
+};
+use crate::api::output_tag::OutputTag;
+use crate::api::schema::Schema;
+use crate::api::state::{KeyedProcessContext, TimeDomain};
+use crate::api::window::{TWindow, Window};
+
+const WINDOW_STATE: &str = "window_process";
+
+/// Buffer the records of the key in the windows assigned by the upstream `WindowAssigner`,
+/// process them by the `ProcessWindowFunction` once the window ends.
+///
+/// The event time windows are kept for `allowed_lateness` after the end of the window,
+/// the records arriving in the period re-fire the window.
+/// If a `ReduceFunction` is given, the records are pre-aggregated into one record per window.
+pub(crate) struct WindowProcessFunction {
+    time_domain: TimeDomain,
+    allowed_lateness: u64,
+    late_data_output_tag: Option<OutputTag>,
+    reduce_fn: Option<Box<dyn ReduceFunction>>,
+    process_fn: Box<dyn ProcessWindowFunction>,
+}
+
+impl WindowProcessFunction {
+    pub fn new(
+        time_domain: TimeDomain,
+        allowed_lateness: u64,
+        late_data_output_tag: Option<OutputTag>,
+        reduce_fn: Option<Box<dyn ReduceFunction>>,
+        process_fn: Box<dyn ProcessWindowFunction>,
+    ) -> Self {
+        WindowProcessFunction {
+            time_domain,
+            allowed_lateness,
+            late_data_output_tag,
+            reduce_fn,
+            process_fn,
+        }
+    }
+
+    fn current_time(&self, context: &mut KeyedProcessContext) -> u64 {
+        match self.time_domain {
+            TimeDomain::EventTime => context.timer_service().get_current_watermark(),
+            TimeDomain::ProcessingTime => context.timer_service().get_current_processing_time(),
+        }
+    }
+
+    fn register_timer(&self, context: &mut KeyedProcessContext, time: u64) {
+        match self.time_domain {
+            TimeDomain::EventTime => context.timer_service().register_event_time_timer(time),
+            TimeDomain::ProcessingTime => {
+                context.timer_service().register_processing_time_timer(time)
+            }
+        }
+    }
+
+    /// the late records are only checked with the event time,
+    /// the processing time windows are assigned by the current time and never late
+    fn is_late(&self, window: &Window, current_time: u64) -> bool {
+        self.time_domain == TimeDomain::EventTime
+            && window.max_timestamp() + self.allowed_lateness <= current_time
+    }
+
+    /// add the record into the window, or reduce it into the pre-aggregated record of the window
+    fn add(&self, mut record: Record, windows: Vec<Window>, context: &mut KeyedProcessContext) {
+        let reduce_fn = match &self.reduce_fn {
+            Some(reduce_fn) => reduce_fn,
+            None => {
+                record.set_location_windows(windows);
+                context.get_list_state(WINDOW_STATE).add(record);
+                return;
+            }
+        };
+
+        let mut elements = context.get_list_state(WINDOW_STATE).get().to_vec();
+        for window in windows {
+            let position = elements
+                .iter()
+                .position(|element| element.get_location_windows()[0] == window);
+            let mut reduced_record = match position {
+                Some(position) => reduce_fn.reduce(Some(&mut elements[position]), &mut record),
+                None => reduce_fn.reduce(None, &mut record),
+            };
+            reduced_record.set_location_windows(vec![window]);
+
+            match position {
+                Some(position) => elements[position] = reduced_record,
+                None => elements.push(reduced_record),
+            }
+        }
+        context.get_list_state(WINDOW_STATE).update(elements);
+    }
+
+    /// process the records of the key in the `window`
+    fn fire(&mut self, window: &Window, context: &mut KeyedProcessContext) -> Vec<Record> {
+        let elements: Vec<Record> = context
+            .get_list_state(WINDOW_STATE)
+            .get()
+            .iter()
+            .filter(|element| element.get_location_windows().contains(window))
+            .cloned()
+            .collect();
+        if elements.is_empty() {
+            return Vec::new();
+        }
+
+        let time_window = match window {
+            Window::TimeWindow(time_window) => time_window,
+        };
+        self.process_fn
+            .process(
+                context.get_current_key(),
+                time_window,
+                Box::new(elements.into_iter()),
+            )
+            .map(|mut record| {
+                record.timestamp = window.max_timestamp();
+                record
+            })
+            .collect()
+    }
+}
+
+impl KeyedProcessFunction for WindowProcessFunction {
+    fn open(&mut self, context: &Context) -> crate::api::Result<()> {
+        if let Some(reduce_fn) = &mut self.reduce_fn {
+            reduce_fn.open(context)?;
+        }
+        self.process_fn.open(context)
+    }
+
+    fn process_element(
+        &mut self,
+        mut record: Record,
+        context: &mut KeyedProcessContext,
+    ) -> Box<dyn Iterator<Item = Record>> {
+        let current_time = self.current_time(context);
+        let windows: Vec<Window> = record
+            .get_location_windows()
+            .iter()
+            .filter(|window| !self.is_late(window, current_time))
+            .cloned()
+            .collect();
+        if windows.is_empty() {
+            return match &self.late_data_output_tag {
+                Some(output_tag) => {
+                    record.set_output_tag(output_tag.clone());
+                    Box::new(vec![record].into_iter())
+                }
+                None => Box::new(std::iter::empty()),
+            };
+        }
+
+        // the windows have been fired but still in the allowed lateness
+        let fired_windows: Vec<Window> = windows
+            .iter()
+            .filter(|window| window.max_timestamp() <= current_time)
+            .cloned()
+            .collect();
+        for window in &windows {
+            if window.max_timestamp() > current_time {
+                self.register_timer(context, window.max_timestamp());
+            }
+            if self.allowed_lateness > 0 {
+                self.register_timer(context, window.max_timestamp() + self.allowed_lateness);
+            }
+        }
+        self.add(record, windows, context);
+
+        let mut records = Vec::new();
+        for window in &fired_windows {
+            records.extend(self.fire(window, context));
+        }
+        Box::new(records.into_iter())
+    }
+
+    fn on_timer(
+        &mut self,
+        timestamp: u64,
+        _time_domain: TimeDomain,
+        context: &mut KeyedProcessContext,
+    ) -> Box<dyn Iterator<Item = Record>> {
+        let fired_windows: HashSet<Window> = context
+            .get_list_state(WINDOW_STATE)
+            .get()
+            .iter()
+            .flat_map(|element| element.get_location_windows().iter())
+            .filter(|window| window.max_timestamp() == timestamp)
+            .cloned()
+            .collect();
+
+        let mut records = Vec::new();
+        for window in &fired_windows {
+            records.extend(self.fire(window, context));
+        }
+
+        let elements = context.get_list_state(WINDOW_STATE).get().to_vec();
+        context.get_list_state(WINDOW_STATE).update(evict_windows(
+            elements,
+            timestamp,
+            self.allowed_lateness,
+        ));
+
+        Box::new(records.into_iter())
+    }
+
+    fn close(&mut self) -> crate::api::Result<()> {
+        if let Some(reduce_fn) = &mut self.reduce_fn {
+            reduce_fn.close()?;
+        }
+        self.process_fn.close()
+    }
+}
+
+impl Function for WindowProcessFunction {
+    fn get_name(&self) -> &str {
+        self.process_fn.get_name()
+    }
+
+    fn get_output_schema(&self, input_schema: &Schema) -> Schema {
+        self.process_fn.get_output_schema(input_schema)
+    }
+
+    /// the records are consumed by the pre-aggregation if there is one
+    fn get_input_schema(&self) -> Schema {
+        match &self.reduce_fn {
+            Some(reduce_fn) => reduce_fn.get_input_schema(),
+            None => self.process_fn.get_input_schema(),
+        }
+    }
+}
+
+/// remove the windows that `window.max_timestamp() + allowed_lateness <= timestamp`,
+/// the records without any window left are evicted
+fn evict_windows(records: Vec<Record>, timestamp: u64, allowed_lateness: u64) -> Vec<Record> {
+    records
+        .into_iter()
+        .filter_map(|mut record| {
+            let windows: Vec<Window> = record
+                .get_location_windows()
+                .iter()
+                .filter(|window| window.max_timestamp() + allowed_lateness > timestamp)
+                .cloned()
+                .collect();
+            if windows.is_empty() {
+                None
+            } else {
+                record.set_location_windows(windows);
+                Some(record)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::element::Record;
+    use crate::api::window::{TimeWindow, Window};
+    use crate::functions::system::window_process_function::evict_windows;
+
+    #[test]
+    pub fn evict_windows_test() {
+        let mut record = Record::new();
+        record.set_location_windows(vec![
+            Window::TimeWindow(TimeWindow::new(0, 10)),
+            Window::TimeWindow(TimeWindow::new(5, 15)),
+        ]);
+
+        // the window [0, 10) is kept in the allowed lateness
+        let records = evict_windows(vec![record.clone()], 10, 5);
+        assert_eq!(records[0].get_location_windows().len(), 2);
+
+        let records = evict_windows(vec![record.clone()], 15, 5);
+        assert_eq!(records[0].get_location_windows().len(), 1);
+
+        let records = evict_windows(vec![record], 20, 5);
+        assert!(records.is_empty());
+    }
+}
@@ -223,7 +223,14 @@ impl Client {
                                         _ => {}
                                     }
 
+                                    let end_of_input = element.is_end_of_input();
                                     Client::send_to_channel(element, &self.sender, &counter).await;
+
+                                    // nothing is published by the remote task after the end
+                                    if end_of_input {
+                                        info!("Pull finish, channel_key={:?}", self.channel_key);
+                                        return Ok(());
+                                    }
                                 }
                                 ResponseCode::BatchFinish => {
                                     // info!("batch finish");
@@ -1,7 +1,9 @@
 use std::borrow::BorrowMut;
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::thread::JoinHandle;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::api::env::{StreamApp, StreamExecutionEnvironment};
 use crate::pub_sub::network;
@@ -13,6 +15,9 @@ use crate::runtime::{worker, ApplicationDescriptor, TaskManagerStatus, WorkerMan
 use crate::storage::metadata::MetadataLoader;
 use crate::utils;
 
+/// the worker exits if the coordinator is unreachable for the duration after the end of input
+const COORDINATOR_LOST_TIMEOUT: Duration = Duration::from_secs(60);
+
 pub(crate) fn run<S>(context: Context, stream_env: StreamExecutionEnvironment, stream_app: S)
 where
     S: StreamApp + 'static,
@@ -24,7 +29,13 @@ where
     let server_addr = bootstrap_publish_serve(context.bind_ip.to_string());
     info!("bootstrap publish server, listen: {}", server_addr);
 
-    bootstrap_timer_task(&application_descriptor, &context, server_addr);
+    let finished = Arc::new(AtomicBool::new(false));
+    bootstrap_timer_task(
+        &application_descriptor,
+        &context,
+        server_addr,
+        finished.clone(),
+    );
     info!("bootstrap timer task");
 
     let window_timer = start_window_timer();
@@ -42,7 +53,7 @@ where
     let join_handles = run_tasks(
         &application_descriptor,
         &context,
-        metadata_loader,
+        metadata_loader.clone(),
         window_timer,
         stream_env,
         stream_app,
@@ -52,6 +63,26 @@ where
     join_handles.into_iter().for_each(|join_handle| {
         join_handle.join().unwrap();
     });
+    info!("all task has reached the end of input");
+
+    // keep serving the published elements until the application is finished
+    finished.store(true, Ordering::Relaxed);
+    if waiting_application_finished(metadata_loader.borrow_mut()) {
+        // the coordinator waits for the exit of all the workers before clearing the metadata
+        status_heartbeat(
+            application_descriptor
+                .coordinator_manager
+                .coordinator_address
+                .as_str(),
+            context.task_manager_id.as_str(),
+            server_addr.to_string().as_str(),
+            context.metric_addr.as_str(),
+            "exited",
+        );
+        info!("report the exit to the coordinator");
+    } else {
+        warn!("the coordinator is unreachable, exit without reporting");
+    }
     info!("work end");
 }
 
@@ -93,6 +124,7 @@ fn bootstrap_timer_task(
     application_descriptor: &ApplicationDescriptor,
     context: &Context,
     bind_addr: SocketAddr,
+    finished: Arc<AtomicBool>,
 ) {
     let coordinator_address = application_descriptor
         .coordinator_manager
@@ -104,6 +136,7 @@ fn bootstrap_timer_task(
         context.task_manager_id.as_str(),
         bind_addr.to_string().as_str(),
         context.metric_addr.as_str(),
+        "ok",
     );
 
     // heat beat timer
@@ -112,6 +145,7 @@ fn bootstrap_timer_task(
         context.task_manager_id.as_str(),
         bind_addr.to_string().as_str(),
         context.metric_addr.as_str(),
+        finished,
     );
 
     // report checkpoint timer
@@ -130,6 +164,34 @@ fn waiting_all_task_manager_fine(metadata_loader: &mut MetadataLoader) {
     }
 }
 
+/// waiting for the coordinator to mark the application `Finished`, the other workers may
+/// still consume the elements published by this worker.
+/// return false if the coordinator is unreachable for `COORDINATOR_LOST_TIMEOUT`
+fn waiting_application_finished(metadata_loader: &mut MetadataLoader) -> bool {
+    let mut latest_reached = Instant::now();
+    loop {
+        match metadata_loader.try_get_application_descriptor() {
+            Ok(job_descriptor) => {
+                if job_descriptor.coordinator_manager.coordinator_status
+                    == TaskManagerStatus::Finished
+                {
+                    return true;
+                }
+                latest_reached = Instant::now();
+            }
+            Err(e) => {
+                if latest_reached.elapsed() > COORDINATOR_LOST_TIMEOUT {
+                    error!("the coordinator is lost. {}", e);
+                    return false;
+                }
+                warn!("get metadata(`JobDescriptor`) error. {}", e);
+            }
+        }
+
+        std::thread::sleep(Duration::from_secs(1));
+    }
+}
+
 fn run_tasks<S>(
     application_descriptor: &ApplicationDescriptor,
     context: &Context,
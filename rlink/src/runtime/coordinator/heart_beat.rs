@@ -1,10 +1,10 @@
 use std::ops::Deref;
 use std::sync::RwLock;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::api::cluster::MetadataStorageType;
-use crate::runtime::ApplicationDescriptor;
-use crate::storage::metadata::{loop_read_job_descriptor, MetadataStorage};
+use crate::runtime::{ApplicationDescriptor, TaskManagerStatus};
+use crate::storage::metadata::{loop_read_job_descriptor, loop_update_job_status, MetadataStorage};
 use crate::utils;
 
 lazy_static! {
@@ -23,7 +23,18 @@ pub(crate) fn get_global_job_descriptor() -> Option<ApplicationDescriptor> {
     j.deref().clone()
 }
 
-pub(crate) fn start_heart_beat_timer(metadata_storage_mode: MetadataStorageType) {
+/// The reason that the heartbeat check is interrupted
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum HeartbeatResult {
+    /// some worker lost its heartbeat
+    Timeout,
+    /// all the workers have reached the end of the input
+    Finished,
+}
+
+pub(crate) fn start_heart_beat_timer(
+    metadata_storage_mode: MetadataStorageType,
+) -> HeartbeatResult {
     let metadata_storage = MetadataStorage::new(&metadata_storage_mode);
     loop {
         std::thread::sleep(Duration::from_secs(5));
@@ -32,35 +43,213 @@ pub(crate) fn start_heart_beat_timer(metadata_storage_mode: MetadataStorageType)
         update_global_job_descriptor(job_descriptor.clone());
 
         let current_timestamp = utils::date_time::current_timestamp().as_millis() as u64;
+        if let Some(heartbeat_result) = check_heartbeat(&job_descriptor, current_timestamp) {
+            return heartbeat_result;
+        }
 
-        for task_manager_descriptor in &job_descriptor.worker_managers {
-            if current_timestamp < task_manager_descriptor.latest_heart_beat_ts {
-                continue;
-            }
+        debug!(
+            "all({}) task is final",
+            job_descriptor.worker_managers.len()
+        );
+    }
+}
 
-            let dur = Duration::from_millis(
-                current_timestamp - task_manager_descriptor.latest_heart_beat_ts,
-            );
+/// check the heartbeats of the workers, `None` if the application keeps running
+fn check_heartbeat(
+    job_descriptor: &ApplicationDescriptor,
+    current_timestamp: u64,
+) -> Option<HeartbeatResult> {
+    for task_manager_descriptor in &job_descriptor.worker_managers {
+        if current_timestamp < task_manager_descriptor.latest_heart_beat_ts {
+            continue;
+        }
 
-            debug!(
-                "heartbeat delay {}ms from TaskManager {}",
-                dur.as_millis(),
+        let dur =
+            Duration::from_millis(current_timestamp - task_manager_descriptor.latest_heart_beat_ts);
+
+        debug!(
+            "heartbeat delay {}ms from TaskManager {}",
+            dur.as_millis(),
+            task_manager_descriptor.task_manager_address
+        );
+
+        if dur.as_secs() > 50 {
+            error!(
+                "heart beat lag {}s from TaskManager {}, and break heartbeat",
+                dur.as_secs(),
                 task_manager_descriptor.task_manager_address
             );
-
-            if dur.as_secs() > 50 {
-                error!(
-                    "heart beat lag {}s from TaskManager {}, and break heartbeat",
-                    dur.as_secs(),
-                    task_manager_descriptor.task_manager_address
-                );
-                return;
-            }
+            return Some(HeartbeatResult::Timeout);
         }
+    }
 
-        debug!(
-            "all({}) task is final",
+    let finished = job_descriptor
+        .worker_managers
+        .iter()
+        .all(|x| x.task_status == TaskManagerStatus::Finished);
+    if finished {
+        info!(
+            "all({}) task is finished",
             job_descriptor.worker_managers.len()
         );
+        return Some(HeartbeatResult::Finished);
+    }
+
+    None
+}
+
+/// mark the application `Finished` and waiting for all the workers to report the exit,
+/// the workers keep publishing the elements until they see the `Finished` status.
+/// return false if some workers have not exited in the `timeout`
+pub(crate) fn waiting_worker_exit(
+    metadata_storage: &mut MetadataStorage,
+    timeout: Duration,
+) -> bool {
+    loop_update_job_status(metadata_storage, TaskManagerStatus::Finished);
+    info!("Job update state to `Finished`, waiting for the workers exit");
+
+    let begin = Instant::now();
+    loop {
+        let job_descriptor = loop_read_job_descriptor(metadata_storage);
+        let running_workers: Vec<&str> = job_descriptor
+            .worker_managers
+            .iter()
+            .filter(|x| x.task_status != TaskManagerStatus::Exited)
+            .map(|x| x.task_manager_id.as_str())
+            .collect();
+        if running_workers.is_empty() {
+            return true;
+        }
+
+        if begin.elapsed() > timeout {
+            warn!(
+                "the workers {:?} have not exited in {}s",
+                running_workers,
+                timeout.as_secs()
+            );
+            return false;
+        }
+
+        std::thread::sleep(Duration::from_millis(500));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::api::cluster::MetadataStorageType;
+    use crate::api::properties::Properties;
+    use crate::runtime::coordinator::heart_beat::{
+        check_heartbeat, waiting_worker_exit, HeartbeatResult,
+    };
+    use crate::runtime::{
+        ApplicationDescriptor, CoordinatorManagerDescriptor, TaskManagerStatus,
+        WorkerManagerDescriptor,
+    };
+    use crate::storage::metadata::{
+        loop_read_job_descriptor, loop_save_job_descriptor, MetadataStorage, TMetadataStorage,
+    };
+
+    fn worker_manager(task_manager_id: &str) -> WorkerManagerDescriptor {
+        WorkerManagerDescriptor {
+            task_status: TaskManagerStatus::Finished,
+            latest_heart_beat_ts: 0,
+            task_manager_id: task_manager_id.to_string(),
+            task_manager_address: "".to_string(),
+            metrics_address: "".to_string(),
+            cpu_cores: 1,
+            physical_memory: 1024,
+            task_descriptors: vec![],
+        }
+    }
+
+    fn application_descriptor(
+        worker_managers: Vec<WorkerManagerDescriptor>,
+    ) -> ApplicationDescriptor {
+        ApplicationDescriptor {
+            coordinator_manager: CoordinatorManagerDescriptor {
+                application_id: "application_id".to_string(),
+                application_name: "application_name".to_string(),
+                application_properties: Properties::new(),
+                coordinator_address: "".to_string(),
+                coordinator_status: TaskManagerStatus::Registered,
+            },
+            worker_managers,
+        }
+    }
+
+    /// the workers report the exit once the application is `Finished`
+    fn spawn_workers(task_manager_ids: Vec<&'static str>) {
+        std::thread::spawn(move || {
+            let metadata_storage = MetadataStorage::new(&MetadataStorageType::Memory);
+            loop {
+                let job_descriptor = loop_read_job_descriptor(&metadata_storage);
+                if job_descriptor.coordinator_manager.coordinator_status
+                    == TaskManagerStatus::Finished
+                {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+
+            for task_manager_id in task_manager_ids {
+                metadata_storage
+                    .update_task_status(task_manager_id, "", TaskManagerStatus::Exited, "")
+                    .unwrap();
+            }
+        });
+    }
+
+    #[test]
+    pub fn waiting_worker_exit_test() {
+        let mut metadata_storage = MetadataStorage::new(&MetadataStorageType::Memory);
+        let application_descriptor =
+            application_descriptor(vec![worker_manager("worker_0"), worker_manager("worker_1")]);
+
+        // all the workers exit
+        loop_save_job_descriptor(&mut metadata_storage, application_descriptor.clone());
+        spawn_workers(vec!["worker_0", "worker_1"]);
+        assert!(waiting_worker_exit(
+            &mut metadata_storage,
+            Duration::from_secs(10)
+        ));
+
+        // the `worker_1` doesn't exit
+        loop_save_job_descriptor(&mut metadata_storage, application_descriptor);
+        spawn_workers(vec!["worker_0"]);
+        assert!(!waiting_worker_exit(
+            &mut metadata_storage,
+            Duration::from_secs(1)
+        ));
+    }
+
+    #[test]
+    pub fn check_heartbeat_test() {
+        let current_timestamp = 100_000;
+        let mut worker_0 = worker_manager("worker_0");
+        worker_0.latest_heart_beat_ts = current_timestamp - 1000;
+        let mut worker_1 = worker_0.clone();
+        worker_1.task_manager_id = "worker_1".to_string();
+        worker_1.task_status = TaskManagerStatus::Registered;
+
+        // the `worker_1` has not reached the end of its input
+        let mut descriptor = application_descriptor(vec![worker_0.clone(), worker_1.clone()]);
+        assert_eq!(check_heartbeat(&descriptor, current_timestamp), None);
+
+        // the application is finished once all the workers reach the end of the input
+        descriptor.worker_managers[1].task_status = TaskManagerStatus::Finished;
+        assert_eq!(
+            check_heartbeat(&descriptor, current_timestamp),
+            Some(HeartbeatResult::Finished)
+        );
+
+        // the lost heartbeat interrupts the running application
+        worker_1.latest_heart_beat_ts = current_timestamp - 60_000;
+        let descriptor = application_descriptor(vec![worker_0, worker_1]);
+        assert_eq!(
+            check_heartbeat(&descriptor, current_timestamp),
+            Some(HeartbeatResult::Timeout)
+        );
     }
 }
@@ -11,6 +11,7 @@ use crate::dag::DagManager;
 use crate::deployment::TResourceManager;
 use crate::runtime::context::Context;
 use crate::runtime::coordinator::checkpoint_manager::CheckpointManager;
+use crate::runtime::coordinator::heart_beat::HeartbeatResult;
 use crate::runtime::coordinator::server::web_launch;
 use crate::runtime::coordinator::task_distribution::build_job_descriptor;
 use crate::runtime::{ApplicationDescriptor, TaskManagerStatus};
@@ -26,6 +27,9 @@ pub mod heart_beat;
 pub mod server;
 pub mod task_distribution;
 
+/// the workers not exited in the duration after the application is finished are stopped
+const WORKER_EXIT_TIMEOUT: Duration = Duration::from_secs(60);
+
 pub(crate) struct CoordinatorTask<S, R>
 where
     S: StreamApp + 'static,
@@ -115,9 +119,24 @@ where
             self.waiting_worker_status_fine();
             info!("all worker status is fine");
 
-            // heartbeat check. blocking util heartbeat timeout
-            heart_beat::start_heart_beat_timer(self.metadata_storage_mode.clone());
-            info!("heartbeat has interrupted");
+            // heartbeat check. blocking util heartbeat timeout or all the inputs end
+            let heartbeat_result =
+                heart_beat::start_heart_beat_timer(self.metadata_storage_mode.clone());
+            info!("heartbeat has interrupted. {:?}", heartbeat_result);
+
+            if heartbeat_result == HeartbeatResult::Finished {
+                // all the inputs end, the workers exit by themselves once they see the `Finished`
+                if self.waiting_worker_exit() {
+                    info!("all workers exit");
+                } else {
+                    self.stop_all_worker_tasks(worker_task_ids);
+                    info!("stop the workers that have not exited");
+                }
+
+                self.clear_metadata();
+                info!("application finished");
+                break;
+            }
 
             // heartbeat timeout and stop all worker's tasks
             self.stop_all_worker_tasks(worker_task_ids);
@@ -201,9 +220,12 @@ where
                         .find(|ck| ck.task_id.task_number == task_descriptor.task_id.task_number)
                         .unwrap();
                     task_descriptor.checkpoint_id = ck.checkpoint_id;
-                    task_descriptor.checkpoint_handle = Some(CheckpointHandle {
-                        handle: ck.handle.handle.clone(),
-                    });
+                    task_descriptor.checkpoint_handles.push((
+                        *operator_id,
+                        CheckpointHandle {
+                            handle: ck.handle.handle.clone(),
+                        },
+                    ));
                     info!("task_descriptor {:?} checkpoint loaded", task_descriptor);
                 }
             }
@@ -270,6 +292,12 @@ where
         }
     }
 
+    /// mark the application `Finished`, the workers exit by themselves once they see it
+    fn waiting_worker_exit(&self) -> bool {
+        let mut metadata_storage = MetadataStorage::new(&self.metadata_storage_mode);
+        heart_beat::waiting_worker_exit(metadata_storage.borrow_mut(), WORKER_EXIT_TIMEOUT)
+    }
+
     fn stop_all_worker_tasks(&self, worker_task_ids: Vec<TaskResourceInfo>) {
         // loop stop all workers util all are success
         loop {
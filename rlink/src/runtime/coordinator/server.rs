@@ -199,7 +199,7 @@ pub(crate) struct HeartbeatModel {
     pub task_manager_id: String,
     pub task_manager_address: String,
     pub metrics_address: String,
-    // ok, panic
+    // ok, panic, finished, exited
     pub status: String,
 }
 
@@ -209,15 +209,36 @@ pub(crate) async fn heartbeat(
 ) -> Result<HttpResponse, Error> {
     let metadata_storage = MetadataStorage::new(&context.metadata_mode);
 
-    if !heartbeat_model.status.eq("ok") {
-        error!("heart beat status: {}", heartbeat_model.status.as_str());
+    // the heartbeat sent before the exit may arrive after the exit report
+    let exited = metadata_storage
+        .read_job_descriptor()
+        .map(|job_descriptor| {
+            job_descriptor.worker_managers.iter().any(|x| {
+                x.task_manager_id.eq(&heartbeat_model.task_manager_id)
+                    && x.task_status == TaskManagerStatus::Exited
+            })
+        })
+        .unwrap_or(false);
+    if exited {
+        let response = StdResponse::new(ResponseCode::OK, Some(true));
+        return Ok(HttpResponse::Ok().json(response));
     }
 
+    let task_manager_status = match heartbeat_model.status.as_str() {
+        "ok" => TaskManagerStatus::Registered,
+        "finished" => TaskManagerStatus::Finished,
+        "exited" => TaskManagerStatus::Exited,
+        status => {
+            error!("heart beat status: {}", status);
+            TaskManagerStatus::Registered
+        }
+    };
+
     metadata_storage
         .update_task_status(
             heartbeat_model.task_manager_id.as_str(),
             heartbeat_model.task_manager_address.as_str(),
-            TaskManagerStatus::Registered,
+            task_manager_status,
             heartbeat_model.metrics_address.as_str(),
         )
         .unwrap();
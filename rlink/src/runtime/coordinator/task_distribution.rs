@@ -32,7 +32,7 @@ pub(crate) fn build_job_descriptor(
                 operator_ids,
                 input_split: task_instance.input_split.clone(),
                 checkpoint_id: CheckpointId::default(),
-                checkpoint_handle: None,
+                checkpoint_handles: Vec::new(),
             };
             task_descriptors.push(task_descriptor);
         }
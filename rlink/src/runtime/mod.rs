@@ -88,6 +88,11 @@ pub enum TaskManagerStatus {
     Registered = 1,
     /// TaskManager lost and try to recreate a new TaskManager
     Migration = 2,
+    /// all the tasks of the TaskManager have reached the end of the input,
+    /// the application is finished when all the TaskManagers are finished
+    Finished = 3,
+    /// the TaskManager has exited after the application is finished
+    Exited = 4,
 }
 
 // todo rename to TaskDescriptor
@@ -97,7 +102,17 @@ pub struct TaskDescriptor {
     pub operator_ids: Vec<OperatorId>,
     pub input_split: InputSplit,
     pub checkpoint_id: CheckpointId,
-    pub checkpoint_handle: Option<CheckpointHandle>,
+    /// the checkpoint handles of the operators in the task
+    pub checkpoint_handles: Vec<(OperatorId, CheckpointHandle)>,
+}
+
+impl TaskDescriptor {
+    pub fn get_checkpoint_handle(&self, operator_id: OperatorId) -> Option<CheckpointHandle> {
+        self.checkpoint_handles
+            .iter()
+            .find(|(id, _handle)| *id == operator_id)
+            .map(|(_id, handle)| handle.clone())
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -1,3 +1,5 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
 use crate::api::cluster::StdResponse;
@@ -11,6 +13,7 @@ pub(crate) fn status_heartbeat(
     task_manager_id: &str,
     task_manager_address: &str,
     metrics_address: &str,
+    status: &str,
 ) {
     let coordinator_address = coordinator_address.to_string();
     let task_manager_id = task_manager_id.to_string();
@@ -22,6 +25,7 @@ pub(crate) fn status_heartbeat(
         task_manager_id.as_str(),
         task_manager_address.as_str(),
         metrics_address.as_str(),
+        status,
     ));
 }
 
@@ -30,13 +34,14 @@ pub(crate) async fn status_heartbeat_async(
     task_manager_id: &str,
     task_manager_address: &str,
     metrics_address: &str,
+    status: &str,
 ) {
     let url = format!("{}/heartbeat", coordinator_address);
 
     let status = if panic::is_panic() {
         "panic".to_string()
     } else {
-        "ok".to_string()
+        status.to_string()
     };
     let model = HeartbeatModel {
         task_manager_id: task_manager_id.to_string(),
@@ -63,11 +68,13 @@ pub(crate) async fn status_heartbeat_async(
     };
 }
 
+/// report the status every 10s, the status is `finished` once `finished` is set
 pub(crate) fn start_heart_beat_timer(
     coordinator: &str,
     task_manager_id: &str,
     task_manager_address: &str,
     metrics_address: &str,
+    finished: Arc<AtomicBool>,
 ) {
     let coordinator = coordinator.to_string();
     let task_manager_id = task_manager_id.to_string();
@@ -80,6 +87,7 @@ pub(crate) fn start_heart_beat_timer(
             task_manager_id.as_str(),
             task_manager_address.as_str(),
             metrics_address.as_str(),
+            finished,
         ));
     });
 }
@@ -89,6 +97,7 @@ pub(crate) async fn start_heart_beat_timer_async(
     task_manager_id: &str,
     task_manager_address: &str,
     metrics_address: &str,
+    finished: Arc<AtomicBool>,
 ) {
     info!("heartbeat loop starting...");
     loop {
@@ -99,6 +108,11 @@ pub(crate) async fn start_heart_beat_timer_async(
             task_manager_id,
             task_manager_address,
             metrics_address,
+            if finished.load(Ordering::Relaxed) {
+                "finished"
+            } else {
+                "ok"
+            },
         )
         .await;
     }
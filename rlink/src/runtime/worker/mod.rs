@@ -8,12 +8,15 @@ use crate::api::env::{StreamApp, StreamExecutionEnvironment};
 use crate::api::function::KeySelectorFunction;
 use crate::api::operator::{DefaultStreamOperator, StreamOperator};
 use crate::api::runtime::{JobId, OperatorId};
+use crate::api::window::WindowAssigner;
+use crate::dag::job_graph::JobNode;
 use crate::dag::{DagManager, OperatorType};
 use crate::runtime::context::Context;
 use crate::runtime::timer::WindowTimer;
 use crate::runtime::worker::runnable::co_process_runnable::CoProcessRunnable;
 use crate::runtime::worker::runnable::{
-    FilterRunnable, FlatMapRunnable, KeyByRunnable, ReduceRunnable, Runnable, RunnableContext,
+    AsyncMapRunnable, BroadcastProcessRunnable, FilterRunnable, FlatMapRunnable, KeyByRunnable,
+    KeyedProcessRunnable, PartitionRunnable, ReduceRunnable, Runnable, RunnableContext,
     SinkRunnable, SourceRunnable, WatermarkAssignerRunnable, WindowAssignerRunnable,
 };
 use crate::runtime::{ApplicationDescriptor, TaskDescriptor};
@@ -138,6 +141,23 @@ where
             .get_job_node(&self.task_descriptor.task_id)
             .expect(format!("Job={:?} is not found", &self.task_descriptor).as_str());
 
+        // processing time windows are fired by the `WindowTimer` instead of watermarks
+        let (processing_time_window, merging_window, mut default_trigger) =
+            match self.get_window_assigner(&job_node, &operators) {
+                Some(window_assigner) => (
+                    !window_assigner.is_event_time(),
+                    window_assigner.is_merging(),
+                    Some(window_assigner.get_default_trigger()),
+                ),
+                None => (false, false, None),
+            };
+        // the processing time timers of the `KeyedProcessFunction` are fired by the `WindowTimer` too
+        let processing_time_timer = processing_time_window
+            || job_node
+                .stream_nodes
+                .iter()
+                .any(|stream_node| stream_node.operator_type == OperatorType::KeyedProcess);
+
         let mut invoke_operators = Vec::new();
         for index in 0..job_node.stream_nodes.len() {
             let operator_id = job_node.stream_nodes[index].id;
@@ -148,6 +168,7 @@ where
                         operator_id,
                         self.task_descriptor.input_split.clone(),
                         stream_operator,
+                        processing_time_timer,
                         None,
                     );
                     let op: Box<dyn Runnable> = Box::new(op);
@@ -163,23 +184,74 @@ where
                     let op: Box<dyn Runnable> = Box::new(op);
                     op
                 }
+                StreamOperator::StreamAsyncMap(stream_operator, async_options) => {
+                    let op =
+                        AsyncMapRunnable::new(operator_id, stream_operator, async_options, None);
+                    let op: Box<dyn Runnable> = Box::new(op);
+                    op
+                }
                 StreamOperator::StreamCoProcess(stream_operator) => {
                     let op = CoProcessRunnable::new(operator_id, stream_operator, None);
                     let op: Box<dyn Runnable> = Box::new(op);
                     op
                 }
+                StreamOperator::StreamBroadcastProcess(stream_operator, descriptors) => {
+                    let op = BroadcastProcessRunnable::new(
+                        operator_id,
+                        stream_operator,
+                        descriptors,
+                        None,
+                    );
+                    let op: Box<dyn Runnable> = Box::new(op);
+                    op
+                }
                 StreamOperator::StreamKeyBy(stream_operator) => {
                     let op = KeyByRunnable::new(operator_id, stream_operator, None);
                     let op: Box<dyn Runnable> = Box::new(op);
                     op
                 }
-                StreamOperator::StreamReduce(stream_operator) => {
+                StreamOperator::StreamPartition(stream_operator, distribution) => {
+                    let op =
+                        PartitionRunnable::new(operator_id, stream_operator, distribution, None);
+                    let op: Box<dyn Runnable> = Box::new(op);
+                    op
+                }
+                StreamOperator::StreamReduce(stream_operator, window_options) => {
                     let stream_key_by = self.get_dependency_key_by(
                         &dag_manager,
                         operators.borrow_mut(),
                         job_node.job_id,
                     );
-                    let op = ReduceRunnable::new(operator_id, stream_key_by, stream_operator, None);
+                    let trigger = window_options
+                        .trigger
+                        .or(default_trigger.take())
+                        .expect("window trigger not found");
+                    let op = ReduceRunnable::new(
+                        operator_id,
+                        stream_key_by,
+                        stream_operator,
+                        trigger,
+                        window_options.allowed_lateness,
+                        window_options.late_data_output_tag,
+                        processing_time_window,
+                        merging_window,
+                        None,
+                    );
+                    let op: Box<dyn Runnable> = Box::new(op);
+                    op
+                }
+                StreamOperator::StreamKeyedProcess(stream_operator) => {
+                    let stream_key_bys = self.get_dependency_key_bys(
+                        &dag_manager,
+                        operators.borrow_mut(),
+                        &job_node,
+                    );
+                    let op = KeyedProcessRunnable::new(
+                        operator_id,
+                        stream_key_bys,
+                        stream_operator,
+                        None,
+                    );
                     let op: Box<dyn Runnable> = Box::new(op);
                     op
                 }
@@ -218,6 +290,63 @@ where
         }
     }
 
+    /// find the `WindowAssigner` of the job,
+    /// the window properties are shared by the `SourceRunnable` and `ReduceRunnable`
+    fn get_window_assigner<'a>(
+        &self,
+        job_node: &JobNode,
+        operators: &'a HashMap<OperatorId, StreamOperator>,
+    ) -> Option<&'a dyn WindowAssigner> {
+        job_node
+            .stream_nodes
+            .iter()
+            .filter_map(|stream_node| operators.get(&stream_node.id))
+            .find_map(|operator| match operator {
+                StreamOperator::StreamWindowAssigner(stream_operator) => {
+                    Some(stream_operator.operator_fn.as_ref())
+                }
+                _ => None,
+            })
+    }
+
+    /// find the `KeySelectorFunction` of each parent job,
+    /// ordered by the parents of the job's virtual source, the same as the `DataStream` index
+    fn get_dependency_key_bys(
+        &self,
+        dag_manager: &DagManager,
+        operators: &mut HashMap<OperatorId, StreamOperator>,
+        job_node: &JobNode,
+    ) -> Vec<(JobId, DefaultStreamOperator<dyn KeySelectorFunction>)> {
+        let job_parents = dag_manager.get_job_parents(job_node.job_id);
+        let mut stream_key_bys = Vec::new();
+        for parent_id in &job_node.stream_nodes[0].parent_ids {
+            let parent_job_node = job_parents
+                .iter()
+                .map(|(parent_job_node, _)| parent_job_node)
+                .find(|parent_job_node| {
+                    parent_job_node
+                        .stream_nodes
+                        .iter()
+                        .any(|stream_node| stream_node.id == *parent_id)
+                })
+                .expect("parent job not found");
+
+            let key_by_operator = parent_job_node
+                .stream_nodes
+                .iter()
+                .find(|x| x.operator_type == OperatorType::KeyBy)
+                .and_then(|stream_node| operators.remove(&stream_node.id));
+            match key_by_operator {
+                Some(StreamOperator::StreamKeyBy(stream_operator)) => {
+                    stream_key_bys.push((parent_job_node.job_id, stream_operator))
+                }
+                _ => error!("dependency StreamKeyBy not found"),
+            }
+        }
+
+        stream_key_bys
+    }
+
     fn get_dependency_key_by(
         &self,
         dag_manager: &DagManager,
@@ -0,0 +1,519 @@
+use std::collections::VecDeque;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use bytes::BytesMut;
+use crossbeam::channel::{unbounded, Receiver, Sender};
+use tokio::runtime::Handle;
+
+use crate::api::checkpoint::{Checkpoint, CheckpointHandle};
+use crate::api::element::{Element, Record, Serde};
+use crate::api::function::{AsyncFunction, AsyncOptions, AsyncOutputMode};
+use crate::api::operator::DefaultStreamOperator;
+use crate::api::output_tag::OutputTag;
+use crate::api::runtime::{CheckpointId, OperatorId, TaskId};
+use crate::metrics::{register_counter, Tag};
+use crate::runtime::worker::checkpoint::report_checkpoint;
+use crate::runtime::worker::runnable::{is_consumed, Runnable, RunnableContext};
+use crate::utils::thread::get_async_runtime_handle;
+
+/// the records of a completed lookup, `None` if the lookup is timeout
+type AsyncResult = (u64, Option<Vec<Record>>);
+
+#[derive(Debug)]
+enum AsyncEntry {
+    /// the input record is kept until the result is emitted, so it can be checkpointed
+    Lookup {
+        seq: u64,
+        record: Record,
+        result: Option<Vec<Record>>,
+    },
+    /// the watermark, barrier or stream status, emitted after all the lookups before it
+    Element(Element),
+}
+
+impl AsyncEntry {
+    fn is_element(&self) -> bool {
+        match self {
+            AsyncEntry::Element(_) => true,
+            _ => false,
+        }
+    }
+
+    fn is_completed(&self) -> bool {
+        match self {
+            AsyncEntry::Lookup { result, .. } => result.is_some(),
+            AsyncEntry::Element(_) => true,
+        }
+    }
+}
+
+/// the checkpoint of the in-flight lookups, the records are kept as the serialized bytes
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct AsyncSnapshot {
+    records: Vec<Vec<u8>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct AsyncMapRunnable {
+    operator_id: OperatorId,
+    task_id: TaskId,
+
+    stream_async: DefaultStreamOperator<dyn AsyncFunction>,
+    async_options: AsyncOptions,
+    next_runnable: Option<Box<dyn Runnable>>,
+
+    side_outputs: Vec<OutputTag>,
+
+    /// the handle of the runtime shared by the tasks of the process
+    runtime: Option<Handle>,
+    sender: Sender<AsyncResult>,
+    receiver: Receiver<AsyncResult>,
+
+    /// the lookups and the other elements in the order of the input
+    queue: VecDeque<AsyncEntry>,
+    in_flight: usize,
+    next_seq: u64,
+
+    counter: Arc<AtomicU64>,
+}
+
+impl AsyncMapRunnable {
+    pub fn new(
+        operator_id: OperatorId,
+        stream_async: DefaultStreamOperator<dyn AsyncFunction>,
+        async_options: AsyncOptions,
+        next_runnable: Option<Box<dyn Runnable>>,
+    ) -> Self {
+        info!("Create AsyncMapRunnable");
+
+        let (sender, receiver) = unbounded();
+        AsyncMapRunnable {
+            operator_id,
+            task_id: TaskId::default(),
+            stream_async,
+            async_options,
+            next_runnable,
+            side_outputs: Vec::new(),
+            runtime: None,
+            sender,
+            receiver,
+            queue: VecDeque::new(),
+            in_flight: 0,
+            next_seq: 0,
+            counter: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// spawn the lookup of the record, block the task until a slot is free if the capacity is full
+    fn submit(&mut self, record: Record) {
+        while self.in_flight >= self.async_options.capacity {
+            self.wait_completed();
+            self.emit_completed();
+        }
+
+        let seq = self.next_seq;
+        self.next_seq += 1;
+
+        let future = self.stream_async.operator_fn.async_invoke(record.clone());
+        let timeout = self.async_options.timeout;
+        let sender = self.sender.clone();
+        self.runtime.as_ref().unwrap().spawn(async move {
+            let result = tokio::time::timeout(timeout, future).await.ok();
+            if let Err(e) = sender.send((seq, result)) {
+                error!("send the async result error. {}", e);
+            }
+        });
+
+        self.queue.push_back(AsyncEntry::Lookup {
+            seq,
+            record,
+            result: None,
+        });
+        self.in_flight += 1;
+    }
+
+    fn complete(&mut self, async_result: AsyncResult) {
+        let (seq, records) = async_result;
+        let stream_async = &self.stream_async;
+        let entry = self.queue.iter_mut().find(|entry| match entry {
+            AsyncEntry::Lookup { seq: s, .. } => *s == seq,
+            _ => false,
+        });
+
+        if let Some(AsyncEntry::Lookup { record, result, .. }) = entry {
+            let records = records.unwrap_or_else(|| {
+                warn!("async lookup timeout, seq={}", seq);
+                stream_async.operator_fn.timeout(record.clone())
+            });
+            *result = Some(records);
+            self.in_flight -= 1;
+        }
+    }
+
+    fn poll_completed(&mut self) {
+        while let Ok(async_result) = self.receiver.try_recv() {
+            self.complete(async_result);
+        }
+    }
+
+    fn wait_completed(&mut self) {
+        // the `sender` is held by self, so the channel is never disconnected
+        let async_result = self.receiver.recv().unwrap();
+        self.complete(async_result);
+    }
+
+    /// emit the completed entries, the watermarks and barriers are never overtaken by the lookups
+    fn emit_completed(&mut self) {
+        loop {
+            if self.async_options.output_mode == AsyncOutputMode::Unordered {
+                let mut end = self
+                    .queue
+                    .iter()
+                    .position(|entry| entry.is_element())
+                    .unwrap_or(self.queue.len());
+                let mut index = 0;
+                while index < end {
+                    if self.queue[index].is_completed() {
+                        let entry = self.queue.remove(index).unwrap();
+                        self.emit(entry);
+                        end -= 1;
+                    } else {
+                        index += 1;
+                    }
+                }
+            }
+
+            match self.queue.front() {
+                Some(entry) if entry.is_completed() => {
+                    let entry = self.queue.pop_front().unwrap();
+                    self.emit(entry);
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn emit(&mut self, entry: AsyncEntry) {
+        match entry {
+            AsyncEntry::Lookup { result, .. } => {
+                let mut len = 0;
+                for record in result.unwrap_or_default() {
+                    let element = Element::Record(record);
+                    if !is_consumed(self.side_outputs.as_slice(), &element) {
+                        continue;
+                    }
+
+                    self.next_runnable.as_mut().unwrap().run(element);
+                    len += 1;
+                }
+
+                self.counter.fetch_add(len, Ordering::Relaxed);
+            }
+            AsyncEntry::Element(element) => self.next_runnable.as_mut().unwrap().run(element),
+        }
+    }
+
+    /// the results of the lookups before the barrier are not emitted yet,
+    /// so the input records are checkpointed and looked up again on restore
+    fn snapshot(&self) -> Option<CheckpointHandle> {
+        let records = self
+            .queue
+            .iter()
+            .filter_map(|entry| match entry {
+                AsyncEntry::Lookup { record, .. } => Some(record.to_bytes().to_vec()),
+                _ => None,
+            })
+            .collect();
+        match serde_json::to_string(&AsyncSnapshot { records }) {
+            Ok(handle) => Some(CheckpointHandle { handle }),
+            Err(e) => {
+                error!("snapshot the in-flight records error. {}", e);
+                None
+            }
+        }
+    }
+
+    /// re-submit the lookups that were in flight on the checkpoint
+    fn restore(&mut self, handle: &Option<CheckpointHandle>) {
+        if let Some(handle) = handle {
+            match serde_json::from_str::<AsyncSnapshot>(handle.handle.as_str()) {
+                Ok(snapshot) => {
+                    info!("restore {} in-flight records", snapshot.records.len());
+                    for bytes in snapshot.records {
+                        let record = Record::deserialize(&mut BytesMut::from(bytes.as_slice()));
+                        self.submit(record);
+                    }
+                }
+                Err(e) => error!("restore the in-flight records error. {}", e),
+            }
+        }
+    }
+}
+
+impl Runnable for AsyncMapRunnable {
+    fn open(&mut self, context: &RunnableContext) -> anyhow::Result<()> {
+        self.next_runnable.as_mut().unwrap().open(context)?;
+
+        self.task_id = context.task_descriptor.task_id;
+
+        let fun_context = context.to_fun_context(self.operator_id);
+        self.stream_async.operator_fn.open(&fun_context)?;
+
+        self.side_outputs = context.get_side_outputs(self.operator_id);
+        self.runtime = Some(get_async_runtime_handle());
+
+        let checkpoint_handle = context
+            .task_descriptor
+            .get_checkpoint_handle(self.operator_id);
+        self.restore(&checkpoint_handle);
+
+        let tags = vec![
+            Tag("job_id".to_string(), self.task_id.job_id.0.to_string()),
+            Tag(
+                "task_number".to_string(),
+                self.task_id.task_number.to_string(),
+            ),
+        ];
+        let metric_name = format!(
+            "AsyncMap_{}",
+            self.stream_async.operator_fn.as_ref().get_name()
+        );
+        register_counter(metric_name.as_str(), tags, self.counter.clone());
+
+        Ok(())
+    }
+
+    fn run(&mut self, element: Element) {
+        self.poll_completed();
+
+        if element.is_record() {
+            self.submit(element.into_record());
+        } else {
+            if element.is_end_of_input() {
+                // complete all the lookups before the end of the input
+                while self.in_flight > 0 {
+                    self.wait_completed();
+                }
+            }
+            if element.is_barrier() {
+                let checkpoint_id = element.as_barrier().checkpoint_id;
+                self.checkpoint(checkpoint_id);
+            }
+            self.queue.push_back(AsyncEntry::Element(element));
+        }
+
+        self.emit_completed();
+    }
+
+    fn close(&mut self) -> anyhow::Result<()> {
+        while self.in_flight > 0 {
+            self.wait_completed();
+        }
+        self.emit_completed();
+
+        self.stream_async.operator_fn.close()?;
+        self.next_runnable.as_mut().unwrap().close()
+    }
+
+    fn set_next_runnable(&mut self, next_runnable: Option<Box<dyn Runnable>>) {
+        self.next_runnable = next_runnable;
+    }
+
+    fn checkpoint(&mut self, checkpoint_id: CheckpointId) {
+        let handle = match self.snapshot() {
+            Some(handle) => handle,
+            None => return,
+        };
+
+        let ck = Checkpoint {
+            operator_id: self.operator_id,
+            task_id: self.task_id,
+            checkpoint_id,
+            handle,
+        };
+        report_checkpoint(ck);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::future::BoxFuture;
+
+    use crate::api;
+    use crate::api::element::{types, Element, Record};
+    use crate::api::function::{AsyncFunction, AsyncOptions, AsyncOutputMode, Context, Function};
+    use crate::api::operator::{DefaultStreamOperator, FunctionCreator};
+    use crate::api::runtime::OperatorId;
+    use crate::runtime::worker::runnable::async_map_runnable::AsyncMapRunnable;
+    use crate::runtime::worker::runnable::tests::CollectRunnable;
+    use crate::runtime::worker::runnable::Runnable;
+    use crate::utils::thread::get_async_runtime_handle;
+
+    const TIMEOUT_VALUE: i64 = -1;
+
+    /// the lookup of the record completes after the milliseconds of its value
+    struct DelayAsyncFunction {}
+
+    impl AsyncFunction for DelayAsyncFunction {
+        fn open(&mut self, _context: &Context) -> api::Result<()> {
+            Ok(())
+        }
+
+        fn async_invoke(&self, mut record: Record) -> BoxFuture<'static, Vec<Record>> {
+            let delay = record.get_reader(&[types::I64]).get_i64(0).unwrap() as u64;
+            Box::pin(async move {
+                tokio::time::delay_for(Duration::from_millis(delay)).await;
+                vec![record]
+            })
+        }
+
+        fn timeout(&self, _record: Record) -> Vec<Record> {
+            vec![record(TIMEOUT_VALUE).into_record()]
+        }
+
+        fn close(&mut self) -> api::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Function for DelayAsyncFunction {
+        fn get_name(&self) -> &str {
+            "DelayAsyncFunction"
+        }
+    }
+
+    fn record(value: i64) -> Element {
+        let mut record = Record::new();
+        record.get_writer(&[types::I64]).set_i64(value).unwrap();
+        Element::Record(record)
+    }
+
+    /// the values of the records, the other elements are `None`
+    fn values(elements: Vec<Element>) -> Vec<Option<i64>> {
+        elements
+            .into_iter()
+            .map(|element| {
+                if element.is_record() {
+                    let mut record = element.into_record();
+                    Some(record.get_reader(&[types::I64]).get_i64(0).unwrap())
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// the runnable is not opened, so the runtime is set as `open` does
+    fn async_map_runnable(
+        capacity: usize,
+        timeout: Duration,
+        output_mode: AsyncOutputMode,
+    ) -> (AsyncMapRunnable, CollectRunnable) {
+        let collect = CollectRunnable::default();
+        let stream_async = DefaultStreamOperator::new(
+            1,
+            FunctionCreator::User,
+            Box::new(DelayAsyncFunction {}) as Box<dyn AsyncFunction>,
+        );
+        let async_options = AsyncOptions {
+            capacity,
+            timeout,
+            output_mode,
+        };
+        let mut runnable = AsyncMapRunnable::new(
+            OperatorId(1),
+            stream_async,
+            async_options,
+            Some(Box::new(collect.clone())),
+        );
+        runnable.runtime = Some(get_async_runtime_handle());
+
+        (runnable, collect)
+    }
+
+    fn run_out_of_order(output_mode: AsyncOutputMode) -> Vec<Option<i64>> {
+        let (mut runnable, collect) = async_map_runnable(10, Duration::from_secs(10), output_mode);
+
+        runnable.run(record(200));
+        runnable.run(record(10));
+        std::thread::sleep(Duration::from_millis(100));
+        runnable.run(Element::new_stream_status(100, false));
+        runnable.run(Element::new_stream_status(200, true));
+
+        let elements = collect.take();
+        assert!(elements[2].is_stream_status());
+        assert!(elements[3].is_end_of_input());
+        values(elements)
+    }
+
+    #[test]
+    pub fn async_map_ordered_test() {
+        let values = run_out_of_order(AsyncOutputMode::Ordered);
+        assert_eq!(values, vec![Some(200), Some(10), None, None]);
+    }
+
+    #[test]
+    pub fn async_map_unordered_test() {
+        // the 10ms lookup overtakes the 200ms lookup, but not the stream status after them
+        let values = run_out_of_order(AsyncOutputMode::Unordered);
+        assert_eq!(values, vec![Some(10), Some(200), None, None]);
+    }
+
+    #[test]
+    pub fn async_map_capacity_test() {
+        let (mut runnable, collect) =
+            async_map_runnable(1, Duration::from_secs(10), AsyncOutputMode::Ordered);
+
+        runnable.run(record(50));
+        assert_eq!(runnable.in_flight, 1);
+
+        // the task is blocked until the first lookup completes
+        runnable.run(record(10));
+        assert_eq!(runnable.in_flight, 1);
+        assert_eq!(values(collect.take()), vec![Some(50)]);
+
+        runnable.run(Element::new_stream_status(100, true));
+        assert_eq!(runnable.in_flight, 0);
+        assert_eq!(values(collect.take()), vec![Some(10), None]);
+    }
+
+    #[test]
+    pub fn async_map_timeout_test() {
+        let (mut runnable, collect) =
+            async_map_runnable(10, Duration::from_millis(20), AsyncOutputMode::Ordered);
+
+        runnable.run(record(500));
+        runnable.run(record(1));
+        runnable.run(Element::new_stream_status(100, true));
+
+        assert_eq!(
+            values(collect.take()),
+            vec![Some(TIMEOUT_VALUE), Some(1), None]
+        );
+    }
+
+    #[test]
+    pub fn async_map_restore_test() {
+        let (mut runnable, collect) =
+            async_map_runnable(10, Duration::from_secs(10), AsyncOutputMode::Ordered);
+
+        runnable.run(record(100));
+        runnable.run(record(150));
+        let handle = runnable.snapshot();
+        assert!(handle.is_some());
+        assert!(collect.take().is_empty());
+
+        // the in-flight records are looked up again by the restored task
+        let (mut restored, collect) =
+            async_map_runnable(10, Duration::from_secs(10), AsyncOutputMode::Ordered);
+        restored.restore(&handle);
+        assert_eq!(restored.in_flight, 2);
+
+        restored.run(Element::new_stream_status(100, true));
+        assert_eq!(values(collect.take()), vec![Some(100), Some(150), None]);
+    }
+}
@@ -0,0 +1,214 @@
+use crate::api::checkpoint::{Checkpoint, CheckpointHandle};
+use crate::api::element::{Element, Record, StreamStatus};
+use crate::api::function::BroadcastProcessFunction;
+use crate::api::operator::DefaultStreamOperator;
+use crate::api::output_tag::OutputTag;
+use crate::api::runtime::{CheckpointId, JobId, OperatorId, TaskId};
+use crate::api::state::{
+    BroadcastContext, BroadcastStateSnapshot, BroadcastStateStore, MapStateDescriptor,
+    ReadOnlyBroadcastContext,
+};
+use crate::runtime::worker::checkpoint::report_checkpoint;
+use crate::runtime::worker::runnable::co_process_runnable::InputWatermarkAlign;
+use crate::runtime::worker::runnable::{is_consumed, Runnable, RunnableContext};
+
+#[derive(Debug)]
+pub(crate) struct BroadcastProcessRunnable {
+    operator_id: OperatorId,
+    task_id: TaskId,
+
+    stream_broadcast_process: DefaultStreamOperator<dyn BroadcastProcessFunction>,
+    next_runnable: Option<Box<dyn Runnable>>,
+
+    /// the job of the broadcast stream, the other records come from the data stream
+    broadcast_job_id: JobId,
+
+    side_outputs: Vec<OutputTag>,
+
+    state_store: BroadcastStateStore,
+
+    /// the number of the input tasks, all the watermarks and barriers are broadcast by them
+    num_inputs: usize,
+    watermark_align: Option<InputWatermarkAlign>,
+
+    current_checkpoint_id: CheckpointId,
+    reached_barriers: usize,
+}
+
+impl BroadcastProcessRunnable {
+    pub fn new(
+        operator_id: OperatorId,
+        stream_broadcast_process: DefaultStreamOperator<dyn BroadcastProcessFunction>,
+        descriptors: Vec<MapStateDescriptor>,
+        next_runnable: Option<Box<dyn Runnable>>,
+    ) -> Self {
+        info!("Create BroadcastProcessRunnable");
+
+        BroadcastProcessRunnable {
+            operator_id,
+            task_id: TaskId::default(),
+            stream_broadcast_process,
+            next_runnable,
+            broadcast_job_id: JobId::default(),
+            side_outputs: Vec::new(),
+            state_store: BroadcastStateStore::new(descriptors.as_slice()),
+            num_inputs: 0,
+            watermark_align: None,
+            current_checkpoint_id: CheckpointId::default(),
+            reached_barriers: 0,
+        }
+    }
+
+    fn emit(&mut self, records: Box<dyn Iterator<Item = Record>>) {
+        for record in records {
+            let element = Element::Record(record);
+            if !is_consumed(self.side_outputs.as_slice(), &element) {
+                continue;
+            }
+
+            self.next_runnable.as_mut().unwrap().run(element);
+        }
+    }
+
+    fn restore_state(&mut self, handle: &Option<CheckpointHandle>) {
+        if let Some(handle) = handle {
+            match serde_json::from_str::<BroadcastStateSnapshot>(handle.handle.as_str()) {
+                Ok(snapshot) => self.state_store.restore(snapshot),
+                Err(e) => error!("restore the broadcast state error. {}", e),
+            }
+        }
+    }
+}
+
+impl Runnable for BroadcastProcessRunnable {
+    fn open(&mut self, context: &RunnableContext) -> anyhow::Result<()> {
+        self.next_runnable.as_mut().unwrap().open(context)?;
+
+        self.task_id = context.task_descriptor.task_id;
+
+        // the broadcast stream is the last parent of the virtual source
+        let broadcast_parent_id = {
+            let stream_node = context.get_stream(self.operator_id);
+            let source_stream_node = context.get_stream(stream_node.parent_ids[0]);
+            *source_stream_node.parent_ids.last().unwrap()
+        };
+        self.broadcast_job_id = context
+            .get_parent_jobs()
+            .iter()
+            .find_map(|(node, _)| {
+                node.stream_nodes
+                    .iter()
+                    .find(|x| x.id == broadcast_parent_id)
+                    .map(|_x| node.job_id)
+            })
+            .expect("broadcast job not found");
+
+        let fun_context = context.to_fun_context(self.operator_id);
+        self.stream_broadcast_process
+            .operator_fn
+            .open(&fun_context)?;
+
+        self.side_outputs = context.get_side_outputs(self.operator_id);
+
+        self.num_inputs = context.get_num_inputs();
+        self.watermark_align = Some(InputWatermarkAlign::new(self.num_inputs));
+
+        let checkpoint_handle = context
+            .task_descriptor
+            .get_checkpoint_handle(self.operator_id);
+        self.restore_state(&checkpoint_handle);
+
+        Ok(())
+    }
+
+    fn run(&mut self, element: Element) {
+        match element {
+            Element::Record(record) => {
+                let records = if record.channel_key.source_task_id.job_id == self.broadcast_job_id {
+                    let mut context =
+                        BroadcastContext::new(record.timestamp, &mut self.state_store);
+                    self.stream_broadcast_process
+                        .operator_fn
+                        .process_broadcast_element(record, &mut context)
+                } else {
+                    let context =
+                        ReadOnlyBroadcastContext::new(record.timestamp, &self.state_store);
+                    self.stream_broadcast_process
+                        .operator_fn
+                        .process_element(record, &context)
+                };
+                self.emit(records);
+            }
+            Element::Watermark(watermark) => {
+                let watermark_align = self.watermark_align.as_mut().unwrap();
+                if let Some(timestamp) = watermark_align.insert(&watermark) {
+                    let stream_status = StreamStatus::new(watermark.status_timestamp, false);
+                    self.next_runnable
+                        .as_mut()
+                        .unwrap()
+                        .run(Element::new_watermark(
+                            self.task_id.task_number,
+                            self.task_id.num_tasks,
+                            timestamp,
+                            &stream_status,
+                        ));
+                }
+            }
+            Element::Barrier(barrier) => {
+                if self.current_checkpoint_id != barrier.checkpoint_id {
+                    if self.reached_barriers > 0 {
+                        error!(
+                            "Found a new checkpoint({:?}) if the current checkpoint({:?}) is not completed",
+                            barrier.checkpoint_id, self.current_checkpoint_id,
+                        );
+                    }
+                    self.current_checkpoint_id = barrier.checkpoint_id;
+                    self.reached_barriers = 0;
+                }
+
+                // the broadcast records before the barriers are applied by all the instances
+                self.reached_barriers += 1;
+                if self.reached_barriers == self.num_inputs {
+                    self.checkpoint(barrier.checkpoint_id);
+                    self.reached_barriers = 0;
+
+                    self.next_runnable
+                        .as_mut()
+                        .unwrap()
+                        .run(Element::Barrier(barrier));
+                }
+            }
+            _ => {
+                self.next_runnable.as_mut().unwrap().run(element);
+            }
+        }
+    }
+
+    fn close(&mut self) -> anyhow::Result<()> {
+        self.stream_broadcast_process.operator_fn.close()?;
+        self.next_runnable.as_mut().unwrap().close()
+    }
+
+    fn set_next_runnable(&mut self, next_runnable: Option<Box<dyn Runnable>>) {
+        self.next_runnable = next_runnable;
+    }
+
+    fn checkpoint(&mut self, checkpoint_id: CheckpointId) {
+        let snapshot = self.state_store.snapshot();
+        let handle = match serde_json::to_string(&snapshot) {
+            Ok(handle) => handle,
+            Err(e) => {
+                error!("snapshot the broadcast state error. {}", e);
+                return;
+            }
+        };
+
+        let ck = Checkpoint {
+            operator_id: self.operator_id,
+            task_id: self.task_id,
+            checkpoint_id,
+            handle: CheckpointHandle { handle },
+        };
+        report_checkpoint(ck);
+    }
+}
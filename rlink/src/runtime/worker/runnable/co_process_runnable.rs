@@ -1,10 +1,12 @@
+use std::cmp::min;
 use std::collections::HashMap;
 
-use crate::api::element::Element;
+use crate::api::element::{Element, StreamStatus, Watermark};
 use crate::api::function::CoProcessFunction;
 use crate::api::operator::DefaultStreamOperator;
+use crate::api::output_tag::OutputTag;
 use crate::api::runtime::{CheckpointId, JobId, OperatorId};
-use crate::runtime::worker::runnable::{Runnable, RunnableContext};
+use crate::runtime::worker::runnable::{is_consumed, Runnable, RunnableContext};
 
 #[derive(Debug)]
 pub(crate) struct CoProcessRunnable {
@@ -15,6 +17,12 @@ pub(crate) struct CoProcessRunnable {
     /// key: JobId,
     /// value: DataStream index  
     parent_jobs: HashMap<JobId, usize>,
+
+    side_outputs: Vec<OutputTag>,
+
+    task_number: u16,
+    num_tasks: u16,
+    watermark_align: Option<InputWatermarkAlign>,
 }
 
 impl CoProcessRunnable {
@@ -30,6 +38,10 @@ impl CoProcessRunnable {
             stream_co_process,
             next_runnable,
             parent_jobs: HashMap::new(),
+            side_outputs: Vec::new(),
+            task_number: 0,
+            num_tasks: 0,
+            watermark_align: None,
         }
     }
 }
@@ -60,6 +72,15 @@ impl Runnable for CoProcessRunnable {
         let fun_context = context.to_fun_context(self.operator_id);
         self.stream_co_process.operator_fn.open(&fun_context)?;
 
+        self.side_outputs = context.get_side_outputs(self.operator_id);
+
+        self.task_number = context.task_descriptor.task_id.task_number;
+        self.num_tasks = context.task_descriptor.task_id.num_tasks;
+
+        // the watermarks are received from each connected parent task
+        let num_inputs = context.get_num_inputs();
+        self.watermark_align = Some(InputWatermarkAlign::new(num_inputs));
+
         Ok(())
     }
 
@@ -84,10 +105,27 @@ impl Runnable for CoProcessRunnable {
                 };
 
                 for record in records {
+                    let element = Element::Record(record);
+                    if !is_consumed(self.side_outputs.as_slice(), &element) {
+                        continue;
+                    }
+
+                    self.next_runnable.as_mut().unwrap().run(element);
+                }
+            }
+            Element::Watermark(watermark) => {
+                let watermark_align = self.watermark_align.as_mut().unwrap();
+                if let Some(timestamp) = watermark_align.insert(&watermark) {
+                    let stream_status = StreamStatus::new(watermark.status_timestamp, false);
                     self.next_runnable
                         .as_mut()
                         .unwrap()
-                        .run(Element::Record(record));
+                        .run(Element::new_watermark(
+                            self.task_number,
+                            self.num_tasks,
+                            timestamp,
+                            &stream_status,
+                        ));
                 }
             }
             _ => {
@@ -107,3 +145,145 @@ impl Runnable for CoProcessRunnable {
 
     fn checkpoint(&mut self, _checkpoint_id: CheckpointId) {}
 }
+
+/// align the watermarks of all input tasks by `status_timestamp`,
+/// the minimum watermark is emitted once all inputs have reached
+#[derive(Debug)]
+pub(crate) struct InputWatermarkAlign {
+    num_inputs: usize,
+    // Hash<Watermark::status_timestamp, (minimum watermark timestamp, reached counter)>
+    status_watermarks: HashMap<u64, (u64, usize)>,
+    current_watermark: u64,
+}
+
+impl InputWatermarkAlign {
+    pub fn new(num_inputs: usize) -> Self {
+        InputWatermarkAlign {
+            num_inputs,
+            status_watermarks: HashMap::new(),
+            current_watermark: 0,
+        }
+    }
+
+    /// Return the aligned watermark timestamp if it advances
+    pub fn insert(&mut self, watermark: &Watermark) -> Option<u64> {
+        let status_timestamp = watermark.status_timestamp;
+        let reached = {
+            let (timestamp, reached_counter) = self
+                .status_watermarks
+                .entry(status_timestamp)
+                .or_insert((u64::MAX, 0));
+            *timestamp = min(*timestamp, watermark.timestamp);
+            *reached_counter += 1;
+            *reached_counter >= self.num_inputs
+        };
+
+        if !reached {
+            // the watermarks of a lost input are kept limited
+            if self.status_watermarks.len() > 100 {
+                let min_status_timestamp = *self.status_watermarks.keys().min().unwrap();
+                self.status_watermarks.remove(&min_status_timestamp);
+            }
+            return None;
+        }
+
+        let (timestamp, _) = self.status_watermarks.remove(&status_timestamp).unwrap();
+        // the earlier unaligned watermarks are outdated
+        self.status_watermarks.retain(|x, _| *x > status_timestamp);
+
+        if timestamp > self.current_watermark {
+            self.current_watermark = timestamp;
+            Some(timestamp)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::element::{StreamStatus, Watermark};
+    use crate::api::watermark::MAX_WATERMARK;
+    use crate::runtime::worker::runnable::co_process_runnable::InputWatermarkAlign;
+
+    #[test]
+    pub fn input_watermark_align_test() {
+        let mut align = InputWatermarkAlign::new(3);
+
+        let stream_status = StreamStatus::new(10, false);
+        assert_eq!(
+            align.insert(&Watermark::new(0, 1, 1000, &stream_status)),
+            None
+        );
+        assert_eq!(
+            align.insert(&Watermark::new(0, 2, 800, &stream_status)),
+            None
+        );
+        assert_eq!(
+            align.insert(&Watermark::new(1, 2, 900, &stream_status)),
+            Some(800)
+        );
+
+        // the watermark never goes back
+        let stream_status = StreamStatus::new(20, false);
+        assert_eq!(
+            align.insert(&Watermark::new(0, 1, 2000, &stream_status)),
+            None
+        );
+        assert_eq!(
+            align.insert(&Watermark::new(0, 2, 700, &stream_status)),
+            None
+        );
+        assert_eq!(
+            align.insert(&Watermark::new(1, 2, 2000, &stream_status)),
+            None
+        );
+
+        let stream_status = StreamStatus::new(30, false);
+        assert_eq!(
+            align.insert(&Watermark::new(0, 1, 3000, &stream_status)),
+            None
+        );
+        assert_eq!(
+            align.insert(&Watermark::new(0, 2, 2500, &stream_status)),
+            None
+        );
+        assert_eq!(
+            align.insert(&Watermark::new(1, 2, 2800, &stream_status)),
+            Some(2500)
+        );
+    }
+
+    #[test]
+    pub fn end_of_input_watermark_align_test() {
+        let mut align = InputWatermarkAlign::new(2);
+
+        let stream_status = StreamStatus::new(10, false);
+        assert_eq!(
+            align.insert(&Watermark::new(0, 1, 1000, &stream_status)),
+            None
+        );
+        assert_eq!(
+            align.insert(&Watermark::new(0, 1, 900, &stream_status)),
+            Some(900)
+        );
+
+        // the first input ends, the other one is still running
+        let end_status = StreamStatus::new(MAX_WATERMARK.timestamp, true);
+        assert_eq!(
+            align.insert(&Watermark::new(0, 1, MAX_WATERMARK.timestamp, &end_status)),
+            None
+        );
+        let stream_status = StreamStatus::new(20, false);
+        assert_eq!(
+            align.insert(&Watermark::new(0, 1, 2000, &stream_status)),
+            None
+        );
+
+        // the final watermarks of all the inputs are aligned by the end status
+        assert_eq!(
+            align.insert(&Watermark::new(0, 1, MAX_WATERMARK.timestamp, &end_status)),
+            Some(MAX_WATERMARK.timestamp)
+        );
+    }
+}
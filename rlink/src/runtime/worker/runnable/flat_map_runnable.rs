@@ -5,9 +5,10 @@ use std::sync::Arc;
 use crate::api::element::Element;
 use crate::api::function::FlatMapFunction;
 use crate::api::operator::DefaultStreamOperator;
+use crate::api::output_tag::OutputTag;
 use crate::api::runtime::{CheckpointId, OperatorId};
 use crate::metrics::{register_counter, Tag};
-use crate::runtime::worker::runnable::{Runnable, RunnableContext};
+use crate::runtime::worker::runnable::{is_consumed, Runnable, RunnableContext};
 
 #[derive(Debug)]
 pub(crate) struct FlatMapRunnable {
@@ -15,6 +16,8 @@ pub(crate) struct FlatMapRunnable {
     stream_map: DefaultStreamOperator<dyn FlatMapFunction>,
     next_runnable: Option<Box<dyn Runnable>>,
 
+    side_outputs: Vec<OutputTag>,
+
     counter: Arc<AtomicU64>,
 }
 
@@ -30,6 +33,7 @@ impl FlatMapRunnable {
             operator_id,
             stream_map,
             next_runnable,
+            side_outputs: Vec::new(),
             counter: Arc::new(AtomicU64::new(0)),
         }
     }
@@ -41,6 +45,8 @@ impl Runnable for FlatMapRunnable {
         let fun_context = context.to_fun_context(self.operator_id);
         self.stream_map.operator_fn.open(&fun_context)?;
 
+        self.side_outputs = context.get_side_outputs(self.operator_id);
+
         let tags = vec![
             Tag(
                 "job_id".to_string(),
@@ -70,6 +76,10 @@ impl Runnable for FlatMapRunnable {
 
             let mut len = 0;
             for ele in elements {
+                if !is_consumed(self.side_outputs.as_slice(), &ele) {
+                    continue;
+                }
+
                 self.next_runnable.as_mut().unwrap().run(ele);
                 len += 1;
             }
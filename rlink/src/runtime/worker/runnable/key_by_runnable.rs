@@ -4,18 +4,19 @@ use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 use crate::api::element::Element;
-use crate::api::function::KeySelectorFunction;
+use crate::api::function::{KeySelectorFunction, Partitioner};
 use crate::api::operator::DefaultStreamOperator;
 use crate::api::runtime::{CheckpointId, OperatorId};
+use crate::functions::partitioner::HashPartitioner;
 use crate::metrics::{register_counter, Tag};
 use crate::runtime::worker::runnable::{Runnable, RunnableContext};
-use crate::utils;
 
 #[derive(Debug)]
 pub(crate) struct KeyByRunnable {
     operator_id: OperatorId,
     stream_key_by: DefaultStreamOperator<dyn KeySelectorFunction>,
     next_runnable: Option<Box<dyn Runnable>>,
+    partitioner: HashPartitioner,
     partition_size: u16,
 
     counter: Arc<AtomicU64>,
@@ -31,6 +32,7 @@ impl KeyByRunnable {
             operator_id,
             stream_key_by,
             next_runnable,
+            partitioner: HashPartitioner::new(),
             partition_size: 0,
             counter: Arc::new(AtomicU64::new(0)),
         }
@@ -67,49 +69,31 @@ impl Runnable for KeyByRunnable {
     }
 
     fn run(&mut self, mut element: Element) {
+        if element.is_end_of_input() {
+            // broadcast to all partitions by the `SystemOutputFormat`
+            self.next_runnable.as_mut().unwrap().run(element);
+            return;
+        }
+
         match element.borrow_mut() {
             Element::Record(record) => {
-                let key_row = self
+                let mut key_row = self
                     .stream_key_by
                     .operator_fn
                     .as_mut()
                     .get_key(record.borrow_mut());
 
-                let hash_code = utils::hash::hash_code(key_row.values.as_slice()).unwrap_or(0);
-                let partition_num = hash_code % self.partition_size as u32;
-                // info!(
-                //     "partition: {}, hash code: {}, partition_size: {}",
-                //     partition_num,
-                //     hash_code,
-                //     self.partition_size,
-                // );
-                record.partition_num = partition_num as u16;
+                record.partition_num = self
+                    .partitioner
+                    .partition(&mut key_row, self.partition_size);
 
                 self.next_runnable.as_mut().unwrap().run(element);
 
                 self.counter.fetch_add(1, Ordering::Relaxed);
             }
-            Element::Watermark(watermark) => {
-                for index in 0..self.partition_size {
-                    let mut row_watermark = watermark.clone();
-                    row_watermark.partition_num = index as u16;
-
-                    self.next_runnable
-                        .as_mut()
-                        .unwrap()
-                        .run(Element::Watermark(row_watermark));
-                }
-            }
-            Element::Barrier(barrier) => {
-                for index in 0..self.partition_size {
-                    let mut row_barrier = barrier.clone();
-                    row_barrier.partition_num = index as u16;
-
-                    self.next_runnable
-                        .as_mut()
-                        .unwrap()
-                        .run(Element::Barrier(row_barrier));
-                }
+            Element::Watermark(_) | Element::Barrier(_) => {
+                // broadcast to all partitions by the `SystemOutputFormat`
+                self.next_runnable.as_mut().unwrap().run(element);
             }
             _ => {}
         }
@@ -0,0 +1,425 @@
+use std::borrow::BorrowMut;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use crate::api::checkpoint::{Checkpoint, CheckpointHandle};
+use crate::api::element::{Element, Record, StreamStatus};
+use crate::api::function::{KeySelectorFunction, KeyedProcessFunction};
+use crate::api::operator::DefaultStreamOperator;
+use crate::api::output_tag::OutputTag;
+use crate::api::runtime::{CheckpointId, JobId, OperatorId, TaskId};
+use crate::api::state::{
+    KeyedProcessContext, KeyedStateSnapshot, KeyedStateStore, TimeDomain, TimerService,
+    TimerSnapshot,
+};
+use crate::api::watermark::MAX_WATERMARK;
+use crate::metrics::{register_counter, Tag};
+use crate::runtime::worker::checkpoint::report_checkpoint;
+use crate::runtime::worker::runnable::co_process_runnable::InputWatermarkAlign;
+use crate::runtime::worker::runnable::{is_consumed, Runnable, RunnableContext};
+use crate::utils::date_time::current_timestamp_millis;
+
+/// the checkpoint of the `KeyedProcessRunnable`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct KeyedProcessSnapshot {
+    timers: TimerSnapshot,
+    states: KeyedStateSnapshot,
+}
+
+#[derive(Debug)]
+pub(crate) struct KeyedProcessRunnable {
+    operator_id: OperatorId,
+    task_id: TaskId,
+
+    /// the key selectors of the parent jobs, ordered by the `DataStream` index
+    stream_key_bys: Vec<(JobId, DefaultStreamOperator<dyn KeySelectorFunction>)>,
+    stream_process: DefaultStreamOperator<dyn KeyedProcessFunction>,
+    next_runnable: Option<Box<dyn Runnable>>,
+
+    side_outputs: Vec<OutputTag>,
+
+    state_store: KeyedStateStore,
+    timer_service: TimerService,
+
+    /// the number of the input tasks, all the watermarks and barriers are broadcast by them
+    num_inputs: usize,
+    watermark_align: Option<InputWatermarkAlign>,
+
+    current_checkpoint_id: CheckpointId,
+    reached_barriers: usize,
+
+    counter: Arc<AtomicU64>,
+}
+
+impl KeyedProcessRunnable {
+    pub fn new(
+        operator_id: OperatorId,
+        stream_key_bys: Vec<(JobId, DefaultStreamOperator<dyn KeySelectorFunction>)>,
+        stream_process: DefaultStreamOperator<dyn KeyedProcessFunction>,
+        next_runnable: Option<Box<dyn Runnable>>,
+    ) -> Self {
+        info!("Create KeyedProcessRunnable");
+
+        KeyedProcessRunnable {
+            operator_id,
+            task_id: TaskId::default(),
+            stream_key_bys,
+            stream_process,
+            next_runnable,
+            side_outputs: Vec::new(),
+            state_store: KeyedStateStore::new(),
+            timer_service: TimerService::new(),
+            num_inputs: 0,
+            watermark_align: None,
+            current_checkpoint_id: CheckpointId::default(),
+            reached_barriers: 0,
+            counter: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    fn emit(&mut self, records: Box<dyn Iterator<Item = Record>>) {
+        for record in records {
+            let element = Element::Record(record);
+            if !is_consumed(self.side_outputs.as_slice(), &element) {
+                continue;
+            }
+
+            self.next_runnable.as_mut().unwrap().run(element);
+        }
+    }
+
+    /// fire the timers in order, the states are scoped to the key of the timer
+    fn on_timers(&mut self, timers: Vec<(u64, Record)>, time_domain: TimeDomain) {
+        for (timestamp, key) in timers {
+            self.timer_service.set_current_key(key);
+
+            let records = {
+                let mut context = KeyedProcessContext::new(
+                    timestamp,
+                    0,
+                    &mut self.state_store,
+                    &mut self.timer_service,
+                );
+                self.stream_process
+                    .operator_fn
+                    .on_timer(timestamp, time_domain, &mut context)
+            };
+            self.emit(records);
+        }
+    }
+
+    /// fire all the timers when the input ends, including the timers registered by the firing
+    fn on_end_of_input(&mut self) {
+        self.timer_service.current_watermark = MAX_WATERMARK.timestamp;
+        loop {
+            let timers = self
+                .timer_service
+                .poll_event_time_timers(MAX_WATERMARK.timestamp);
+            if timers.is_empty() {
+                break;
+            }
+            self.on_timers(timers, TimeDomain::EventTime);
+        }
+
+        self.timer_service.current_processing_time = MAX_WATERMARK.timestamp;
+        loop {
+            let timers = self
+                .timer_service
+                .poll_processing_time_timers(MAX_WATERMARK.timestamp);
+            if timers.is_empty() {
+                break;
+            }
+            self.on_timers(timers, TimeDomain::ProcessingTime);
+        }
+    }
+
+    fn restore_state(&mut self, handle: &Option<CheckpointHandle>) {
+        if let Some(handle) = handle {
+            match serde_json::from_str::<KeyedProcessSnapshot>(handle.handle.as_str()) {
+                Ok(snapshot) => {
+                    self.timer_service.restore(snapshot.timers);
+                    self.state_store.restore(snapshot.states);
+                }
+                Err(e) => error!("restore the keyed states and timers error. {}", e),
+            }
+        }
+    }
+}
+
+impl Runnable for KeyedProcessRunnable {
+    fn open(&mut self, context: &RunnableContext) -> anyhow::Result<()> {
+        self.next_runnable.as_mut().unwrap().open(context)?;
+
+        self.task_id = context.task_descriptor.task_id;
+
+        let fun_context = context.to_fun_context(self.operator_id);
+        self.stream_process.operator_fn.open(&fun_context)?;
+        for (_job_id, stream_key_by) in &mut self.stream_key_bys {
+            stream_key_by.operator_fn.open(&fun_context)?;
+        }
+
+        self.side_outputs = context.get_side_outputs(self.operator_id);
+
+        self.num_inputs = context.get_num_inputs();
+        self.watermark_align = Some(InputWatermarkAlign::new(self.num_inputs));
+
+        let checkpoint_handle = context
+            .task_descriptor
+            .get_checkpoint_handle(self.operator_id);
+        self.restore_state(&checkpoint_handle);
+
+        let tags = vec![
+            Tag("job_id".to_string(), self.task_id.job_id.0.to_string()),
+            Tag(
+                "task_number".to_string(),
+                self.task_id.task_number.to_string(),
+            ),
+        ];
+        let metric_name = format!(
+            "KeyedProcess_{}",
+            self.stream_process.operator_fn.as_ref().get_name()
+        );
+        register_counter(metric_name.as_str(), tags, self.counter.clone());
+
+        Ok(())
+    }
+
+    fn run(&mut self, element: Element) {
+        match element {
+            Element::Record(mut record) => {
+                let stream_seq = if self.stream_key_bys.len() > 1 {
+                    let job_id = record.channel_key.source_task_id.job_id;
+                    self.stream_key_bys
+                        .iter()
+                        .position(|(parent_job_id, _)| *parent_job_id == job_id)
+                        .expect("parent job not found")
+                } else {
+                    0
+                };
+                let key = match self.stream_key_bys.get(stream_seq) {
+                    Some((_job_id, stream_key_by)) => {
+                        stream_key_by.operator_fn.get_key(record.borrow_mut())
+                    }
+                    None => Record::with_capacity(0),
+                };
+                self.timer_service.set_current_key(key);
+
+                let records = {
+                    let mut context = KeyedProcessContext::new(
+                        record.timestamp,
+                        stream_seq,
+                        &mut self.state_store,
+                        &mut self.timer_service,
+                    );
+                    self.stream_process
+                        .operator_fn
+                        .process_element(record, &mut context)
+                };
+                self.emit(records);
+
+                self.counter.fetch_add(1, Ordering::Relaxed);
+            }
+            Element::Watermark(watermark) => {
+                let watermark_align = self.watermark_align.as_mut().unwrap();
+                if let Some(timestamp) = watermark_align.insert(&watermark) {
+                    self.timer_service.current_watermark = timestamp;
+                    let timers = self.timer_service.poll_event_time_timers(timestamp);
+                    self.on_timers(timers, TimeDomain::EventTime);
+
+                    let stream_status = StreamStatus::new(watermark.status_timestamp, false);
+                    self.next_runnable
+                        .as_mut()
+                        .unwrap()
+                        .run(Element::new_watermark(
+                            self.task_id.task_number,
+                            self.task_id.num_tasks,
+                            timestamp,
+                            &stream_status,
+                        ));
+                }
+            }
+            Element::StreamStatus(stream_status) if stream_status.end => {
+                self.on_end_of_input();
+                self.next_runnable
+                    .as_mut()
+                    .unwrap()
+                    .run(Element::StreamStatus(stream_status));
+            }
+            Element::StreamStatus(_stream_status) => {
+                // the processing time timers are fired by the `WindowTimer`
+                let processing_time = current_timestamp_millis();
+                self.timer_service.current_processing_time = processing_time;
+                let timers = self
+                    .timer_service
+                    .poll_processing_time_timers(processing_time);
+                self.on_timers(timers, TimeDomain::ProcessingTime);
+            }
+            Element::Barrier(barrier) => {
+                if self.current_checkpoint_id != barrier.checkpoint_id {
+                    if self.reached_barriers > 0 {
+                        error!(
+                            "Found a new checkpoint({:?}) if the current checkpoint({:?}) is not completed",
+                            barrier.checkpoint_id, self.current_checkpoint_id,
+                        );
+                    }
+                    self.current_checkpoint_id = barrier.checkpoint_id;
+                    self.reached_barriers = 0;
+                }
+
+                self.reached_barriers += 1;
+                if self.reached_barriers == self.num_inputs {
+                    self.checkpoint(barrier.checkpoint_id);
+                    self.reached_barriers = 0;
+
+                    self.next_runnable
+                        .as_mut()
+                        .unwrap()
+                        .run(Element::Barrier(barrier));
+                }
+            }
+        }
+    }
+
+    fn close(&mut self) -> anyhow::Result<()> {
+        for (_job_id, stream_key_by) in &mut self.stream_key_bys {
+            stream_key_by.operator_fn.close()?;
+        }
+        self.stream_process.operator_fn.close()?;
+        self.next_runnable.as_mut().unwrap().close()
+    }
+
+    fn set_next_runnable(&mut self, next_runnable: Option<Box<dyn Runnable>>) {
+        self.next_runnable = next_runnable;
+    }
+
+    fn checkpoint(&mut self, checkpoint_id: CheckpointId) {
+        let snapshot = KeyedProcessSnapshot {
+            timers: self.timer_service.snapshot(),
+            states: self.state_store.snapshot(),
+        };
+        let handle = match serde_json::to_string(&snapshot) {
+            Ok(handle) => handle,
+            Err(e) => {
+                error!("snapshot the keyed states and timers error. {}", e);
+                return;
+            }
+        };
+
+        let ck = Checkpoint {
+            operator_id: self.operator_id,
+            task_id: self.task_id,
+            checkpoint_id,
+            handle: CheckpointHandle { handle },
+        };
+        report_checkpoint(ck);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api;
+    use crate::api::element::{types, Element, Record};
+    use crate::api::function::{Context, Function, KeyedProcessFunction};
+    use crate::api::operator::{DefaultStreamOperator, FunctionCreator};
+    use crate::api::runtime::OperatorId;
+    use crate::api::state::{KeyedProcessContext, TimeDomain};
+    use crate::api::watermark::MAX_WATERMARK;
+    use crate::runtime::worker::runnable::keyed_process_runnable::KeyedProcessRunnable;
+    use crate::runtime::worker::runnable::tests::CollectRunnable;
+    use crate::runtime::worker::runnable::Runnable;
+
+    const PROCESSING_TIMER: u64 = 1000;
+
+    /// register an event time timer 10ms after the record and a processing time timer,
+    /// the event time timers are chained every 50ms until 100
+    struct TimerProcessFunction {}
+
+    impl KeyedProcessFunction for TimerProcessFunction {
+        fn open(&mut self, _context: &Context) -> api::Result<()> {
+            Ok(())
+        }
+
+        fn process_element(
+            &mut self,
+            record: Record,
+            context: &mut KeyedProcessContext,
+        ) -> Box<dyn Iterator<Item = Record>> {
+            let timer_service = context.timer_service();
+            timer_service.register_event_time_timer(record.timestamp + 10);
+            timer_service.register_processing_time_timer(PROCESSING_TIMER);
+            Box::new(std::iter::empty())
+        }
+
+        fn on_timer(
+            &mut self,
+            timestamp: u64,
+            time_domain: TimeDomain,
+            context: &mut KeyedProcessContext,
+        ) -> Box<dyn Iterator<Item = Record>> {
+            let value = match time_domain {
+                TimeDomain::EventTime => {
+                    if timestamp < 100 {
+                        context
+                            .timer_service()
+                            .register_event_time_timer(timestamp + 50);
+                    }
+                    timestamp as i64
+                }
+                TimeDomain::ProcessingTime => -(timestamp as i64),
+            };
+
+            let mut record = Record::new();
+            record.get_writer(&[types::I64]).set_i64(value).unwrap();
+            Box::new(vec![record].into_iter())
+        }
+
+        fn close(&mut self) -> api::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Function for TimerProcessFunction {
+        fn get_name(&self) -> &str {
+            "TimerProcessFunction"
+        }
+    }
+
+    #[test]
+    pub fn end_of_input_flush_test() {
+        let collect = CollectRunnable::default();
+        let stream_process = DefaultStreamOperator::new(
+            1,
+            FunctionCreator::User,
+            Box::new(TimerProcessFunction {}) as Box<dyn KeyedProcessFunction>,
+        );
+        let mut runnable = KeyedProcessRunnable::new(
+            OperatorId(1),
+            vec![],
+            stream_process,
+            Some(Box::new(collect.clone())),
+        );
+
+        let mut record = Record::new();
+        record.get_writer(&[types::I64]).set_i64(0).unwrap();
+        record.timestamp = 5;
+        runnable.run(Element::Record(record));
+        assert!(collect.take().is_empty());
+
+        // all the timers fire, including the ones registered by the firing timers,
+        // before the end of the input is forwarded
+        runnable.run(Element::new_stream_status(MAX_WATERMARK.timestamp, true));
+        let mut elements = collect.take();
+        let end_of_input = elements.pop().unwrap();
+        assert!(end_of_input.is_end_of_input());
+
+        let values: Vec<i64> = elements
+            .into_iter()
+            .map(|element| {
+                let mut record = element.into_record();
+                record.get_reader(&[types::I64]).get_i64(0).unwrap()
+            })
+            .collect();
+        assert_eq!(values, vec![15, 65, 115, -(PROCESSING_TIMER as i64)]);
+    }
+}
@@ -3,6 +3,7 @@ use std::time::Duration;
 
 use crate::api::checkpoint::FunctionSnapshotContext;
 use crate::api::element::Element;
+use crate::api::output_tag::OutputTag;
 use crate::api::properties::SystemProperties;
 use crate::api::runtime::{CheckpointId, OperatorId};
 use crate::dag::job_graph::{JobEdge, JobNode};
@@ -12,19 +13,27 @@ use crate::runtime::timer::WindowTimer;
 use crate::runtime::worker::FunctionContext;
 use crate::runtime::{ApplicationDescriptor, TaskDescriptor};
 
+pub mod async_map_runnable;
+pub mod broadcast_process_runnable;
 pub mod co_process_runnable;
 pub mod filter_runnable;
 pub mod flat_map_runnable;
 pub mod key_by_runnable;
+pub mod keyed_process_runnable;
+pub mod partition_runnable;
 pub mod reduce_runnable;
 pub mod sink_runnable;
 pub mod source_runnable;
 pub mod watermark_assigner_runnable;
 pub mod window_assigner_runnable;
 
+pub(crate) use async_map_runnable::AsyncMapRunnable;
+pub(crate) use broadcast_process_runnable::BroadcastProcessRunnable;
 pub(crate) use filter_runnable::FilterRunnable;
 pub(crate) use flat_map_runnable::FlatMapRunnable;
 pub(crate) use key_by_runnable::KeyByRunnable;
+pub(crate) use keyed_process_runnable::KeyedProcessRunnable;
+pub(crate) use partition_runnable::PartitionRunnable;
 pub(crate) use reduce_runnable::ReduceRunnable;
 pub(crate) use sink_runnable::SinkRunnable;
 pub(crate) use source_runnable::SourceRunnable;
@@ -48,7 +57,7 @@ impl RunnableContext {
             operator_id,
             task_id: self.task_descriptor.task_id.clone(),
             checkpoint_id: self.task_descriptor.checkpoint_id,
-            checkpoint_handle: self.task_descriptor.checkpoint_handle.clone(),
+            checkpoint_handle: self.task_descriptor.get_checkpoint_handle(operator_id),
 
             parents: self
                 .dag_manager
@@ -101,6 +110,14 @@ impl RunnableContext {
             .collect()
     }
 
+    /// the number of the parent tasks connected to the task, it sends the watermarks
+    /// and the end of the input from each of them
+    pub(crate) fn get_num_inputs(&self) -> usize {
+        self.dag_manager
+            .get_task_parents(&self.task_descriptor.task_id)
+            .len()
+    }
+
     pub(crate) fn get_parent_jobs(&self) -> Vec<(JobNode, JobEdge)> {
         self.dag_manager
             .get_job_parents(self.task_descriptor.task_id.job_id)
@@ -116,6 +133,24 @@ impl RunnableContext {
     pub(crate) fn get_stream(&self, operator_id: OperatorId) -> StreamNode {
         self.dag_manager.get_stream(operator_id).unwrap()
     }
+
+    pub(crate) fn get_side_outputs(&self, operator_id: OperatorId) -> Vec<OutputTag> {
+        self.dag_manager
+            .stream_graph()
+            .get_side_outputs(operator_id)
+    }
+}
+
+/// the records of the main output are always consumed,
+/// the records of a side output are consumed only if the side output is subscribed
+pub(crate) fn is_consumed(side_outputs: &[OutputTag], element: &Element) -> bool {
+    match element {
+        Element::Record(record) => match &record.output_tag {
+            Some(output_tag) => side_outputs.contains(output_tag),
+            None => true,
+        },
+        _ => true,
+    }
 }
 
 pub(crate) trait Runnable: Debug {
@@ -125,3 +160,42 @@ pub(crate) trait Runnable: Debug {
     fn set_next_runnable(&mut self, next_runnable: Option<Box<dyn Runnable>>);
     fn checkpoint(&mut self, checkpoint_id: CheckpointId);
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::api::element::Element;
+    use crate::api::runtime::CheckpointId;
+    use crate::runtime::worker::runnable::{Runnable, RunnableContext};
+
+    /// the last runnable of the chain in the tests, collect the elements it runs
+    #[derive(Debug, Clone, Default)]
+    pub(crate) struct CollectRunnable {
+        pub(crate) elements: Arc<Mutex<Vec<Element>>>,
+    }
+
+    impl CollectRunnable {
+        pub(crate) fn take(&self) -> Vec<Element> {
+            self.elements.lock().unwrap().drain(..).collect()
+        }
+    }
+
+    impl Runnable for CollectRunnable {
+        fn open(&mut self, _context: &RunnableContext) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn run(&mut self, element: Element) {
+            self.elements.lock().unwrap().push(element);
+        }
+
+        fn close(&mut self) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn set_next_runnable(&mut self, _next_runnable: Option<Box<dyn Runnable>>) {}
+
+        fn checkpoint(&mut self, _checkpoint_id: CheckpointId) {}
+    }
+}
@@ -0,0 +1,111 @@
+use std::borrow::BorrowMut;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use crate::api::element::Element;
+use crate::api::function::{Distribution, Partitioner};
+use crate::api::operator::DefaultStreamOperator;
+use crate::api::runtime::{CheckpointId, OperatorId};
+use crate::metrics::{register_counter, Tag};
+use crate::runtime::worker::runnable::{Runnable, RunnableContext};
+
+#[derive(Debug)]
+pub(crate) struct PartitionRunnable {
+    operator_id: OperatorId,
+    stream_partition: DefaultStreamOperator<dyn Partitioner>,
+    distribution: Distribution,
+    next_runnable: Option<Box<dyn Runnable>>,
+    partition_size: u16,
+
+    counter: Arc<AtomicU64>,
+}
+
+impl PartitionRunnable {
+    pub fn new(
+        operator_id: OperatorId,
+        stream_partition: DefaultStreamOperator<dyn Partitioner>,
+        distribution: Distribution,
+        next_runnable: Option<Box<dyn Runnable>>,
+    ) -> Self {
+        info!("Create PartitionRunnable");
+
+        PartitionRunnable {
+            operator_id,
+            stream_partition,
+            distribution,
+            next_runnable,
+            partition_size: 0,
+            counter: Arc::new(AtomicU64::new(0)),
+        }
+    }
+}
+
+impl Runnable for PartitionRunnable {
+    fn open(&mut self, context: &RunnableContext) -> anyhow::Result<()> {
+        self.next_runnable.as_mut().unwrap().open(context)?;
+
+        let fun_context = context.to_fun_context(self.operator_id);
+        self.stream_partition.operator_fn.open(&fun_context)?;
+
+        self.partition_size = context.get_child_parallelism() as u16;
+
+        let tags = vec![
+            Tag(
+                "job_id".to_string(),
+                context.task_descriptor.task_id.job_id.0.to_string(),
+            ),
+            Tag(
+                "task_number".to_string(),
+                context.task_descriptor.task_id.task_number.to_string(),
+            ),
+        ];
+        let metric_name = format!(
+            "Partition_{}",
+            self.stream_partition.operator_fn.as_ref().get_name()
+        );
+        register_counter(metric_name.as_str(), tags, self.counter.clone());
+
+        Ok(())
+    }
+
+    fn run(&mut self, mut element: Element) {
+        match element.borrow_mut() {
+            Element::Record(record) => {
+                let partitioner = self.stream_partition.operator_fn.as_mut();
+                if self.distribution == Distribution::Broadcast {
+                    // replicate the record, each replica is routed to another partition
+                    for _ in 0..self.partition_size {
+                        let mut record = record.clone();
+                        record.partition_num =
+                            partitioner.partition(&mut record, self.partition_size);
+                        self.next_runnable
+                            .as_mut()
+                            .unwrap()
+                            .run(Element::Record(record));
+                    }
+                } else {
+                    record.partition_num = partitioner.partition(record, self.partition_size);
+                    self.next_runnable.as_mut().unwrap().run(element);
+                }
+
+                self.counter.fetch_add(1, Ordering::Relaxed);
+            }
+            Element::Watermark(_) | Element::Barrier(_) | Element::StreamStatus(_) => {
+                // broadcast to all partitions by the `SystemOutputFormat`
+                self.next_runnable.as_mut().unwrap().run(element);
+            }
+        }
+    }
+
+    fn close(&mut self) -> anyhow::Result<()> {
+        self.stream_partition.operator_fn.close()?;
+        self.next_runnable.as_mut().unwrap().close()
+    }
+
+    fn set_next_runnable(&mut self, next_runnable: Option<Box<dyn Runnable>>) {
+        self.next_runnable = next_runnable;
+    }
+
+    fn checkpoint(&mut self, _checkpoint_id: CheckpointId) {}
+}
@@ -5,13 +5,19 @@ use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 use crate::api::backend::KeyedStateBackend;
+use crate::api::checkpoint::{Checkpoint, CheckpointHandle};
 use crate::api::element::{Barrier, Element, Record, Watermark};
-use crate::api::function::{KeySelectorFunction, ReduceFunction};
+use crate::api::function::{AggregateFunction, KeySelectorFunction};
 use crate::api::operator::DefaultStreamOperator;
+use crate::api::output_tag::OutputTag;
 use crate::api::properties::SystemProperties;
-use crate::api::runtime::{CheckpointId, OperatorId};
-use crate::api::window::{TWindow, Window};
+use crate::api::runtime::{CheckpointId, OperatorId, TaskId};
+use crate::api::state::TimeDomain;
+use crate::api::trigger::{Trigger, TriggerContext, TriggerResult, TriggerSnapshot};
+use crate::api::watermark::MAX_WATERMARK;
+use crate::api::window::{TWindow, Window, WindowAssignerContext};
 use crate::metrics::{register_counter, Tag};
+use crate::runtime::worker::checkpoint::report_checkpoint;
 use crate::runtime::worker::runnable::{Runnable, RunnableContext};
 use crate::storage::keyed_state::{TWindowState, WindowState};
 use crate::utils::date_time::timestamp_str;
@@ -19,13 +25,25 @@ use crate::utils::date_time::timestamp_str;
 #[derive(Debug)]
 pub(crate) struct ReduceRunnable {
     operator_id: OperatorId,
-    task_number: u16,
+    task_id: TaskId,
     dependency_parallelism: u16,
 
     stream_key_by: Option<DefaultStreamOperator<dyn KeySelectorFunction>>,
-    stream_reduce: DefaultStreamOperator<dyn ReduceFunction>,
+    stream_reduce: DefaultStreamOperator<dyn AggregateFunction>,
     next_runnable: Option<Box<dyn Runnable>>,
 
+    trigger: Box<dyn Trigger>,
+    trigger_context: TriggerContext,
+    /// keep the event time windows for a grace period after the watermark passes the window
+    allowed_lateness: u64,
+    /// the side output of the records arriving after the allowed lateness
+    late_data_output_tag: Option<OutputTag>,
+
+    /// clean up windows by processing time instead of watermarks
+    processing_time_window: bool,
+    /// merge the intersecting windows of a key, eg: session windows
+    merging_window: bool,
+
     state: Option<WindowState>, // HashMap<Vec<u8>, Record>, // HashMap<TimeWindow, HashMap<Record, Record>>,
 
     current_checkpoint_id: CheckpointId,
@@ -44,16 +62,27 @@ impl ReduceRunnable {
     pub fn new(
         operator_id: OperatorId,
         stream_key_by: Option<DefaultStreamOperator<dyn KeySelectorFunction>>,
-        stream_reduce: DefaultStreamOperator<dyn ReduceFunction>,
+        stream_reduce: DefaultStreamOperator<dyn AggregateFunction>,
+        trigger: Box<dyn Trigger>,
+        allowed_lateness: u64,
+        late_data_output_tag: Option<OutputTag>,
+        processing_time_window: bool,
+        merging_window: bool,
         next_runnable: Option<Box<dyn Runnable>>,
     ) -> Self {
         ReduceRunnable {
             operator_id,
-            task_number: 0,
+            task_id: TaskId::default(),
             dependency_parallelism: 0,
             stream_key_by,
             stream_reduce,
             next_runnable,
+            trigger,
+            trigger_context: TriggerContext::new(),
+            allowed_lateness,
+            late_data_output_tag,
+            processing_time_window,
+            merging_window,
             state: None,
             current_checkpoint_id: CheckpointId::default(),
             reached_barriers: Vec::new(),
@@ -66,26 +95,254 @@ impl ReduceRunnable {
     }
 }
 
+impl ReduceRunnable {
+    /// the window is cleaned up once the clock passes the end of the window,
+    /// the event time window is kept for the `allowed_lateness` additionally
+    fn is_cleanup_time(&self, window: &Window) -> bool {
+        if self.processing_time_window {
+            window.max_timestamp() <= self.trigger_context.get_current_processing_time()
+        } else {
+            window.max_timestamp() + self.allowed_lateness
+                <= self.trigger_context.get_current_watermark()
+        }
+    }
+
+    /// remove the record's windows that have been cleaned up,
+    /// return `false` if there is no window left, the record is late
+    fn retain_acceptable_windows(&self, record: &mut Record) -> bool {
+        if self.processing_time_window {
+            return true;
+        }
+
+        match record.location_windows.as_mut() {
+            Some(windows) => {
+                let current_watermark = self.trigger_context.get_current_watermark();
+                let allowed_lateness = self.allowed_lateness;
+                windows
+                    .retain(|window| window.max_timestamp() + allowed_lateness > current_watermark);
+                !windows.is_empty()
+            }
+            None => true,
+        }
+    }
+
+    /// drop the late record or route it to the late data side output
+    fn on_late_record(&mut self, mut record: Record) {
+        let n = self.expire_counter.fetch_add(1, Ordering::Relaxed);
+        if n & 1048575 == 1 {
+            error!(
+                "expire data. record timestamp={}, limit window={:?}",
+                timestamp_str(record.timestamp),
+                self.limited_watermark_window.as_ref()
+            );
+        }
+
+        if let Some(output_tag) = &self.late_data_output_tag {
+            record.location_windows = None;
+            record.output_tag = Some(output_tag.clone());
+            self.next_runnable
+                .as_mut()
+                .unwrap()
+                .run(Element::Record(record));
+        }
+    }
+
+    /// apply the `TriggerResult`s of the keys to the window's state at once,
+    /// and notify downstream to emit the fired keys of the window
+    fn on_trigger_results(
+        &mut self,
+        window: &Window,
+        results: Vec<(Record, TriggerResult)>,
+    ) -> bool {
+        // the keys past the cleanup time are moved to downstream instead of copying the state
+        let cleanup = self.is_cleanup_time(window);
+
+        let mut fire_keys = Vec::new();
+        let mut drop_keys = Vec::new();
+        let mut purge_keys = Vec::new();
+        for (key, trigger_result) in results {
+            if trigger_result == TriggerResult::Continue {
+                continue;
+            }
+
+            let purge = trigger_result.is_purge() || cleanup;
+            if purge {
+                self.trigger_context.set_current_key(key.clone());
+                self.trigger.clear(window, &mut self.trigger_context);
+            }
+
+            match (trigger_result.is_fire(), purge) {
+                (true, false) => fire_keys.push(key),
+                (true, true) => drop_keys.push(key),
+                (false, _) => purge_keys.push(key),
+            }
+        }
+
+        let state = self.state.as_mut().unwrap();
+        let aggregate_func = &self.stream_reduce.operator_fn;
+        let result_fun = |accumulator| aggregate_func.get_result(accumulator);
+
+        let mut fired = false;
+        if !fire_keys.is_empty() {
+            fired |= state.fire_keys(window, fire_keys.as_slice(), &result_fun);
+        }
+        if !drop_keys.is_empty() {
+            fired |= state.drop_keys(window, drop_keys.as_slice(), &result_fun);
+        }
+        if !purge_keys.is_empty() {
+            state.purge_keys(window, purge_keys.as_slice());
+        }
+
+        if fired {
+            let mut drop_record = Record::new();
+            drop_record.trigger_window = Some(window.clone());
+
+            self.next_runnable
+                .as_mut()
+                .unwrap()
+                .run(Element::from(drop_record));
+        }
+
+        fired
+    }
+
+    /// group the expired timers by window, so a window is emitted once for all its fired keys
+    fn on_timers(&mut self, timers: Vec<(u64, Record, Window)>, time_domain: TimeDomain) -> usize {
+        let mut windows = Vec::new();
+        let mut window_results: HashMap<Window, Vec<(Record, TriggerResult)>> = HashMap::new();
+        for (time, key, window) in timers {
+            self.trigger_context.set_current_key(key.clone());
+            let trigger_result = match time_domain {
+                TimeDomain::EventTime => {
+                    self.trigger
+                        .on_event_time(time, &window, &mut self.trigger_context)
+                }
+                TimeDomain::ProcessingTime => {
+                    self.trigger
+                        .on_processing_time(time, &window, &mut self.trigger_context)
+                }
+            };
+
+            if !window_results.contains_key(&window) {
+                windows.push(window.clone());
+            }
+            window_results
+                .entry(window)
+                .or_default()
+                .push((key, trigger_result));
+        }
+
+        let mut fired_windows = 0;
+        for window in windows {
+            let results = window_results.remove(&window).unwrap();
+            if self.on_trigger_results(&window, results) {
+                fired_windows += 1;
+            }
+        }
+        fired_windows
+    }
+
+    /// fire the event time timers that `timer <= watermark`
+    fn on_event_time(&mut self, watermark: u64) {
+        self.trigger_context.current_watermark = watermark;
+
+        let timers = self.trigger_context.poll_event_time_timers(watermark);
+        let fired_windows = self.on_timers(timers, TimeDomain::EventTime);
+        if fired_windows > 0 {
+            debug!(
+                "check window for fire, watermark={}, fired window size={}",
+                timestamp_str(watermark),
+                fired_windows
+            );
+        }
+
+        if !self.processing_time_window {
+            self.cleanup_windows();
+        }
+    }
+
+    /// fire the processing time timers that `timer <= processing_time`
+    fn on_processing_time(&mut self, processing_time: u64) {
+        self.trigger_context.current_processing_time = processing_time;
+
+        let timers = self
+            .trigger_context
+            .poll_processing_time_timers(processing_time);
+        let fired_windows = self.on_timers(timers, TimeDomain::ProcessingTime);
+        if fired_windows > 0 {
+            debug!(
+                "check window for fire, processing time={}, fired window size={}",
+                timestamp_str(processing_time),
+                fired_windows
+            );
+        }
+
+        if self.processing_time_window {
+            self.cleanup_windows();
+        }
+    }
+
+    /// fire all the windows when the input ends, as if the clock reaches the end of time
+    fn on_end_of_input(&mut self) {
+        if self.processing_time_window {
+            self.on_processing_time(MAX_WATERMARK.timestamp);
+        } else {
+            self.on_event_time(MAX_WATERMARK.timestamp);
+        }
+        info!("ReduceRunnable flush all windows at the end of input");
+    }
+
+    fn restore_state(&mut self, handle: &Option<CheckpointHandle>) {
+        if let Some(handle) = handle {
+            match serde_json::from_str::<TriggerSnapshot>(handle.handle.as_str()) {
+                Ok(snapshot) => self.trigger_context.restore(snapshot),
+                Err(e) => error!("restore the trigger timers and states error. {}", e),
+            }
+        }
+    }
+
+    /// purge the windows that are past the cleanup time and not fired by the trigger
+    fn cleanup_windows(&mut self) {
+        let windows = self.state.as_ref().unwrap().windows();
+        for window in windows {
+            if self.is_cleanup_time(&window) {
+                let state = self.state.as_mut().unwrap();
+                for key in state.keys(&window) {
+                    self.trigger_context.set_current_key(key);
+                    self.trigger.clear(&window, &mut self.trigger_context);
+                }
+                state.purge_window(&window);
+            }
+        }
+    }
+}
+
 impl Runnable for ReduceRunnable {
     fn open(&mut self, context: &RunnableContext) -> anyhow::Result<()> {
         self.next_runnable.as_mut().unwrap().open(context)?;
 
+        self.task_id = context.task_descriptor.task_id;
+
         let fun_context = context.to_fun_context(self.operator_id);
         self.stream_reduce.operator_fn.open(&fun_context)?;
         self.stream_key_by
             .as_mut()
             .map(|s| s.operator_fn.open(&fun_context));
 
-        self.task_number = context.task_descriptor.task_id.task_number;
         self.dependency_parallelism = context.get_parent_parallelism();
 
         self.watermark_align = Some(WatermarkAlign::new());
 
         info!(
             "ReduceRunnable Opened. task_number={}, num_tasks={}",
-            self.task_number, context.task_descriptor.task_id.num_tasks
+            self.task_id.task_number, self.task_id.num_tasks
         );
 
+        let checkpoint_handle = context
+            .task_descriptor
+            .get_checkpoint_handle(self.operator_id);
+        self.restore_state(&checkpoint_handle);
+
         let state_mode = context
             .application_descriptor
             .coordinator_manager
@@ -126,43 +383,72 @@ impl Runnable for ReduceRunnable {
     }
 
     fn run(&mut self, element: Element) {
-        let state = self.state.as_mut().unwrap();
         match element {
             Element::Record(mut record) => {
                 // Record expiration check
-                let acceptable = self
-                    .limited_watermark_window
-                    .as_ref()
-                    .map(|limit_window| {
-                        record
-                            .get_max_location_windows()
-                            .map(|window| window.min_timestamp() >= limit_window.min_timestamp())
-                            .unwrap_or(true)
-                    })
-                    .unwrap_or(true);
-                if !acceptable {
-                    let n = self.expire_counter.fetch_add(1, Ordering::Relaxed);
-                    if n & 1048575 == 1 {
-                        error!(
-                            "expire data. record window={:?}, limit window={:?}",
-                            record.get_min_location_windows().unwrap(),
-                            self.limited_watermark_window.as_ref().unwrap()
-                        );
-                    }
+                if !self.retain_acceptable_windows(&mut record) {
+                    self.on_late_record(record);
                     return;
                 }
 
+                let state = self.state.as_mut().unwrap();
                 let key = match &self.stream_key_by {
                     Some(stream_key_by) => stream_key_by.operator_fn.get_key(record.borrow_mut()),
                     None => Record::with_capacity(0),
                 };
-
-                let reduce_func = &self.stream_reduce.operator_fn;
-                state.merge(key, record, |val1, val2| reduce_func.reduce(val1, val2));
+                self.trigger_context.set_current_key(key.clone());
+
+                let timestamp = record.timestamp;
+                let aggregate_func = &self.stream_reduce.operator_fn;
+                let create_fun = || aggregate_func.create_accumulator();
+                let add_fun =
+                    |acc: &mut Record, record: &mut Record| aggregate_func.add(acc, record);
+                let windows = if self.merging_window {
+                    let merged_windows = state.merge_windows(
+                        key.clone(),
+                        record,
+                        create_fun,
+                        add_fun,
+                        |acc, other| aggregate_func.merge(acc, other),
+                    );
+
+                    // move the trigger state and timers of the merged windows to the new one
+                    let mut windows = Vec::with_capacity(merged_windows.len());
+                    for (window, merged) in merged_windows {
+                        if !merged.is_empty() {
+                            self.trigger.on_merge(
+                                &window,
+                                merged.as_slice(),
+                                &mut self.trigger_context,
+                            );
+                            for merged_window in &merged {
+                                self.trigger.clear(merged_window, &mut self.trigger_context);
+                            }
+                        }
+                        windows.push(window);
+                    }
+                    windows
+                } else {
+                    let windows = record.get_location_windows().clone();
+                    state.merge(key.clone(), record, create_fun, add_fun);
+                    windows
+                };
 
                 self.counter.fetch_add(1, Ordering::Relaxed);
+
+                for window in windows {
+                    let trigger_result =
+                        self.trigger
+                            .on_element(timestamp, &window, &mut self.trigger_context);
+                    self.on_trigger_results(&window, vec![(key.clone(), trigger_result)]);
+                }
             }
             Element::Watermark(watermark) => {
+                if self.processing_time_window {
+                    // processing time windows are fired by the `WindowTimer`
+                    return;
+                }
+
                 let watermark_status_timestamp = watermark.status_timestamp;
 
                 let watermark_align = self.watermark_align.as_mut().unwrap();
@@ -183,52 +469,19 @@ impl Runnable for ReduceRunnable {
 
                     // info!("minimum_watermark_window: {:?}", minimum_watermark_window);
 
-                    let mut drop_windows = Vec::new();
-
-                    // info!("begin drop window");
-                    for window in state.windows() {
-                        // info!(
-                        //     "check window [{}/{}]",
-                        //     timestamp_str(window.min_timestamp()),
-                        //     timestamp_str(window.max_timestamp()),
-                        // );
-                        if window.max_timestamp() <= minimum_watermark_window.min_timestamp() {
-                            drop_windows.push(window.clone());
-                            state.drop_window(&window);
-
-                            // info!(
-                            //     "drop window [{}/{}]",
-                            //     timestamp_str(window.min_timestamp()),
-                            //     timestamp_str(window.max_timestamp()),
-                            // );
-                        }
-                    }
-
-                    drop_windows.sort_by_key(|w| w.max_timestamp());
-
-                    if drop_windows.len() > 0 {
-                        debug!(
-                            "check window for drop, trigger watermark={}, drop window size={}",
-                            timestamp_str(minimum_watermark_window.max_timestamp()),
-                            drop_windows.len()
-                        );
-
-                        // align_watermark.drop_windows = Some(drop_windows);
-                        // self.next_runnable
-                        //     .as_mut()
-                        //     .unwrap()
-                        //     .run(Element::from(align_watermark));
-
-                        for drop_window in drop_windows {
-                            let mut drop_record = Record::new();
-                            drop_record.trigger_window = Some(drop_window);
-
-                            self.next_runnable
-                                .as_mut()
-                                .unwrap()
-                                .run(Element::from(drop_record));
-                        }
-                    }
+                    self.on_event_time(minimum_watermark_window.min_timestamp());
+                }
+            }
+            Element::StreamStatus(stream_status) => {
+                if stream_status.end {
+                    self.on_end_of_input();
+                    self.next_runnable
+                        .as_mut()
+                        .unwrap()
+                        .run(Element::StreamStatus(stream_status));
+                } else {
+                    let processing_time = WindowAssignerContext {}.get_current_processing_time();
+                    self.on_processing_time(processing_time);
                 }
             }
             Element::Barrier(barrier) => {
@@ -240,10 +493,10 @@ impl Runnable for ReduceRunnable {
                     self.reached_barriers.push(barrier);
                     if self.reached_barriers.len() == self.dependency_parallelism as usize {
                         self.checkpoint(self.current_checkpoint_id);
-                    }
 
-                    self.current_checkpoint_id = CheckpointId::default();
-                    self.reached_barriers.clear();
+                        self.current_checkpoint_id = CheckpointId::default();
+                        self.reached_barriers.clear();
+                    }
                 } else {
                     if self.current_checkpoint_id.0 > barrier.checkpoint_id.0 {
                         error!(
@@ -262,7 +515,6 @@ impl Runnable for ReduceRunnable {
                     }
                 }
             }
-            _ => {}
         }
     }
 
@@ -276,8 +528,22 @@ impl Runnable for ReduceRunnable {
         self.next_runnable = next_runnable;
     }
 
-    fn checkpoint(&mut self, _checkpoint_id: CheckpointId) {
-        // foreach self.reached_barriers
+    fn checkpoint(&mut self, checkpoint_id: CheckpointId) {
+        let handle = match serde_json::to_string(&self.trigger_context.snapshot()) {
+            Ok(handle) => handle,
+            Err(e) => {
+                error!("snapshot the trigger timers and states error. {}", e);
+                return;
+            }
+        };
+
+        let ck = Checkpoint {
+            operator_id: self.operator_id,
+            task_id: self.task_id,
+            checkpoint_id,
+            handle: CheckpointHandle { handle },
+        };
+        report_checkpoint(ck);
     }
 }
 
@@ -475,9 +741,203 @@ impl WatermarkAlign {
 
 #[cfg(test)]
 mod tests {
-    use crate::api::element::{StreamStatus, Watermark};
+    use crate::api;
+    use crate::api::backend::KeyedStateBackend;
+    use crate::api::element::{types, Element, Record, StreamStatus, Watermark};
+    use crate::api::function::{AggregateFunction, Context, Function};
+    use crate::api::operator::{DefaultStreamOperator, FunctionCreator};
+    use crate::api::runtime::{JobId, OperatorId};
+    use crate::api::trigger::{CountTrigger, EventTimeTrigger, Trigger};
+    use crate::api::watermark::MAX_WATERMARK;
     use crate::api::window::{TimeWindow, Window};
-    use crate::runtime::worker::runnable::reduce_runnable::WatermarkAlign;
+    use crate::runtime::worker::runnable::reduce_runnable::{ReduceRunnable, WatermarkAlign};
+    use crate::runtime::worker::runnable::tests::CollectRunnable;
+    use crate::runtime::worker::runnable::Runnable;
+    use crate::storage::keyed_state::mem_storage::remove_drop_window;
+    use crate::storage::keyed_state::{TWindowState, WindowState};
+
+    /// sum the `I64` values of the records
+    struct SumAggregateFunction {}
+
+    impl AggregateFunction for SumAggregateFunction {
+        fn open(&mut self, _context: &Context) -> api::Result<()> {
+            Ok(())
+        }
+
+        fn create_accumulator(&self) -> Record {
+            new_record(0, 0)
+        }
+
+        fn add(&self, accumulator: &mut Record, record: &mut Record) {
+            let sum = value(accumulator) + value(record);
+            *accumulator = new_record(sum, 0);
+        }
+
+        fn merge(&self, accumulator: &mut Record, other: &mut Record) {
+            let sum = value(accumulator) + value(other);
+            *accumulator = new_record(sum, 0);
+        }
+
+        fn get_result(&self, accumulator: Record) -> Record {
+            accumulator
+        }
+
+        fn close(&mut self) -> api::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Function for SumAggregateFunction {
+        fn get_name(&self) -> &str {
+            "SumAggregateFunction"
+        }
+    }
+
+    fn new_record(value: i64, timestamp: u64) -> Record {
+        let mut record = Record::new();
+        record.get_writer(&[types::I64]).set_i64(value).unwrap();
+        record.timestamp = timestamp;
+        record
+    }
+
+    fn value(record: &mut Record) -> i64 {
+        record.get_reader(&[types::I64]).get_i64(0).unwrap()
+    }
+
+    fn window_record(value: i64, timestamp: u64, window: TimeWindow) -> Element {
+        let mut record = new_record(value, timestamp);
+        record.set_location_windows(vec![Window::TimeWindow(window)]);
+        Element::Record(record)
+    }
+
+    fn session_record(value: i64, timestamp: u64, gap: u64) -> Element {
+        window_record(
+            value,
+            timestamp,
+            TimeWindow::new(timestamp, timestamp + gap),
+        )
+    }
+
+    /// the runnable is not opened, so the state is created as `open` does
+    fn reduce_runnable(
+        job_id: JobId,
+        trigger: Box<dyn Trigger>,
+        merging_window: bool,
+    ) -> (ReduceRunnable, CollectRunnable) {
+        let collect = CollectRunnable::default();
+        let stream_reduce = DefaultStreamOperator::new(
+            1,
+            FunctionCreator::User,
+            Box::new(SumAggregateFunction {}) as Box<dyn AggregateFunction>,
+        );
+        let mut runnable = ReduceRunnable::new(
+            OperatorId(1),
+            None,
+            stream_reduce,
+            trigger,
+            0,
+            None,
+            false,
+            merging_window,
+            Some(Box::new(collect.clone())),
+        );
+        runnable.watermark_align = Some(WatermarkAlign::new());
+        runnable.state = Some(WindowState::new(
+            "app".to_string(),
+            job_id,
+            0,
+            KeyedStateBackend::Memory,
+        ));
+
+        (runnable, collect)
+    }
+
+    /// the windows notified to downstream and the fired results of them
+    fn fired_windows(job_id: JobId, collect: &CollectRunnable) -> Vec<(Window, i64)> {
+        collect
+            .take()
+            .into_iter()
+            .filter(|element| element.is_record())
+            .map(|element| {
+                let window = element.into_record().trigger_window.unwrap();
+                let state = remove_drop_window(job_id, 0, window.clone()).unwrap();
+                let mut record = state.iter().next().unwrap();
+                (window, value(&mut record))
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn session_merge_count_trigger_test() {
+        let job_id = JobId(110);
+        let (mut runnable, collect) = reduce_runnable(job_id, Box::new(CountTrigger::of(3)), true);
+
+        runnable.run(session_record(1, 0, 10));
+        runnable.run(session_record(2, 20, 10));
+        assert!(fired_windows(job_id, &collect).is_empty());
+
+        // the record bridges the two sessions, the counts of them are merged into
+        // the covering window, so the third record fires it
+        runnable.run(session_record(3, 10, 10));
+        let merged_window = Window::TimeWindow(TimeWindow::new(0, 30));
+        assert_eq!(
+            fired_windows(job_id, &collect),
+            vec![(merged_window.clone(), 6)]
+        );
+
+        // the trigger state of the merged windows is cleared
+        let trigger_context = &runnable.trigger_context;
+        for window in &[TimeWindow::new(0, 10), TimeWindow::new(20, 30)] {
+            let window = Window::TimeWindow(window.clone());
+            assert_eq!(trigger_context.get_state(&window, "count"), None);
+        }
+        assert_eq!(trigger_context.get_state(&merged_window, "count"), None);
+
+        // the count restarts after firing
+        runnable.run(session_record(4, 25, 10));
+        assert!(fired_windows(job_id, &collect).is_empty());
+        assert_eq!(
+            runnable.trigger_context.get_state(&merged_window, "count"),
+            None
+        );
+        let merged_window = Window::TimeWindow(TimeWindow::new(0, 35));
+        assert_eq!(
+            runnable.trigger_context.get_state(&merged_window, "count"),
+            Some(1)
+        );
+    }
+
+    #[test]
+    pub fn end_of_input_flush_test() {
+        let job_id = JobId(111);
+        let (mut runnable, collect) =
+            reduce_runnable(job_id, Box::new(EventTimeTrigger::new()), false);
+
+        runnable.run(window_record(1, 2, TimeWindow::new(0, 10)));
+        runnable.run(window_record(2, 5, TimeWindow::new(0, 10)));
+        runnable.run(window_record(4, 12, TimeWindow::new(10, 20)));
+
+        // no watermark reaches the windows, they are fired by the end of the input
+        runnable.run(Element::new_stream_status(MAX_WATERMARK.timestamp, true));
+
+        let elements = collect.elements.lock().unwrap().clone();
+        assert_eq!(elements.len(), 3);
+        assert!(elements[2].is_end_of_input());
+        assert_eq!(
+            fired_windows(job_id, &collect),
+            vec![
+                (Window::TimeWindow(TimeWindow::new(0, 10)), 3),
+                (Window::TimeWindow(TimeWindow::new(10, 20)), 4),
+            ]
+        );
+
+        // the windows and their timers are cleaned up
+        assert!(runnable.state.as_ref().unwrap().windows().is_empty());
+        assert!(runnable
+            .trigger_context
+            .poll_event_time_timers(MAX_WATERMARK.timestamp)
+            .is_empty());
+    }
 
     #[test]
     pub fn watermark_align_test() {
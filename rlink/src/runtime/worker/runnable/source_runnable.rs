@@ -8,6 +8,7 @@ use crate::api::element::Element;
 use crate::api::function::{InputFormat, InputSplit};
 use crate::api::operator::{DefaultStreamOperator, FunctionCreator, TStreamOperator};
 use crate::api::runtime::{CheckpointId, OperatorId, TaskId};
+use crate::api::watermark::MAX_WATERMARK;
 use crate::channel::named_channel;
 use crate::channel::sender::ChannelSender;
 use crate::metrics::Tag;
@@ -25,8 +26,14 @@ pub(crate) struct SourceRunnable {
     stream_source: DefaultStreamOperator<dyn InputFormat>,
     next_runnable: Option<Box<dyn Runnable>>,
 
+    /// fire the processing time windows and timers of this job by `StreamStatus` events
+    processing_time_window: bool,
+
     stream_status_timer: Option<TimerChannel>,
     checkpoint_timer: Option<TimerChannel>,
+
+    /// the number of the inputs, the source ends once all the inputs have ended
+    num_inputs: usize,
 }
 
 impl SourceRunnable {
@@ -34,6 +41,7 @@ impl SourceRunnable {
         operator_id: OperatorId,
         input_split: InputSplit,
         stream_source: DefaultStreamOperator<dyn InputFormat>,
+        processing_time_window: bool,
         next_runnable: Option<Box<dyn Runnable>>,
     ) -> Self {
         info!("Create SourceRunnable input_split={:?}", &input_split);
@@ -45,15 +53,20 @@ impl SourceRunnable {
             stream_source,
             next_runnable,
 
+            processing_time_window,
+
             stream_status_timer: None,
             checkpoint_timer: None,
+
+            num_inputs: 0,
         }
     }
 
     fn poll_input_element(&mut self, sender: ChannelSender<Element>, running: Arc<AtomicBool>) {
         let iterator = self.stream_source.operator_fn.element_iter();
+        let num_inputs = self.num_inputs;
         crate::utils::thread::spawn("poll_input_element", move || {
-            match SourceRunnable::poll_input_element0(iterator, sender, running) {
+            match SourceRunnable::poll_input_element0(iterator, sender, running, num_inputs) {
                 Ok(_) => {}
                 Err(e) => panic!("poll_input_element thread error. {}", e),
             }
@@ -64,12 +77,31 @@ impl SourceRunnable {
         iterator: Box<dyn Iterator<Item = Element> + Send>,
         sender: ChannelSender<Element>,
         running: Arc<AtomicBool>,
+        num_inputs: usize,
     ) -> anyhow::Result<()> {
-        for record in iterator {
-            sender.send(record).map_err(|e| anyhow!(e))?;
+        let mut ended_inputs = 0;
+        for element in iterator {
+            // stop polling once all the inputs end, the upstream channels may be closed after
+            if element.is_end_of_input() {
+                ended_inputs += 1;
+                if ended_inputs < num_inputs {
+                    continue;
+                }
+                break;
+            }
+
+            sender.send(element).map_err(|e| anyhow!(e))?;
         }
 
         running.store(false, Ordering::Relaxed);
+
+        // the end of all the inputs, it is the last element of the source.
+        // the end status of all the tasks shares the timestamp, so that the final watermarks
+        // are aligned by the downstream
+        let stream_status = Element::new_stream_status(MAX_WATERMARK.timestamp, true);
+        sender.send(stream_status).map_err(|e| anyhow!(e))?;
+
+        info!("input element finish");
         Ok(())
     }
 
@@ -89,15 +121,20 @@ impl SourceRunnable {
         running: Arc<AtomicBool>,
     ) -> anyhow::Result<()> {
         loop {
-            let running = running.load(Ordering::Relaxed);
             let window_time = stream_status_timer.recv().map_err(|e| anyhow!(e))?;
-
-            let stream_status = Element::new_stream_status(window_time, !running);
-            sender.send(stream_status).map_err(|e| anyhow!(e))?;
-
-            if !running {
+            if !running.load(Ordering::Relaxed) {
                 info!("StreamStatus WindowTimer stop");
-                // break;
+                return Ok(());
+            }
+
+            let stream_status = Element::new_stream_status(window_time, false);
+            if let Err(e) = sender.send(stream_status) {
+                // the source may end between the check and the sending
+                return if running.load(Ordering::Relaxed) {
+                    Err(anyhow!(e))
+                } else {
+                    Ok(())
+                };
             }
         }
     }
@@ -118,16 +155,20 @@ impl SourceRunnable {
         running: Arc<AtomicBool>,
     ) -> anyhow::Result<()> {
         loop {
-            let running = running.load(Ordering::Relaxed);
-
             let window_time = checkpoint_timer.recv().map_err(|e| anyhow!(e))?;
+            if !running.load(Ordering::Relaxed) {
+                info!("Checkpoint WindowTimer stop");
+                return Ok(());
+            }
 
             let barrier = Element::new_barrier(CheckpointId(window_time));
-            sender.send(barrier).map_err(|e| anyhow!(e))?;
-
-            if !running {
-                info!("Checkpoint WindowTimer stop");
-                // break;
+            if let Err(e) = sender.send(barrier) {
+                // the source may end between the check and the sending
+                return if running.load(Ordering::Relaxed) {
+                    Err(anyhow!(e))
+                } else {
+                    Ok(())
+                };
             }
         }
     }
@@ -147,6 +188,12 @@ impl Runnable for SourceRunnable {
         let source_func = self.stream_source.operator_fn.as_mut();
         source_func.open(input_split, &fun_context)?;
 
+        // the system source receives the end of the input from each parent task
+        self.num_inputs = match self.stream_source.get_fn_creator() {
+            FunctionCreator::User => 1,
+            FunctionCreator::System => context.get_num_inputs(),
+        };
+
         if let FunctionCreator::User = self.stream_source.get_fn_creator() {
             let stream_status_timer = context
                 .window_timer
@@ -160,6 +207,12 @@ impl Runnable for SourceRunnable {
                 .register("Checkpoint Event Timer", checkpoint_period)
                 .expect("register Checkpoint timer error");
             self.checkpoint_timer = Some(checkpoint_timer);
+        } else if self.processing_time_window {
+            let stream_status_timer = context
+                .window_timer
+                .register("Window ProcessingTime Timer", Duration::from_secs(1))
+                .expect("register ProcessingTime timer error");
+            self.stream_status_timer = Some(stream_status_timer);
         }
 
         info!("Operator(SourceOperator) open");
@@ -184,8 +237,10 @@ impl Runnable for SourceRunnable {
         let running = Arc::new(AtomicBool::new(true));
 
         self.poll_input_element(sender.clone(), running.clone());
-        if let FunctionCreator::User = self.stream_source.get_fn_creator() {
+        if self.stream_status_timer.is_some() {
             self.poll_stream_status(sender.clone(), running.clone());
+        }
+        if self.checkpoint_timer.is_some() {
             self.poll_checkpoint(sender.clone(), running.clone());
         }
 
@@ -195,6 +250,14 @@ impl Runnable for SourceRunnable {
                 self.checkpoint(checkpoint_id);
             }
 
+            if element.is_end_of_input() {
+                // notify the downstream operators to flush
+                self.next_runnable.as_mut().unwrap().run(element);
+
+                info!("{} end of input", self.stream_source.operator_fn.get_name());
+                break;
+            }
+
             self.next_runnable.as_mut().unwrap().run(element);
         }
     }
@@ -236,3 +299,51 @@ impl Runnable for SourceRunnable {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    use crate::api::element::{Element, Record};
+    use crate::api::watermark::MAX_WATERMARK;
+    use crate::channel::named_channel;
+    use crate::runtime::worker::runnable::source_runnable::SourceRunnable;
+
+    #[test]
+    pub fn end_of_input_test() {
+        // the end of the inputs from 2 parent tasks at different time
+        let elements = vec![
+            Element::Record(Record::new()),
+            Element::new_stream_status(1000, true),
+            Element::Record(Record::new()),
+            Element::new_stream_status(2000, true),
+        ];
+
+        let (sender, receiver) = named_channel("end_of_input_test", vec![], 100);
+        let running = Arc::new(AtomicBool::new(true));
+        SourceRunnable::poll_input_element0(
+            Box::new(elements.into_iter()),
+            sender,
+            running.clone(),
+            2,
+        )
+        .unwrap();
+        assert!(!running.load(Ordering::Relaxed));
+
+        let mut received = Vec::new();
+        while let Ok(element) = receiver.recv() {
+            received.push(element);
+        }
+
+        // the source ends once, after the records of all the inputs
+        assert_eq!(received.len(), 3);
+        assert!(received[0].is_record());
+        assert!(received[1].is_record());
+        assert!(received[2].is_end_of_input());
+        assert_eq!(
+            received[2].as_stream_status().timestamp,
+            MAX_WATERMARK.timestamp
+        );
+    }
+}
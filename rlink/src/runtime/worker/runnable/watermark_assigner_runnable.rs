@@ -98,15 +98,15 @@ impl Runnable for WatermarkAssignerRunnable {
         } else if element.is_stream_status() {
             let stream_status = element.as_stream_status();
             if stream_status.end {
-                self.next_runnable
-                    .as_mut()
-                    .unwrap()
-                    .run(Element::new_watermark(
-                        self.task_number,
-                        self.num_tasks,
-                        MAX_WATERMARK.timestamp,
-                        stream_status,
-                    ));
+                // fire all the event time windows and timers before the end of the input
+                let watermark_ele = Element::new_watermark(
+                    self.task_number,
+                    self.num_tasks,
+                    MAX_WATERMARK.timestamp,
+                    stream_status,
+                );
+                self.next_runnable.as_mut().unwrap().run(watermark_ele);
+                self.next_runnable.as_mut().unwrap().run(element);
             } else {
                 match watermark_assigner.get_watermark(&element) {
                     Some(watermark) => {
@@ -46,7 +46,7 @@ impl Runnable for WindowAssignerRunnable {
                 let windows = self
                     .stream_window
                     .operator_fn
-                    .assign_windows(record.timestamp, WindowAssignerContext {});
+                    .assign_record_windows(record, WindowAssignerContext {});
 
                 // info!(
                 //     "Create windows, trigger timestamp: {}",
@@ -1,3 +1,4 @@
+use std::collections::hash_map::Keys;
 use std::collections::HashMap;
 
 use crate::api::element::Record;
@@ -38,6 +39,35 @@ impl MemoryReducingState {
 
         state
     }
+
+    pub fn keys(&self) -> Keys<Record, Record> {
+        self.kv.keys()
+    }
+
+    pub fn get(&self, key: &Record) -> Option<&Record> {
+        self.kv.get(key)
+    }
+
+    /// Insert the entries of the `other`, the existing values are replaced
+    pub fn extend(&mut self, other: MemoryReducingState) {
+        self.kv.extend(other.kv);
+    }
+
+    /// Transform the values by the `map_fun`, the keys are kept
+    pub fn map_values<F>(self, map_fun: F) -> Self
+    where
+        F: Fn(Record) -> Record,
+    {
+        let kv = self
+            .kv
+            .into_iter()
+            .map(|(key, val)| (key, map_fun(val)))
+            .collect();
+        MemoryReducingState {
+            state_key: self.state_key,
+            kv,
+        }
+    }
 }
 
 impl TReducingState for MemoryReducingState {
@@ -49,6 +79,10 @@ impl TReducingState for MemoryReducingState {
         self.kv.insert(key, val);
     }
 
+    fn remove(&mut self, key: &Record) -> Option<Record> {
+        self.kv.remove(key)
+    }
+
     fn flush(&mut self) {}
 
     fn snapshot(&mut self) {}
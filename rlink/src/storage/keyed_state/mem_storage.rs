@@ -1,3 +1,4 @@
+use dashmap::mapref::entry::Entry;
 use dashmap::DashMap;
 
 use crate::api::runtime::JobId;
@@ -28,14 +29,27 @@ pub(crate) fn append_drop_window(
     storage_key: StorageKey,
     window: Window,
     state: MemoryReducingState,
-) {
+) -> bool {
     let drop_window_states: &DashMap<StorageKey, DashMap<Window, MemoryReducingState>> =
         &*DROP_WINDOW_STATE_STORAGE;
 
     let task_storage = drop_window_states
         .entry(storage_key)
         .or_insert_with(|| DashMap::new());
-    task_storage.value().insert(window, state);
+    // the unconsumed state of a re-fired window is merged with the latest one,
+    // so the fired keys of a window are not lost before the downstream consumes them
+    // bind the result, so the entry guard is dropped before the `task_storage` guard
+    let appended = match task_storage.value().entry(window) {
+        Entry::Occupied(mut entry) => {
+            entry.get_mut().extend(state);
+            false
+        }
+        Entry::Vacant(entry) => {
+            entry.insert(state);
+            true
+        }
+    };
+    appended
 }
 
 pub(crate) fn remove_drop_window(
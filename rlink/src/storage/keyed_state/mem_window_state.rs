@@ -3,7 +3,7 @@ use std::collections::HashMap;
 
 use crate::api::element::{Barrier, Record};
 use crate::api::runtime::JobId;
-use crate::api::window::Window;
+use crate::api::window::{TimeWindow, Window};
 use crate::storage::keyed_state::mem_reducing_state::MemoryReducingState;
 use crate::storage::keyed_state::mem_storage::{append_drop_window, StorageKey};
 use crate::storage::keyed_state::{StateKey, TReducingState, TWindowState};
@@ -16,6 +16,9 @@ pub struct MemoryWindowState {
 
     windows: HashMap<Window, MemoryReducingState>,
     suggest_state_capacity: usize,
+
+    /// the windows of each key, only used by the merging windows
+    key_windows: HashMap<Record, Vec<TimeWindow>>,
 }
 
 impl MemoryWindowState {
@@ -26,30 +29,115 @@ impl MemoryWindowState {
             task_number,
             windows: HashMap::new(),
             suggest_state_capacity: 512,
+            key_windows: HashMap::new(),
         }
     }
 
-    fn merge_value<F>(&mut self, window: &Window, key: Record, record: &mut Record, reduce_fun: F)
-    where
-        F: Fn(Option<&mut Record>, &mut Record) -> Record,
+    fn merge_value<C, A>(
+        &mut self,
+        window: &Window,
+        key: Record,
+        record: &mut Record,
+        create_fun: C,
+        add_fun: A,
+    ) where
+        C: Fn() -> Record,
+        A: Fn(&mut Record, &mut Record),
     {
-        match self.windows.get_mut(window) {
-            Some(state) => {
-                let state_record = state.get_mut(&key);
-                let new_val = reduce_fun(state_record, record);
-                state.insert(key, new_val);
+        let suggest_state_capacity = self.suggest_state_capacity;
+        let job_id = self.job_id;
+        let task_number = self.task_number;
+        let state = self.windows.entry(window.clone()).or_insert_with(|| {
+            let state_key = StateKey::new(window.clone(), job_id, task_number);
+            MemoryReducingState::new(&state_key, suggest_state_capacity)
+        });
+
+        match state.get_mut(&key) {
+            Some(accumulator) => add_fun(accumulator, record),
+            None => {
+                let mut accumulator = create_fun();
+                add_fun(accumulator.borrow_mut(), record);
+                state.insert(key, accumulator);
             }
+        }
+    }
+
+    fn insert_value(&mut self, window: Window, key: Record, value: Record) {
+        match self.windows.get_mut(&window) {
+            Some(state) => state.insert(key, value),
             None => {
                 let state_key = StateKey::new(window.clone(), self.job_id, self.task_number);
                 let mut state = MemoryReducingState::new(&state_key, self.suggest_state_capacity);
+                state.insert(key, value);
 
-                let new_val = reduce_fun(None, record);
-                state.insert(key, new_val);
+                self.windows.insert(window, state);
+            }
+        }
+    }
 
-                self.windows.insert(window.clone(), state);
+    fn remove_value(&mut self, window: &Window, key: &Record) -> Option<Record> {
+        let (value, empty) = match self.windows.get_mut(window) {
+            Some(state) => {
+                let value = state.remove(key);
+                (value, state.len() == 0)
             }
+            None => (None, false),
+        };
+
+        if empty {
+            self.windows.remove(window);
+        }
+
+        value
+    }
+
+    fn remove_key_windows(&mut self, window: &Window, state: &MemoryReducingState) {
+        for key in state.keys() {
+            self.remove_key_window(window, key);
         }
     }
+
+    fn remove_key_window(&mut self, window: &Window, key: &Record) {
+        let time_window = match window {
+            Window::TimeWindow(time_window) => time_window,
+        };
+
+        let empty = match self.key_windows.get_mut(key) {
+            Some(key_windows) => {
+                key_windows.retain(|key_window| key_window != time_window);
+                key_windows.is_empty()
+            }
+            None => false,
+        };
+
+        if empty {
+            self.key_windows.remove(key);
+        }
+    }
+
+    /// emit the results of the `values` of the window to downstream
+    fn append_values<R>(
+        &self,
+        window: &Window,
+        values: Vec<(Record, Record)>,
+        result_fun: R,
+    ) -> bool
+    where
+        R: Fn(Record) -> Record,
+    {
+        if values.is_empty() {
+            return false;
+        }
+
+        let state_key = StateKey::new(window.clone(), self.job_id, self.task_number);
+        let mut state = MemoryReducingState::new(&state_key, values.len());
+        for (key, value) in values {
+            state.insert(key, result_fun(value));
+        }
+
+        let storage_key = StorageKey::new(self.job_id, self.task_number);
+        append_drop_window(storage_key, window.clone(), state)
+    }
 }
 
 impl TWindowState for MemoryWindowState {
@@ -62,35 +150,191 @@ impl TWindowState for MemoryWindowState {
         windows
     }
 
-    fn merge<F>(&mut self, key: Record, mut record: Record, reduce_fun: F)
+    fn keys(&self, window: &Window) -> Vec<Record> {
+        match self.windows.get(window) {
+            Some(state) => state.keys().cloned().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn merge<C, A>(&mut self, key: Record, mut record: Record, create_fun: C, add_fun: A)
     where
-        F: Fn(Option<&mut Record>, &mut Record) -> Record,
+        C: Fn() -> Record,
+        A: Fn(&mut Record, &mut Record),
     {
         let windows = record.get_location_windows();
 
         if windows.len() == 1 {
             let window = &windows[0].clone();
-            self.merge_value(window, key, record.borrow_mut(), reduce_fun);
+            self.merge_value(window, key, record.borrow_mut(), create_fun, add_fun);
         } else {
             for window in &windows.clone() {
-                self.merge_value(window, key.clone(), record.borrow_mut(), |value, record| {
-                    reduce_fun(value, record)
-                })
+                self.merge_value(
+                    window,
+                    key.clone(),
+                    record.borrow_mut(),
+                    &create_fun,
+                    &add_fun,
+                )
+            }
+        }
+    }
+
+    fn merge_windows<C, A, M>(
+        &mut self,
+        key: Record,
+        mut record: Record,
+        create_fun: C,
+        add_fun: A,
+        merge_fun: M,
+    ) -> Vec<(Window, Vec<Window>)>
+    where
+        C: Fn() -> Record,
+        A: Fn(&mut Record, &mut Record),
+        M: Fn(&mut Record, &mut Record),
+    {
+        let windows = record.get_location_windows().clone();
+        let mut merged_windows = Vec::with_capacity(windows.len());
+        for window in windows {
+            let time_window = match window {
+                Window::TimeWindow(time_window) => time_window,
+            };
+
+            // the windows of a key never intersect each other,
+            // so a single pass is enough to find the covering window
+            let key_windows = self.key_windows.entry(key.clone()).or_default();
+            let mut merged_window = time_window.clone();
+            let mut intersecting_windows = Vec::new();
+            key_windows.retain(|key_window| {
+                if key_window.intersects(time_window.clone()) {
+                    merged_window = merged_window.cover(key_window.clone());
+                    intersecting_windows.push(key_window.clone());
+                    false
+                } else {
+                    true
+                }
+            });
+            key_windows.push(merged_window.clone());
+
+            let merged_window = Window::TimeWindow(merged_window);
+            let intersecting_windows: Vec<Window> = intersecting_windows
+                .into_iter()
+                .map(Window::TimeWindow)
+                .collect();
+
+            let mut merged_value: Option<Record> = None;
+            for intersecting_window in &intersecting_windows {
+                let value = self.remove_value(intersecting_window, &key);
+                merged_value = match (merged_value, value) {
+                    (Some(mut merged_value), Some(mut value)) => {
+                        merge_fun(merged_value.borrow_mut(), value.borrow_mut());
+                        Some(merged_value)
+                    }
+                    (None, value) => value,
+                    (merged_value, None) => merged_value,
+                };
+            }
+
+            let mut accumulator = merged_value.unwrap_or_else(&create_fun);
+            add_fun(accumulator.borrow_mut(), record.borrow_mut());
+            self.insert_value(merged_window.clone(), key.clone(), accumulator);
+
+            // the covering window itself is not merged, if the record falls into it
+            let merged = intersecting_windows
+                .into_iter()
+                .filter(|intersecting_window| *intersecting_window != merged_window)
+                .collect();
+            merged_windows.push((merged_window, merged));
+        }
+
+        merged_windows
+    }
+
+    fn fire_window<R>(&mut self, window: &Window, result_fun: R) -> bool
+    where
+        R: Fn(Record) -> Record,
+    {
+        match self.windows.get(window) {
+            Some(state) => {
+                let state = state.clone().map_values(result_fun);
+                let state_key = StorageKey::new(self.job_id, self.task_number);
+                append_drop_window(state_key, window.clone(), state)
             }
+            None => false,
         }
     }
 
-    fn drop_window(&mut self, window: &Window) {
+    fn drop_window<R>(&mut self, window: &Window, result_fun: R) -> bool
+    where
+        R: Fn(Record) -> Record,
+    {
         match self.windows.remove(&window) {
             Some(state) => {
+                if !self.key_windows.is_empty() {
+                    self.remove_key_windows(window, &state);
+                }
+
                 let len = state.len() as f32;
                 self.suggest_state_capacity = (len * 1.1f32) as usize;
 
+                let state = state.map_values(result_fun);
                 let state_key = StorageKey::new(self.job_id, self.task_number);
-                append_drop_window(state_key, window.clone(), state);
+                append_drop_window(state_key, window.clone(), state)
             }
-            None => {}
+            None => false,
+        }
+    }
+
+    fn purge_window(&mut self, window: &Window) -> bool {
+        match self.windows.remove(&window) {
+            Some(state) => {
+                if !self.key_windows.is_empty() {
+                    self.remove_key_windows(window, &state);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn fire_keys<R>(&mut self, window: &Window, keys: &[Record], result_fun: R) -> bool
+    where
+        R: Fn(Record) -> Record,
+    {
+        let values = match self.windows.get(window) {
+            Some(state) => keys
+                .iter()
+                .filter_map(|key| state.get(key).map(|value| (key.clone(), value.clone())))
+                .collect(),
+            None => return false,
         };
+
+        self.append_values(window, values, result_fun)
+    }
+
+    fn drop_keys<R>(&mut self, window: &Window, keys: &[Record], result_fun: R) -> bool
+    where
+        R: Fn(Record) -> Record,
+    {
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            if let Some(value) = self.remove_value(window, key) {
+                if !self.key_windows.is_empty() {
+                    self.remove_key_window(window, key);
+                }
+                values.push((key.clone(), value));
+            }
+        }
+
+        self.append_values(window, values, result_fun)
+    }
+
+    fn purge_keys(&mut self, window: &Window, keys: &[Record]) {
+        for key in keys {
+            if self.remove_value(window, key).is_some() && !self.key_windows.is_empty() {
+                self.remove_key_window(window, key);
+            }
+        }
     }
 
     fn snapshot(&mut self, _barrier: Barrier) {}
@@ -98,6 +342,97 @@ impl TWindowState for MemoryWindowState {
 
 #[cfg(test)]
 mod tests {
+    use crate::api::element::{types, Record};
+    use crate::api::runtime::JobId;
+    use crate::api::window::{TimeWindow, Window};
+    use crate::storage::keyed_state::mem_storage::remove_drop_window;
+    use crate::storage::keyed_state::mem_window_state::MemoryWindowState;
+    use crate::storage::keyed_state::{TReducingState, TWindowState};
+
+    const ACC_TYPES: [u8; 2] = [types::I64, types::I64];
+
+    fn new_record(values: &[i64]) -> Record {
+        let data_types = vec![types::I64; values.len()];
+        let mut record = Record::new();
+        let mut writer = record.get_writer(data_types.as_slice());
+        for value in values {
+            writer.set_i64(*value).unwrap();
+        }
+        record
+    }
+
+    fn fire_result(job_id: JobId, window: &Window) -> i64 {
+        let state = remove_drop_window(job_id, 0, window.clone()).unwrap();
+        let mut record = state.iter().next().unwrap();
+        let mut reader = record.get_reader(&[types::I64]);
+        reader.get_i64(0).unwrap()
+    }
+
+    #[test]
+    pub fn aggregate_fire_window_test() {
+        let job_id = JobId(100);
+        let window = Window::TimeWindow(TimeWindow::new(0, 10));
+        let mut state = MemoryWindowState::new("app".to_string(), job_id, 0);
+
+        // the accumulator is `[sum, count]`, the result is the average
+        let create_fun = || new_record(&[0, 0]);
+        let add_fun = |acc: &mut Record, record: &mut Record| {
+            let value = record.get_reader(&[types::I64]).get_i64(0).unwrap();
+            let mut reader = acc.get_reader(&ACC_TYPES);
+            let sum = reader.get_i64(0).unwrap() + value;
+            let count = reader.get_i64(1).unwrap() + 1;
+            *acc = new_record(&[sum, count]);
+        };
+        let result_fun = |mut acc: Record| {
+            let mut reader = acc.get_reader(&ACC_TYPES);
+            new_record(&[reader.get_i64(0).unwrap() / reader.get_i64(1).unwrap()])
+        };
+
+        for value in &[2, 4, 9] {
+            let mut record = new_record(&[*value]);
+            record.set_location_windows(vec![window.clone()]);
+            state.merge(Record::with_capacity(0), record, &create_fun, &add_fun);
+        }
+        assert!(state.fire_window(&window, &result_fun));
+        assert_eq!(fire_result(job_id, &window), 5);
+
+        // the accumulator is kept after firing
+        let mut record = new_record(&[10]);
+        record.set_location_windows(vec![window.clone()]);
+        state.merge(Record::with_capacity(0), record, &create_fun, &add_fun);
+        assert!(state.drop_window(&window, &result_fun));
+        assert_eq!(fire_result(job_id, &window), 6);
+        assert!(state.windows().is_empty());
+    }
+
+    #[test]
+    pub fn fire_keys_test() {
+        let job_id = JobId(101);
+        let window = Window::TimeWindow(TimeWindow::new(0, 10));
+        let mut state = MemoryWindowState::new("app".to_string(), job_id, 0);
+
+        let create_fun = || new_record(&[0]);
+        let add_fun = |acc: &mut Record, record: &mut Record| {
+            let value = record.get_reader(&[types::I64]).get_i64(0).unwrap();
+            let sum = acc.get_reader(&[types::I64]).get_i64(0).unwrap() + value;
+            *acc = new_record(&[sum]);
+        };
+        for (key, value) in &[(1, 2), (2, 4), (1, 9)] {
+            let mut record = new_record(&[*value]);
+            record.set_location_windows(vec![window.clone()]);
+            state.merge(new_record(&[*key]), record, &create_fun, &add_fun);
+        }
+
+        // the unconsumed fired keys are merged into a single notification
+        assert!(state.fire_keys(&window, &[new_record(&[1])], |acc| acc));
+        assert!(!state.drop_keys(&window, &[new_record(&[2])], |acc| acc));
+        let fired = remove_drop_window(job_id, 0, window.clone()).unwrap();
+        assert_eq!(fired.len(), 2);
+
+        assert_eq!(state.keys(&window), vec![new_record(&[1])]);
+        state.purge_keys(&window, &[new_record(&[1])]);
+        assert!(state.windows().is_empty());
+    }
 
     #[test]
     pub fn dash_map_test() {
@@ -52,6 +52,7 @@ impl Iterator for StateIterator {
 pub trait TReducingState: Debug {
     fn get_mut(&mut self, key: &Record) -> Option<&mut Record>;
     fn insert(&mut self, key: Record, val: Record);
+    fn remove(&mut self, key: &Record) -> Option<Record>;
     fn flush(&mut self);
     fn snapshot(&mut self);
     fn close(self);
@@ -87,6 +88,12 @@ impl TReducingState for ReducingState {
         }
     }
 
+    fn remove(&mut self, key: &Record) -> Option<Record> {
+        match self {
+            ReducingState::MemoryReducingState(state) => state.remove(key),
+        }
+    }
+
     fn flush(&mut self) {
         match self {
             ReducingState::MemoryReducingState(state) => state.flush(),
@@ -127,11 +134,65 @@ impl TReducingState for ReducingState {
 pub trait TWindowState: Debug {
     fn windows(&self) -> Vec<Window>;
 
-    fn merge<F>(&mut self, key: Record, record: Record, reduce_fun: F)
+    /// the keys that have an accumulator in the window
+    fn keys(&self, window: &Window) -> Vec<Record>;
+
+    /// Add the `record` into the accumulators of the `key` in the windows of the `record`,
+    /// the accumulator is created by the `create_fun` when the `key` is absent in the window.
+    fn merge<C, A>(&mut self, key: Record, record: Record, create_fun: C, add_fun: A)
+    where
+        C: Fn() -> Record,
+        A: Fn(&mut Record, &mut Record);
+
+    /// Add the `record` into the windows of the `key`, the intersecting windows of the `key`
+    /// are folded into a covering window, and their accumulators are merged by the `merge_fun`.
+    /// Returns the covering windows that the `record` is added into,
+    /// each with the former windows of the `key` that are merged into it.
+    fn merge_windows<C, A, M>(
+        &mut self,
+        key: Record,
+        record: Record,
+        create_fun: C,
+        add_fun: A,
+        merge_fun: M,
+    ) -> Vec<(Window, Vec<Window>)>
     where
-        F: Fn(Option<&mut Record>, &mut Record) -> Record;
+        C: Fn() -> Record,
+        A: Fn(&mut Record, &mut Record),
+        M: Fn(&mut Record, &mut Record);
 
-    fn drop_window(&mut self, window: &Window);
+    /// Emit the results of the window's accumulators to downstream and keep the window,
+    /// the results are computed by the `result_fun`.
+    /// Return `false` if the window is not found or the previous emitted state is not consumed,
+    /// in that case the pending state is replaced and no more notification is required.
+    fn fire_window<R>(&mut self, window: &Window, result_fun: R) -> bool
+    where
+        R: Fn(Record) -> Record;
+
+    /// Emit the results of the window's accumulators to downstream and remove the window.
+    /// The return value has the same meaning as `fire_window`.
+    fn drop_window<R>(&mut self, window: &Window, result_fun: R) -> bool
+    where
+        R: Fn(Record) -> Record;
+
+    /// Remove the window without emitting.
+    fn purge_window(&mut self, window: &Window) -> bool;
+
+    /// Emit the results of the accumulators of the `keys` in the window and keep them.
+    /// The return value has the same meaning as `fire_window`.
+    fn fire_keys<R>(&mut self, window: &Window, keys: &[Record], result_fun: R) -> bool
+    where
+        R: Fn(Record) -> Record;
+
+    /// Emit the results of the accumulators of the `keys` in the window and remove them,
+    /// the window is removed once it has no key left.
+    /// The return value has the same meaning as `fire_window`.
+    fn drop_keys<R>(&mut self, window: &Window, keys: &[Record], result_fun: R) -> bool
+    where
+        R: Fn(Record) -> Record;
+
+    /// Remove the accumulators of the `keys` in the window without emitting.
+    fn purge_keys(&mut self, window: &Window, keys: &[Record]);
 
     fn snapshot(&mut self, barrier: Barrier);
 }
@@ -165,18 +226,87 @@ impl TWindowState for WindowState {
         }
     }
 
-    fn merge<F>(&mut self, key: Record, record: Record, reduce_fun: F)
+    fn keys(&self, window: &Window) -> Vec<Record> {
+        match self {
+            WindowState::MemoryWindowState(state) => state.keys(window),
+        }
+    }
+
+    fn merge<C, A>(&mut self, key: Record, record: Record, create_fun: C, add_fun: A)
+    where
+        C: Fn() -> Record,
+        A: Fn(&mut Record, &mut Record),
+    {
+        match self {
+            WindowState::MemoryWindowState(state) => state.merge(key, record, create_fun, add_fun),
+        }
+    }
+
+    fn merge_windows<C, A, M>(
+        &mut self,
+        key: Record,
+        record: Record,
+        create_fun: C,
+        add_fun: A,
+        merge_fun: M,
+    ) -> Vec<(Window, Vec<Window>)>
+    where
+        C: Fn() -> Record,
+        A: Fn(&mut Record, &mut Record),
+        M: Fn(&mut Record, &mut Record),
+    {
+        match self {
+            WindowState::MemoryWindowState(state) => {
+                state.merge_windows(key, record, create_fun, add_fun, merge_fun)
+            }
+        }
+    }
+
+    fn fire_window<R>(&mut self, window: &Window, result_fun: R) -> bool
+    where
+        R: Fn(Record) -> Record,
+    {
+        match self {
+            WindowState::MemoryWindowState(state) => state.fire_window(window, result_fun),
+        }
+    }
+
+    fn drop_window<R>(&mut self, window: &Window, result_fun: R) -> bool
+    where
+        R: Fn(Record) -> Record,
+    {
+        match self {
+            WindowState::MemoryWindowState(state) => state.drop_window(window, result_fun),
+        }
+    }
+
+    fn purge_window(&mut self, window: &Window) -> bool {
+        match self {
+            WindowState::MemoryWindowState(state) => state.purge_window(window),
+        }
+    }
+
+    fn fire_keys<R>(&mut self, window: &Window, keys: &[Record], result_fun: R) -> bool
+    where
+        R: Fn(Record) -> Record,
+    {
+        match self {
+            WindowState::MemoryWindowState(state) => state.fire_keys(window, keys, result_fun),
+        }
+    }
+
+    fn drop_keys<R>(&mut self, window: &Window, keys: &[Record], result_fun: R) -> bool
     where
-        F: Fn(Option<&mut Record>, &mut Record) -> Record,
+        R: Fn(Record) -> Record,
     {
         match self {
-            WindowState::MemoryWindowState(state) => state.merge(key, record, reduce_fun),
+            WindowState::MemoryWindowState(state) => state.drop_keys(window, keys, result_fun),
         }
     }
 
-    fn drop_window(&mut self, window: &Window) {
+    fn purge_keys(&mut self, window: &Window, keys: &[Record]) {
         match self {
-            WindowState::MemoryWindowState(state) => state.drop_window(window),
+            WindowState::MemoryWindowState(state) => state.purge_keys(window, keys),
         }
     }
 
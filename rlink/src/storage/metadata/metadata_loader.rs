@@ -25,24 +25,9 @@ impl MetadataLoader {
     }
 
     pub fn get_application_descriptor(&mut self) -> ApplicationDescriptor {
-        let url = format!("{}/metadata", self.coordinator_address);
         loop {
-            match get_sync(url.as_str()) {
-                Ok(resp) => {
-                    let resp_model: StdResponse<ApplicationDescriptor> =
-                        serde_json::from_str(resp.as_str()).unwrap();
-                    let StdResponse { code, data } = resp_model;
-                    if code != ResponseCode::OK || data.is_none() {
-                        panic!(
-                            "get remote JobDescriptor with error code: ".to_owned() + resp.as_str()
-                        );
-                    }
-
-                    let application_descriptor = data.unwrap();
-                    self.application_descriptor_cache = Some(application_descriptor.clone());
-
-                    return application_descriptor;
-                }
+            match self.try_get_application_descriptor() {
+                Ok(application_descriptor) => return application_descriptor,
                 Err(e) => {
                     error!("get metadata(`JobDescriptor`) error. {}", e);
                     std::thread::sleep(std::time::Duration::from_secs(2));
@@ -50,4 +35,22 @@ impl MetadataLoader {
             }
         }
     }
+
+    /// get the `ApplicationDescriptor` from the coordinator once,
+    /// the caller decides how long to retry, eg: the coordinator may have exited
+    pub fn try_get_application_descriptor(&mut self) -> anyhow::Result<ApplicationDescriptor> {
+        let url = format!("{}/metadata", self.coordinator_address);
+        let resp = get_sync(url.as_str()).map_err(|e| anyhow!(e))?;
+
+        let resp_model: StdResponse<ApplicationDescriptor> = serde_json::from_str(resp.as_str())?;
+        let StdResponse { code, data } = resp_model;
+        if code != ResponseCode::OK || data.is_none() {
+            panic!("get remote JobDescriptor with error code: ".to_owned() + resp.as_str());
+        }
+
+        let application_descriptor = data.unwrap();
+        self.application_descriptor_cache = Some(application_descriptor.clone());
+
+        Ok(application_descriptor)
+    }
 }
@@ -20,3 +20,15 @@ pub fn get_runtime() -> tokio::runtime::Runtime {
     //     .unwrap()
     tokio::runtime::Runtime::new().unwrap()
 }
+
+lazy_static! {
+    /// the runtime shared by the async operators of all the tasks in the process,
+    /// it is never dropped, so only the `Handle` is kept
+    static ref ASYNC_RUNTIME_HANDLE: tokio::runtime::Handle =
+        Box::leak(Box::new(get_runtime())).handle().clone();
+}
+
+/// the handle of the process-wide tokio runtime, eg: the lookups of the `AsyncFunction`
+pub(crate) fn get_async_runtime_handle() -> tokio::runtime::Handle {
+    ASYNC_RUNTIME_HANDLE.clone()
+}